@@ -2,7 +2,7 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use turkish_tokenizer::TurkishTokenizer;
 
 fn tokenizer_benchmark(c: &mut Criterion) {
-    let tokenizer = TurkishTokenizer::new_rust().unwrap();
+    let tokenizer = TurkishTokenizer::new().unwrap();
     
     let test_texts = vec![
         "merhaba dünya",
@@ -21,6 +21,17 @@ fn tokenizer_benchmark(c: &mut Criterion) {
         })
     });
     
+    c.bench_function("count_tokens_medium_text", |b| {
+        b.iter(|| {
+            tokenizer.count_tokens(black_box("Türkçe tokenizer performans testi çok önemlidir"))
+        })
+    });
+    
+    let corpus: Vec<&str> = test_texts.iter().cycle().take(1000).copied().collect();
+    c.bench_function("encode_batch_1000", |b| {
+        b.iter(|| tokenizer.encode_batch(black_box(&corpus)))
+    });
+    
     c.bench_function("tokenize_various_texts", |b| {
         b.iter(|| {
             for text in &test_texts {
@@ -30,5 +41,40 @@ fn tokenizer_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, tokenizer_benchmark);
+/// Prefix matching backends; run once with and once without `--features fst`
+/// to compare them
+fn matcher_benchmark(c: &mut Criterion) {
+    let backend = if cfg!(feature = "fst") { "fst" } else { "trie" };
+    let mut group = c.benchmark_group(format!("matcher_{}", backend));
+
+    group.bench_function("load", |b| b.iter(|| TurkishTokenizer::new().unwrap()));
+
+    let tokenizer = TurkishTokenizer::new().unwrap();
+    let long_words = "çekoslovakyalılaştıramadıklarımızdanmışsınız muvaffakiyetsizleştiricileştiriveremeyebileceklerimizdenmişsinizcesine";
+    group.bench_function("encode_long_words", |b| {
+        b.iter(|| tokenizer.encode(black_box(long_words)))
+    });
+    group.finish();
+}
+
+/// Repetitive text with and without the word cache
+fn word_cache_benchmark(c: &mut Criterion) {
+    let text = "Evlerimizde oturuyoruz ve kitaplarımızı okuyoruz. ".repeat(200);
+    let mut group = c.benchmark_group("word_cache");
+    for capacity in [0, turkish_tokenizer::DEFAULT_WORD_CACHE_CAPACITY] {
+        let tokenizer = TurkishTokenizer::builder()
+            .word_cache_capacity(capacity)
+            .build()
+            .unwrap();
+        group.bench_function(format!("encode_capacity_{}", capacity), |b| {
+            b.iter(|| tokenizer.encode(black_box(&text)))
+        });
+        group.bench_function(format!("tokenize_capacity_{}", capacity), |b| {
+            b.iter(|| tokenizer.tokenize_text(black_box(&text)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tokenizer_benchmark, matcher_benchmark, word_cache_benchmark);
 criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! Precompiles the embedded vocabulary JSON into bincode so constructing a
+//! tokenizer does not parse JSON at runtime.
+
+#[allow(dead_code)]
+#[path = "src/vocab_tables.rs"]
+mod vocab_tables;
+
+use std::{env, fs, path::Path};
+
+/// Compile the tables in `vocab_dir` to `OUT_DIR/{out_file}`
+fn embed_vocab(vocab_dir: &Path, out_file: &str) {
+    let read = |file: &str| {
+        let path = vocab_dir.join(file);
+        println!("cargo:rerun-if-changed={}", path.display());
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e))
+    };
+
+    let tables = vocab_tables::VocabTables::from_json(
+        &read("kokler.json"),
+        &read("ekler.json"),
+        &read("bpe_tokenler.json"),
+    )
+    .expect("embedded vocabulary is valid JSON");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join(out_file);
+    fs::write(&out, bincode::serialize(&tables).expect("vocabulary serializes"))
+        .unwrap_or_else(|e| panic!("failed to write {}: {}", out.display(), e));
+}
+
+fn main() {
+    if cfg!(feature = "vocab-base") {
+        embed_vocab(Path::new("turkish_tokenizer"), "vocab_base.bin");
+    }
+
+    #[cfg(feature = "uniffi")]
+    uniffi::generate_scaffolding("src/turkish_tokenizer.udl").expect("failed to generate UniFFI scaffolding");
+
+    #[cfg(feature = "grpc")]
+    {
+        // Fall back to the bundled protoc so building needs no system install
+        if env::var_os("PROTOC").is_none() {
+            env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().expect("no bundled protoc for this platform"));
+        }
+        tonic_prost_build::compile_protos("proto/turkish_tokenizer.proto").expect("failed to compile the gRPC protos");
+    }
+}
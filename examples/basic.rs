@@ -5,7 +5,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("======================================");
     
     // Initialize the tokenizer
-    let tokenizer = TurkishTokenizer::new_rust()?;
+    let tokenizer = TurkishTokenizer::new()?;
     
     println!("Vocabulary size: {}", tokenizer.vocab_size());
     println!("Pad token: {} (ID: {})", tokenizer.pad_token, tokenizer.pad_token_id);
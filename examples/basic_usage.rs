@@ -5,7 +5,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=======================================\n");
     
     // Initialize the tokenizer
-    let tokenizer = TurkishTokenizer::new_rust()?;
+    let tokenizer = TurkishTokenizer::new()?;
     
     println!("Tokenizer Info:");
     println!("- Vocabulary size: {}", tokenizer.vocab_size());
@@ -55,7 +55,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         match token.token_type {
             TokenType::Root => root_count += 1,
             TokenType::Suffix => suffix_count += 1,
-            TokenType::Bpe => bpe_count += 1,
+            TokenType::Bpe | TokenType::Byte => bpe_count += 1,
         }
     }
     
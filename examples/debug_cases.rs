@@ -1,7 +1,7 @@
 use turkish_tokenizer::TurkishTokenizer;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let tokenizer = TurkishTokenizer::new_rust()?;
+    let tokenizer = TurkishTokenizer::new()?;
     
     // Debug specific cases that failed
     println!("Debugging tokenization results:");
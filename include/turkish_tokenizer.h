@@ -0,0 +1,44 @@
+/* Generated with cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#ifndef TURKISH_TOKENIZER_H
+#define TURKISH_TOKENIZER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct TurkishTokenizer TurkishTokenizer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Create a tokenizer with the embedded vocabulary. Returns null on failure. */
+TurkishTokenizer *ttk_new(void);
+
+/* Release a tokenizer created by `ttk_new`. Null is ignored. */
+void ttk_free(TurkishTokenizer *tokenizer);
+
+/*
+ * Encode `text` and store the number of IDs in `out_len`. Returns null if
+ * an argument is null or `text` is not valid UTF-8. Release the IDs with
+ * `ttk_free_ids`.
+ */
+uint32_t *ttk_encode(const TurkishTokenizer *tokenizer, const char *text, size_t *out_len);
+
+/* Release IDs returned by `ttk_encode`. Null is ignored. */
+void ttk_free_ids(uint32_t *ids, size_t len);
+
+/*
+ * Decode `len` IDs back to text. Returns null if an argument is null.
+ * Release the string with `ttk_free_string`.
+ */
+char *ttk_decode(const TurkishTokenizer *tokenizer, const uint32_t *ids, size_t len);
+
+/* Release a string returned by `ttk_decode`. Null is ignored. */
+void ttk_free_string(char *text);
+
+#ifdef __cplusplus
+} /* extern "C" */
+#endif
+
+#endif /* TURKISH_TOKENIZER_H */
@@ -5,7 +5,7 @@ A high-performance Turkish language tokenizer built with Rust for fast
 morphological text processing.
 """
 
-from .turkish_tokenizer_rs import Token, TokenType, TurkishTokenizer
+from .turkish_tokenizer_rs import Token, TokenType, TurkishTokenizer, tokenize_corpus
 
-__all__ = ["TurkishTokenizer", "Token", "TokenType"]
+__all__ = ["TurkishTokenizer", "Token", "TokenType", "tokenize_corpus"]
 __version__ = "0.1.0"
@@ -0,0 +1,151 @@
+//! Whole tokens for abbreviations.
+//!
+//! "T.C.", "Prof." and "vb." are in none of the vocabulary tables, so they
+//! come out as letters and periods, and "T.C." even gets a case marker per
+//! letter. Entries of the abbreviation table are matched at the start of
+//! a word, after any opening brackets or quotes, when no letter or digit
+//! follows them, so "T.C.'nin" is "T.C." followed by the normally
+//! segmented "'nin". An entry matches as written, or with an
+//! `<uppercase>` marker when only its first letter is capitalized
+//! ("Vb." for "vb."). `tokenize_sentences` does not end a sentence at the
+//! period of an entry either.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::sentences::OPENING;
+use crate::{TokenType, TurkishTokenizer, TurkishTokenizerError, WordPiece};
+
+/// File holding the abbreviation table inside a tokenizer directory
+pub const ABBREVIATIONS_FILE: &str = "abbreviations.json";
+
+/// Abbreviations frequent in Turkish text, for `add_abbreviations` and
+/// `TurkishTokenizerBuilder::abbreviations`. The sentence splitter never
+/// ends a sentence at their period, whether or not they are in the table.
+pub const COMMON_ABBREVIATIONS: &[&str] = &[
+    "alb.", "apt.", "av.", "bkz.", "bl.", "bşk.", "cad.", "doç.", "dr.", "gen.", "gör.", "hz.", "krş.", "ltd.",
+    "mah.", "müh.", "no.", "org.", "örn.", "öğr.", "prof.", "sf.", "sn.", "sok.", "şti.", "tel.", "vb.", "vd.",
+    "vs.", "yrd.", "yy.", "T.C.", "A.Ş.", "A.B.D.", "T.B.M.M.", "K.K.T.C.", "M.Ö.", "M.S.", "s.a.", "a.g.e.",
+];
+
+impl TurkishTokenizer {
+    /// Give each abbreviation a single token. Entries already in the
+    /// vocabulary keep their ID; others get new IDs after it, so the
+    /// model's embeddings must grow to `vocab_size`. Returns how many were
+    /// new to the vocabulary.
+    pub fn add_abbreviations(&mut self, abbreviations: &[&str]) -> usize {
+        let mut added = 0;
+        for &abbreviation in abbreviations {
+            if abbreviation.is_empty() || self.abbreviations.contains_key(abbreviation) {
+                continue;
+            }
+            let id = match self.vocab.get(abbreviation) {
+                Some(&id) => id,
+                None => {
+                    added += 1;
+                    self.append_token(abbreviation, None)
+                }
+            };
+            Arc::make_mut(&mut self.abbreviations).insert(abbreviation.to_string(), id);
+        }
+        self.word_cache.clear();
+        added
+    }
+
+    /// Entries of the abbreviation table and their IDs, ordered by ID
+    pub fn abbreviations(&self) -> Vec<(String, u32)> {
+        let mut entries: Vec<(String, u32)> =
+            self.abbreviations.iter().map(|(abbreviation, &id)| (abbreviation.clone(), id)).collect();
+        entries.sort_by_key(|&(_, id)| id);
+        entries
+    }
+
+    /// Whether `word`, as written or with its first letter lowercased, is
+    /// in the abbreviation table
+    pub(crate) fn is_abbreviation(&self, word: &str) -> bool {
+        self.abbreviation_entry(&word.chars().collect::<Vec<_>>()).is_some()
+    }
+
+    /// ID of `chars` in the abbreviation table, and whether it matched
+    /// with its first letter lowercased
+    fn abbreviation_entry(&self, chars: &[char]) -> Option<(u32, bool)> {
+        let written: String = chars.iter().collect();
+        if let Some(&id) = self.abbreviations.get(&written) {
+            return Some((id, false));
+        }
+        let (&first, rest) = chars.split_first()?;
+        if !first.is_uppercase() {
+            return None;
+        }
+        let mut lowered = Vec::with_capacity(chars.len());
+        self.push_lower(first, &mut lowered);
+        lowered.extend_from_slice(rest);
+        let id = self.abbreviations.get(&lowered.iter().collect::<String>())?;
+        Some((*id, true))
+    }
+
+    /// Start, length, ID and capitalization of the longest abbreviation at
+    /// the start of `word`, after any opening brackets or quotes
+    pub(crate) fn find_abbreviation(&self, word: &[char]) -> Option<(usize, usize, u32, bool)> {
+        if self.abbreviations.is_empty() {
+            return None;
+        }
+        let start = word.iter().take_while(|c| OPENING.contains(c)).count();
+        let rest = &word[start..];
+        let run = rest.iter().take_while(|&&c| c.is_alphanumeric() || c == '.').count();
+        (1..=run)
+            .rev()
+            .filter(|&len| !rest.get(len).is_some_and(|c| c.is_alphanumeric()))
+            .find_map(|len| {
+                let (id, capitalized) = self.abbreviation_entry(&rest[..len])?;
+                Some((start, len, id, capitalized))
+            })
+    }
+
+    /// Emit an abbreviation found by `find_abbreviation` at `pos`.
+    /// `lowered` is scratch space.
+    pub(crate) fn emit_abbreviation(
+        &self,
+        abbreviation: &[char],
+        (id, capitalized): (u32, bool),
+        pos: usize,
+        lowered: &mut Vec<char>,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        lowered.clear();
+        if capitalized {
+            emit(WordPiece::Uppercase(pos));
+            self.push_lower(abbreviation[0], lowered);
+            lowered.extend_from_slice(&abbreviation[1..]);
+        } else {
+            lowered.extend_from_slice(abbreviation);
+        }
+        emit(WordPiece::Matched { id, token_type: TokenType::Root, pos, chars: lowered });
+    }
+
+    /// Re-add abbreviation table entries saved with their IDs
+    pub(crate) fn restore_abbreviations(&mut self, entries: Vec<(String, u32)>) {
+        for (abbreviation, id) in entries {
+            if !self.vocab.contains_key(&abbreviation) {
+                self.append_token(&abbreviation, Some(id));
+            }
+            Arc::make_mut(&mut self.abbreviations).insert(abbreviation, id);
+        }
+        self.word_cache.clear();
+    }
+
+    pub(crate) fn save_abbreviations(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: serde_json::Map<String, serde_json::Value> =
+            self.abbreviations().into_iter().map(|(abbreviation, id)| (abbreviation, id.into())).collect();
+        fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn load_abbreviations(&mut self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: HashMap<String, u32> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        self.restore_abbreviations(entries.into_iter().collect());
+        Ok(())
+    }
+}
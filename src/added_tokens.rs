@@ -0,0 +1,182 @@
+//! Tokens added at runtime with Hugging Face `add_tokens` /
+//! `add_special_tokens` semantics: they are split out of the raw text
+//! before words are segmented, so they always come out whole.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{Token, TokenType, TurkishTokenizer, TurkishTokenizerError};
+
+/// File holding the non-special added tokens inside a tokenizer directory
+pub const ADDED_TOKENS_FILE: &str = "added_tokens.json";
+
+/// A token matched verbatim in the input text
+#[derive(Debug, Clone)]
+pub(crate) struct AddedToken {
+    pub token: Token,
+    /// Special tokens have no word ID and are dropped by
+    /// `decode_with_options(.., true)`
+    pub special: bool,
+}
+
+impl TurkishTokenizer {
+    /// Add tokens matched whole wherever they occur in the text, even
+    /// inside words. Tokens missing from the vocabulary get new IDs after
+    /// it, so the model's embeddings must grow to `vocab_size`. Returns how
+    /// many tokens were new to the vocabulary.
+    pub fn add_tokens(&mut self, tokens: &[&str]) -> usize {
+        let mut added = 0;
+        for &token in tokens {
+            if token.is_empty() || self.added_token(token).is_some() {
+                continue;
+            }
+            let id = match self.vocab.get(token) {
+                Some(&id) => id,
+                None => {
+                    added += 1;
+                    self.append_token(token, None)
+                }
+            };
+            self.register_added_token(token, id, false);
+        }
+        added
+    }
+
+    /// Add special tokens: matched whole like `add_tokens`, without a word
+    /// ID, and skipped when decoding with `skip_special_tokens`. Tokens
+    /// missing from the vocabulary claim reserved `special_*` slots, so the
+    /// vocabulary size does not change. Returns how many tokens were new
+    /// to the vocabulary.
+    pub fn add_special_tokens(&mut self, tokens: &[&str]) -> Result<usize, TurkishTokenizerError> {
+        let mut added = 0;
+        for &token in tokens {
+            if token.is_empty() {
+                continue;
+            }
+            if !self.vocab.contains_key(token) {
+                added += 1;
+            }
+            let id = self.claim_special_token(token)?;
+            self.register_added_token(token, id, true);
+        }
+        Ok(added)
+    }
+
+    /// Tokens added with `add_tokens` or `add_special_tokens` and their IDs
+    pub fn get_added_vocab(&self) -> HashMap<String, u32> {
+        self.added_tokens
+            .iter()
+            .map(|added| (added.token.token.clone(), added.token.id))
+            .collect()
+    }
+
+    /// Tokens added with `add_special_tokens`, ordered by ID
+    pub fn additional_special_tokens(&self) -> Vec<String> {
+        let mut tokens: Vec<&Token> = self.added_tokens.iter().filter(|a| a.special).map(|a| &a.token).collect();
+        tokens.sort_by_key(|token| token.id);
+        tokens.into_iter().map(|token| token.token.clone()).collect()
+    }
+
+    /// Make `token` the BOS, EOS, padding, separator, classifier or mask
+    /// token, by its `special_tokens_map.json` key
+    pub(crate) fn assign_special_token(&mut self, role: &str, token: &str) -> Result<(), TurkishTokenizerError> {
+        if !["bos_token", "eos_token", "pad_token", "sep_token", "cls_token", "mask_token"].contains(&role) {
+            return Err(TurkishTokenizerError::InvalidConfig(format!("Unsupported special token role '{}'", role)));
+        }
+        let id = self.claim_special_token(token)?;
+        let (field, field_id) = match role {
+            "bos_token" => (&mut self.bos_token, &mut self.bos_token_id),
+            "eos_token" => (&mut self.eos_token, &mut self.eos_token_id),
+            "pad_token" => (&mut self.pad_token, &mut self.pad_token_id),
+            "sep_token" => (&mut self.sep_token, &mut self.sep_token_id),
+            "cls_token" => (&mut self.cls_token, &mut self.cls_token_id),
+            "mask_token" => (&mut self.mask_token, &mut self.mask_token_id),
+            _ => unreachable!("role checked above"),
+        };
+        *field = token.to_string();
+        *field_id = id;
+        Ok(())
+    }
+
+    /// Whether `id` belongs to a token added with `add_special_tokens`
+    pub(crate) fn is_added_special(&self, id: u32) -> bool {
+        self.added_tokens.iter().any(|added| added.special && added.token.id == id)
+    }
+
+    /// The added token `text` starts with, longest first
+    pub(crate) fn added_token_at(&self, text: &str) -> Option<&AddedToken> {
+        self.added_tokens.iter().find(|added| text.starts_with(added.token.token.as_str()))
+    }
+
+    fn added_token(&self, token: &str) -> Option<&AddedToken> {
+        self.added_tokens.iter().find(|added| added.token.token == token)
+    }
+
+    /// Give `token` a vocabulary entry of its own outside the three tables,
+    /// with `id` or the next ID after the whole vocabulary
+    pub(crate) fn append_token(&mut self, token: &str, id: Option<u32>) -> u32 {
+        let id = id.unwrap_or_else(|| self.vocab.values().max().map_or(0, |&max_id| max_id + 1));
+        Arc::make_mut(&mut self.vocab).insert(token.to_string(), id);
+        Arc::make_mut(&mut self.decoder).set_forms(id, vec![token.to_string()]);
+        id
+    }
+
+    fn register_added_token(&mut self, token: &str, id: u32, special: bool) {
+        self.added_tokens.retain(|added| added.token.token != token);
+        self.added_tokens.push(AddedToken {
+            token: Token {
+                token: token.to_string(),
+                id,
+                token_type: TokenType::Root,
+                start: 0,
+                end: 0,
+                word_id: None,
+                surface: String::new(),
+            },
+            special,
+        });
+        // Longest first, so a token never shadows a longer one it prefixes
+        self.added_tokens.sort_by_key(|added| std::cmp::Reverse(added.token.token.len()));
+        self.word_cache.clear();
+    }
+
+    pub(crate) fn save_added_tokens(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: serde_json::Map<String, serde_json::Value> =
+            self.added_token_entries().into_iter().map(|(token, id)| (token, id.into())).collect();
+        fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn load_added_tokens(&mut self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut tokens = Vec::with_capacity(entries.len());
+        for (token, id) in entries {
+            tokens.push((token, serde_json::from_value(id)?));
+        }
+        self.restore_added_tokens(tokens)
+    }
+
+    /// Non-special added tokens and their IDs, ordered by ID
+    pub(crate) fn added_token_entries(&self) -> Vec<(String, u32)> {
+        let mut tokens: Vec<&Token> = self.added_tokens.iter().filter(|a| !a.special).map(|a| &a.token).collect();
+        tokens.sort_by_key(|token| token.id);
+        tokens.into_iter().map(|token| (token.token.clone(), token.id)).collect()
+    }
+
+    /// Re-add non-special tokens saved with their IDs
+    pub(crate) fn restore_added_tokens(&mut self, tokens: Vec<(String, u32)>) -> Result<(), TurkishTokenizerError> {
+        for (token, id) in tokens {
+            let id = match self.vocab.get(&token) {
+                Some(&existing) if existing != id => {
+                    return Err(TurkishTokenizerError::ConflictingId { token, id, existing });
+                }
+                Some(_) => id,
+                None => self.append_token(&token, Some(id)),
+            };
+            self.register_added_token(&token, id, false);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+//! Word-level morphological analysis built on the tokenizer's segmentation.
+//!
+//! A word's tokens are split into a stem, everything up to the last
+//! non-suffix token, and the chain of suffixes after it. Each suffix is
+//! labelled with the earliest morphotactic slot it can fill in order, and
+//! the part of speech is guessed from the chain. The analysis is only as
+//! good as the segmentation; enabling `morphotactics` and
+//! `strict_morphology` gives cleaner suffix chains.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::morphology::{next_slot, suffix_slots, Slot};
+use crate::{Token, TokenType, TurkishTokenizer};
+
+/// Grammatical role of a suffix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+pub enum SuffixCategory {
+    /// Forms a new word: `-lık`, `-cı`, `-lı`, `-sız`
+    Derivation,
+    /// Voice, negation, tense or mood: `-ma`, `-yor`, `-dı`, `-acak`
+    Verbal,
+    Plural,
+    Possessive,
+    Case,
+    /// Copula or person ending: `-dır`, `-idi`, `-ım`
+    Copula,
+    /// Buffer letter or vowel whose role depends on its neighbours
+    Buffer,
+}
+
+/// Coarse part-of-speech guess for an analyzed word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyclass(eq, eq_int))]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+pub struct SuffixInfo {
+    /// The suffix as it appears in the word
+    pub surface: String,
+    pub id: u32,
+    pub category: SuffixCategory,
+    /// Char offsets in the analyzed text
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+pub struct MorphAnalysis {
+    /// The word as written
+    pub word: String,
+    /// Lowercased stem the suffixes attach to
+    pub root: String,
+    /// ID of the stem when it is a single root token
+    pub root_id: Option<u32>,
+    pub suffixes: Vec<SuffixInfo>,
+    pub pos_guess: PartOfSpeech,
+}
+
+/// Suffix IDs that form adjectives (`-lı`, `-sız`) or nouns (`-lık`, `-cı`,
+/// `-cık`)
+const ADJECTIVE_SUFFIXES: &[u32] = &[20018, 20019];
+const NOUN_SUFFIXES: &[u32] = &[20027, 20029, 20030];
+
+impl TurkishTokenizer {
+    /// Analyze one word into its stem and suffix chain
+    pub fn analyze(&self, word: &str) -> MorphAnalysis {
+        let word = word.trim();
+        self.analyze_tokens(word, &self.tokenize_text(word))
+    }
+
+    /// Analyze every space-delimited word of `text`; offsets are relative
+    /// to the whole text
+    pub fn analyze_text(&self, text: &str) -> Vec<MorphAnalysis> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut analyses = Vec::new();
+        self.for_each_word(text, |word| {
+            let surface: String = chars[word[0].start..word[word.len() - 1].end].iter().collect();
+            analyses.push(self.analyze_tokens(&surface, word));
+        });
+        analyses
+    }
+
+    /// Dictionary form of the word's root, e.g. "kitap" for "kitabı", or
+    /// `None` if no root matched
+    pub fn stem(&self, word: &str) -> Option<String> {
+        self.root_form(&self.tokenize_text(word.trim()))
+    }
+
+    /// The root of every word of `text` that has one, in order
+    pub fn stem_text(&self, text: &str) -> Vec<String> {
+        let mut stems = Vec::new();
+        self.for_each_word(text, |word| stems.extend(self.root_form(word)));
+        stems
+    }
+
+    /// Call `f` with the tokens of each word of `text`
+    fn for_each_word(&self, text: &str, mut f: impl FnMut(&[Token])) {
+        let mut word: Vec<Token> = Vec::new();
+        for token in self.tokenize_iter(text) {
+            if word.last().is_some_and(|last| last.word_id != token.word_id) {
+                f(&word);
+                word.clear();
+            }
+            if token.word_id.is_some() {
+                word.push(token);
+            }
+        }
+        if !word.is_empty() {
+            f(&word);
+        }
+    }
+
+    /// First form listed for the first root token, which is the
+    /// dictionary form when a root has several spellings
+    fn root_form(&self, tokens: &[Token]) -> Option<String> {
+        let root = tokens.iter().find(|t| t.token_type == TokenType::Root && t.start < t.end)?;
+        let form = self.decoder.forms(root.id).and_then(|forms| forms.first());
+        Some(form.map_or_else(|| root.token.clone(), String::clone))
+    }
+
+    fn analyze_tokens(&self, word: &str, tokens: &[Token]) -> MorphAnalysis {
+        let content: Vec<&Token> = tokens.iter().filter(|t| t.word_id.is_some() && t.start < t.end).collect();
+        let stem_len = content
+            .iter()
+            .rposition(|t| t.token_type != TokenType::Suffix)
+            .map_or(0, |i| i + 1);
+        let (stem, chain) = content.split_at(stem_len);
+
+        let mut slot = Slot::Stem;
+        let suffixes: Vec<SuffixInfo> = chain
+            .iter()
+            .map(|t| {
+                let category = match next_slot(slot, t.id) {
+                    Some(next) => {
+                        let category = slot_category(next, t.id, slot);
+                        slot = next;
+                        category
+                    }
+                    None => suffix_slots(t.id).first().map_or(SuffixCategory::Buffer, |&s| slot_category(s, t.id, s)),
+                };
+                SuffixInfo {
+                    surface: t.token.clone(),
+                    id: t.id,
+                    category,
+                    start: t.start,
+                    end: t.end,
+                }
+            })
+            .collect();
+
+        MorphAnalysis {
+            word: word.to_string(),
+            root: stem.iter().map(|t| t.token.as_str()).collect(),
+            root_id: match stem {
+                [t] if t.token_type == TokenType::Root => Some(t.id),
+                _ => None,
+            },
+            pos_guess: guess_pos(&suffixes),
+            suffixes,
+        }
+    }
+}
+
+/// Category of suffix `id` that moved the word from `from` to `slot`;
+/// suffixes that leave the slot unchanged only when they have no slot of
+/// their own are buffers
+fn slot_category(slot: Slot, id: u32, from: Slot) -> SuffixCategory {
+    if suffix_slots(id).is_empty() && slot == from {
+        return SuffixCategory::Buffer;
+    }
+    match slot {
+        // `-ki` turns the word into a new stem
+        Slot::Start | Slot::Stem => SuffixCategory::Derivation,
+        Slot::Derivation => SuffixCategory::Derivation,
+        Slot::Verbal => SuffixCategory::Verbal,
+        Slot::Plural => SuffixCategory::Plural,
+        Slot::Possessive => SuffixCategory::Possessive,
+        Slot::Case => SuffixCategory::Case,
+        Slot::Copula => SuffixCategory::Copula,
+    }
+}
+
+/// Verbs end in verbal or person suffixes after a verbal one; nominal
+/// inflection marks nouns, and the last derivational suffix decides
+/// otherwise
+fn guess_pos(suffixes: &[SuffixInfo]) -> PartOfSpeech {
+    let inflection = suffixes.iter().rev().find(|s| s.category != SuffixCategory::Buffer);
+    let has_verbal = suffixes.iter().any(|s| s.category == SuffixCategory::Verbal);
+    match inflection.map(|s| s.category) {
+        Some(SuffixCategory::Verbal) => PartOfSpeech::Verb,
+        Some(SuffixCategory::Copula) if has_verbal => PartOfSpeech::Verb,
+        Some(SuffixCategory::Plural | SuffixCategory::Possessive | SuffixCategory::Case | SuffixCategory::Copula) => {
+            PartOfSpeech::Noun
+        }
+        Some(SuffixCategory::Derivation) => {
+            let id = inflection.map_or(0, |s| s.id);
+            if ADJECTIVE_SUFFIXES.contains(&id) {
+                PartOfSpeech::Adjective
+            } else if NOUN_SUFFIXES.contains(&id) {
+                PartOfSpeech::Noun
+            } else {
+                PartOfSpeech::Unknown
+            }
+        }
+        _ => PartOfSpeech::Unknown,
+    }
+}
@@ -0,0 +1,69 @@
+//! Root and suffix matching that tolerates missing Turkish letters.
+//!
+//! Complementary to deasciification: instead of rewriting the text, the
+//! root and suffix tables are also indexed with ı, ğ, ü, ş, ö and ç folded
+//! to i, g, u, s, o and c, and a folded match is used where it is longer
+//! than the exact one. "cocuklar" then gets the IDs of "çocuk" + "lar"
+//! rather than BPE pieces. Forms sharing a folded spelling go to the
+//! lowest ID.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::deasciify::fold;
+use crate::matcher::{PrefixMatcher, VocabMatcher};
+use crate::{TokenType, TurkishTokenizer};
+
+/// Matchers over the folded root and suffix tables
+pub(crate) struct FoldedTables {
+    roots: VocabMatcher,
+    suffixes: VocabMatcher,
+}
+
+fn folded_table(table: &HashMap<String, u32>) -> HashMap<String, u32> {
+    let mut folded: HashMap<String, u32> = HashMap::with_capacity(table.len());
+    for (form, &id) in table {
+        let key: String = form.chars().map(fold).collect();
+        folded.entry(key).and_modify(|lowest| *lowest = (*lowest).min(id)).or_insert(id);
+    }
+    folded
+}
+
+impl TurkishTokenizer {
+    pub fn ascii_fold_matching(&self) -> bool {
+        self.ascii_fold_matching
+    }
+
+    /// Also match roots and suffixes typed without Turkish letters
+    /// ("cocuk" for "çocuk") when that gives a longer match. Token text
+    /// keeps the surface form; the ID is the entry's.
+    pub fn set_ascii_fold_matching(&mut self, enabled: bool) {
+        self.ascii_fold_matching = enabled;
+        self.word_cache.clear();
+    }
+
+    /// Longest root or suffix in `chars[pos..end]` longer than `min_len`
+    /// once both are folded
+    pub(crate) fn folded_match(
+        &self,
+        chars: &[char],
+        pos: usize,
+        end: usize,
+        token_type: &TokenType,
+        min_len: usize,
+    ) -> Option<(u32, usize)> {
+        let tables = self.folded_tables.get_or_init(|| {
+            Arc::new(FoldedTables {
+                roots: VocabMatcher::from_table(&folded_table(&self.roots)),
+                suffixes: VocabMatcher::from_table(&folded_table(&self.suffixes)),
+            })
+        });
+        let matcher = match token_type {
+            TokenType::Root => &tables.roots,
+            TokenType::Suffix => &tables.suffixes,
+            _ => return None,
+        };
+        let folded: Vec<char> = chars[pos..end].iter().map(|&c| fold(c)).collect();
+        matcher.longest_prefix(&folded).filter(|&(_, len)| len > min_len)
+    }
+}
@@ -0,0 +1,77 @@
+//! HTTP tokenization service; see `turkish_tokenizer::server` for the
+//! endpoints. With the `grpc` feature, `--grpc-addr` also serves the gRPC
+//! service from `turkish_tokenizer::grpc` with the same tokenizer.
+//!
+//! Usage: `serve [--addr HOST:PORT] [--grpc-addr HOST:PORT] [--pretrained DIR]`
+
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use turkish_tokenizer::{server, TurkishTokenizer};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut pretrained = None;
+    #[cfg(feature = "grpc")]
+    let mut grpc_addr = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--addr", Some(value)) => addr = value,
+            ("--pretrained", Some(value)) => pretrained = Some(value),
+            #[cfg(feature = "grpc")]
+            ("--grpc-addr", Some(value)) => grpc_addr = Some(value),
+            _ => {
+                eprintln!("usage: serve [--addr HOST:PORT] [--grpc-addr HOST:PORT] [--pretrained DIR]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let tokenizer = match pretrained {
+        Some(dir) => TurkishTokenizer::from_pretrained(dir),
+        None => TurkishTokenizer::new(),
+    };
+    let tokenizer = match tokenizer {
+        Ok(tokenizer) => Arc::new(tokenizer),
+        Err(e) => {
+            eprintln!("failed to load tokenizer: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    #[cfg(feature = "grpc")]
+    if let Some(grpc_addr) = grpc_addr {
+        let Some(listener) = bind(&grpc_addr).await else {
+            return ExitCode::FAILURE;
+        };
+        eprintln!("gRPC listening on {}", grpc_addr);
+        let tokenizer = tokenizer.clone();
+        tokio::spawn(async move {
+            if let Err(e) = turkish_tokenizer::grpc::serve(listener, tokenizer).await {
+                eprintln!("gRPC server error: {}", e);
+                std::process::exit(1);
+            }
+        });
+    }
+
+    let Some(listener) = bind(&addr).await else {
+        return ExitCode::FAILURE;
+    };
+    eprintln!("listening on {}", addr);
+    if let Err(e) = server::serve(listener, tokenizer).await {
+        eprintln!("server error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+async fn bind(addr: &str) -> Option<tokio::net::TcpListener> {
+    tokio::net::TcpListener::bind(addr)
+        .await
+        .inspect_err(|e| eprintln!("failed to bind {}: {}", addr, e))
+        .ok()
+}
@@ -0,0 +1,133 @@
+//! `tokenize-corpus`: JSONL records of `{"text": ..., "input_ids": [...]}`,
+//! one per non-blank input line, in input order.
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use rayon::prelude::*;
+use serde::Serialize;
+use turkish_tokenizer::TurkishTokenizer;
+
+/// Lines tokenized in parallel at a time, bounding memory use on large files
+const CHUNK_LINES: usize = 8192;
+
+#[derive(Args)]
+pub struct TokenizeCorpusArgs {
+    /// A file, a directory searched recursively, or a glob such as
+    /// `data/**/*.txt`
+    #[arg(long)]
+    input: String,
+    /// JSONL file to write, or `-` for stdout
+    #[arg(long)]
+    output: PathBuf,
+    /// Worker threads; defaults to one per core
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Add the configured BOS/EOS tokens to every record
+    #[arg(long)]
+    add_special_tokens: bool,
+}
+
+#[derive(Serialize)]
+pub struct Record<'a> {
+    pub text: &'a str,
+    pub input_ids: Vec<u32>,
+}
+
+/// Files named by `input`, sorted so runs are reproducible
+pub fn input_files(input: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let path = Path::new(input);
+    let mut files = Vec::new();
+    if path.is_dir() {
+        walk(path, &mut files)?;
+    } else if path.exists() {
+        files.push(path.to_path_buf());
+    } else {
+        for entry in glob::glob(input)? {
+            let entry = entry?;
+            if entry.is_file() {
+                files.push(entry);
+            }
+        }
+    }
+    if files.is_empty() {
+        return Err(format!("no input files match '{}'", input).into());
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Call `f` with the lines of `files` in order, a chunk at a time
+pub fn for_each_chunk(
+    files: &[PathBuf],
+    mut f: impl FnMut(&[String]) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    for file in files {
+        let mut lines = BufReader::new(File::open(file)?).lines();
+        loop {
+            let chunk = lines
+                .by_ref()
+                .take(CHUNK_LINES)
+                .collect::<io::Result<Vec<String>>>()
+                .map_err(|e| format!("{}: {}", file.display(), e))?;
+            if chunk.is_empty() {
+                break;
+            }
+            f(&chunk)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn tokenize_corpus(tokenizer: &TurkishTokenizer, args: TokenizeCorpusArgs) -> Result<(), Box<dyn Error>> {
+    let files = input_files(&args.input)?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads.unwrap_or(0))
+        .build()?;
+    let mut output: Box<dyn Write> = if args.output.as_os_str() == "-" {
+        Box::new(BufWriter::new(io::stdout().lock()))
+    } else {
+        Box::new(BufWriter::new(File::create(&args.output)?))
+    };
+
+    let (mut records, mut tokens) = (0usize, 0usize);
+    for_each_chunk(&files, |chunk| {
+        let encoded: Vec<(String, usize)> = pool.install(|| {
+            chunk
+                .par_iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    let input_ids = tokenizer.encode_with(line, args.add_special_tokens);
+                    let count = input_ids.len();
+                    let json = serde_json::to_string(&Record { text: line, input_ids })
+                        .expect("records serialize to JSON");
+                    (json, count)
+                })
+                .collect()
+        });
+        for (json, count) in encoded {
+            writeln!(output, "{}", json)?;
+            records += 1;
+            tokens += count;
+        }
+        Ok(())
+    })?;
+    output.flush()?;
+    eprintln!("{} records, {} tokens from {} files", records, tokens, files.len());
+    Ok(())
+}
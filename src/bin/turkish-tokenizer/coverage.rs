@@ -0,0 +1,70 @@
+//! `coverage`: how well the vocabulary covers a corpus; see
+//! `TurkishTokenizer::coverage`.
+
+use std::error::Error;
+
+use clap::Args;
+use turkish_tokenizer::{CoverageReport, TurkishTokenizer};
+
+use crate::corpus::{for_each_chunk, input_files};
+
+#[derive(Args)]
+pub struct CoverageArgs {
+    /// A file, a directory searched recursively, or a glob such as
+    /// `data/**/*.txt`
+    #[arg(long)]
+    input: String,
+    /// Worker threads; defaults to one per core
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+fn print_report(report: &CoverageReport) {
+    println!("texts            {}", report.texts);
+    println!("words            {}", report.words);
+    println!("tokens           {}", report.tokens);
+    println!("tokens per word  {:.3}", report.tokens_per_word());
+    println!(
+        "unknown tokens   {} ({:.3}%)",
+        report.unknown_tokens,
+        report.unknown_rate() * 100.0
+    );
+    println!("root tokens      {}", report.root_tokens);
+    println!("suffix tokens    {}", report.suffix_tokens);
+    println!("bpe tokens       {}", report.bpe_tokens);
+    println!("byte tokens      {}", report.byte_tokens);
+    let classes = report.top_oov_char_classes();
+    if !classes.is_empty() {
+        println!("OOV characters by class:");
+        for (class, count) in classes {
+            println!("  {:<15}{}", class, count);
+        }
+    }
+}
+
+pub fn coverage(tokenizer: &TurkishTokenizer, args: CoverageArgs) -> Result<(), Box<dyn Error>> {
+    let files = input_files(&args.input)?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads.unwrap_or(0))
+        .build()?;
+
+    let mut report = CoverageReport::default();
+    for_each_chunk(&files, |chunk| {
+        let lines = chunk.iter().map(String::as_str).filter(|line| !line.trim().is_empty());
+        report.merge(pool.install(|| tokenizer.coverage(lines)));
+        Ok(())
+    })?;
+
+    if args.json {
+        let mut json = serde_json::to_value(&report)?;
+        json["unknown_rate"] = report.unknown_rate().into();
+        json["tokens_per_word"] = report.tokens_per_word().into();
+        println!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! `encode`: stdin to stdout, one output line per input line, for use in
+//! Unix pipelines.
+
+use std::error::Error;
+use std::io::{self, BufRead, BufWriter, Write};
+
+use clap::{Args, ValueEnum};
+use turkish_tokenizer::TurkishTokenizer;
+
+use crate::corpus::Record;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Space-separated token IDs
+    Ids,
+    /// `{"text": ..., "input_ids": [...]}` records, as `tokenize-corpus` writes
+    Json,
+}
+
+#[derive(Args)]
+pub struct EncodeArgs {
+    #[arg(long, value_enum, default_value = "ids")]
+    format: Format,
+    /// Add the configured BOS/EOS tokens to every line
+    #[arg(long)]
+    add_special_tokens: bool,
+}
+
+pub fn encode(tokenizer: &TurkishTokenizer, args: EncodeArgs) -> Result<(), Box<dyn Error>> {
+    let mut input = io::stdin().lock();
+    let mut output = BufWriter::new(io::stdout().lock());
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+        let text = line.strip_suffix('\n').unwrap_or(&line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let input_ids = tokenizer.encode_with(text, args.add_special_tokens);
+        match args.format {
+            Format::Ids => {
+                for (i, id) in input_ids.iter().enumerate() {
+                    if i > 0 {
+                        output.write_all(b" ")?;
+                    }
+                    write!(output, "{}", id)?;
+                }
+            }
+            Format::Json => serde_json::to_writer(&mut output, &Record { text, input_ids })?,
+        }
+        output.write_all(b"\n")?;
+    }
+    output.flush()?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Command-line interface, built with the `cli` feature.
+
+mod corpus;
+mod coverage;
+mod encode;
+mod repl;
+mod stats;
+mod vocab;
+
+use std::error::Error;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use turkish_tokenizer::TurkishTokenizer;
+
+#[derive(Parser)]
+#[command(name = "turkish-tokenizer", version, about = "Turkish morphological tokenizer")]
+struct Cli {
+    /// Load the tokenizer from a `save_pretrained` directory instead of the
+    /// embedded vocabulary
+    #[arg(long, global = true, value_name = "DIR")]
+    pretrained: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Tokenize text files line by line into JSONL training records
+    TokenizeCorpus(corpus::TokenizeCorpusArgs),
+    /// Encode stdin line by line, writing one line of IDs per input line
+    Encode(encode::EncodeArgs),
+    /// Print a table of the tokens of each line typed, colored by token type
+    Repl(repl::ReplArgs),
+    /// Report how well the vocabulary covers a corpus
+    Coverage(coverage::CoverageArgs),
+    /// Write the token frequency histogram of a corpus as JSON
+    Stats(stats::StatsArgs),
+    /// Inspect vocabularies, e.g. `vocab diff OLD_DIR NEW_DIR`
+    Vocab(vocab::VocabArgs),
+}
+
+fn load_tokenizer(pretrained: Option<PathBuf>) -> Result<TurkishTokenizer, Box<dyn Error>> {
+    Ok(match pretrained {
+        Some(dir) => TurkishTokenizer::from_pretrained(dir)?,
+        None => TurkishTokenizer::new()?,
+    })
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    // Vocabulary commands load their own tokenizers
+    let tokenizer = || load_tokenizer(cli.pretrained);
+    match cli.command {
+        Command::TokenizeCorpus(args) => corpus::tokenize_corpus(&tokenizer()?, args),
+        Command::Encode(args) => encode::encode(&tokenizer()?, args),
+        Command::Repl(args) => repl::repl(&tokenizer()?, args),
+        Command::Coverage(args) => coverage::coverage(&tokenizer()?, args),
+        Command::Stats(args) => stats::stats(&tokenizer()?, args),
+        Command::Vocab(args) => vocab::vocab(args),
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // The reader went away, as `| head` does; not an error in a pipeline
+        Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) => {
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! `repl`: tokenize lines as they are typed and print each token with its
+//! ID, type and char offsets, colored by token type. `--html` also
+//! collects the lines into a page built with `TurkishTokenizer::visualize`.
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use turkish_tokenizer::{Token, TokenType, TurkishTokenizer};
+
+const RESET: &str = "\x1b[0m";
+const HEADERS: [&str; 5] = ["TOKEN", "ID", "TYPE", "SPAN", "WORD"];
+
+#[derive(Args)]
+pub struct ReplArgs {
+    /// Never color the table; color is also off when stdout is not a
+    /// terminal or `NO_COLOR` is set
+    #[arg(long)]
+    no_color: bool,
+    /// Also write every line's tokens to an HTML page, for reports
+    #[arg(long, value_name = "FILE")]
+    html: Option<PathBuf>,
+}
+
+fn color(token_type: &TokenType) -> &'static str {
+    match token_type {
+        TokenType::Root => "\x1b[32m",
+        TokenType::Suffix => "\x1b[36m",
+        TokenType::Bpe => "\x1b[33m",
+        TokenType::Byte => "\x1b[31m",
+    }
+}
+
+fn type_name(token_type: &TokenType) -> &'static str {
+    match token_type {
+        TokenType::Root => "root",
+        TokenType::Suffix => "suffix",
+        TokenType::Bpe => "bpe",
+        TokenType::Byte => "byte",
+    }
+}
+
+/// The token table for one line, one row per token
+fn table(tokens: &[Token], colored: bool) -> String {
+    let rows: Vec<[String; 5]> = tokens
+        .iter()
+        .map(|token| {
+            [
+                // Debug formatting quotes the token so spaces stay visible
+                format!("{:?}", token.token),
+                token.id.to_string(),
+                type_name(&token.token_type).to_string(),
+                format!("{}..{}", token.start, token.end),
+                token.word_id.map_or("-".to_string(), |id| id.to_string()),
+            ]
+        })
+        .collect();
+    let mut widths = HEADERS.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[&str]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+    let mut out = line(&HEADERS);
+    out.push('\n');
+    for (token, row) in tokens.iter().zip(&rows) {
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        if colored {
+            out.push_str(color(&token.token_type));
+            out.push_str(&line(&cells));
+            out.push_str(RESET);
+        } else {
+            out.push_str(&line(&cells));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+pub fn repl(tokenizer: &TurkishTokenizer, args: ReplArgs) -> Result<(), Box<dyn Error>> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let interactive = stdin.is_terminal();
+    let colored = !args.no_color && stdout.is_terminal() && std::env::var_os("NO_COLOR").is_none();
+
+    let mut input = stdin.lock();
+    let mut line = String::new();
+    let mut html = String::new();
+    loop {
+        if interactive {
+            write!(stdout, "> ")?;
+            stdout.flush()?;
+        }
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+        let text = line.trim_end_matches(['\n', '\r']);
+        if text.is_empty() {
+            continue;
+        }
+        let tokens = tokenizer.tokenize_text(text);
+        writeln!(stdout, "{}", table(&tokens, colored))?;
+        if args.html.is_some() {
+            html.push_str(&tokenizer.visualize(text));
+            html.push('\n');
+        }
+    }
+    if interactive {
+        writeln!(stdout)?;
+    }
+    if let Some(path) = args.html {
+        let page = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Tokenization</title></head>\n<body>\n{}</body>\n</html>\n",
+            html
+        );
+        fs::write(path, page)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! `stats`: token frequency histogram of a corpus as JSON, most frequent
+//! first; see `TurkishTokenizer::count_token_frequencies`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Serialize;
+use turkish_tokenizer::TurkishTokenizer;
+
+use crate::corpus::{for_each_chunk, input_files};
+
+#[derive(Args)]
+pub struct StatsArgs {
+    /// A file, a directory searched recursively, or a glob such as
+    /// `data/**/*.txt`
+    #[arg(long)]
+    input: String,
+    /// JSON file to write, or `-` for stdout
+    #[arg(long, default_value = "-")]
+    output: PathBuf,
+    /// Worker threads; defaults to one per core
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct TokenCount<'a> {
+    id: u32,
+    token: Option<&'a str>,
+    count: u64,
+}
+
+#[derive(Serialize)]
+struct Histogram<'a> {
+    total_tokens: u64,
+    unique_tokens: usize,
+    tokens: Vec<TokenCount<'a>>,
+}
+
+pub fn stats(tokenizer: &TurkishTokenizer, args: StatsArgs) -> Result<(), Box<dyn Error>> {
+    let files = input_files(&args.input)?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads.unwrap_or(0))
+        .build()?;
+
+    let mut counts: HashMap<u32, u64> = HashMap::new();
+    for_each_chunk(&files, |chunk| {
+        let lines = chunk.iter().map(String::as_str).filter(|line| !line.trim().is_empty());
+        for (id, count) in pool.install(|| tokenizer.count_token_frequencies(lines)) {
+            *counts.entry(id).or_default() += count;
+        }
+        Ok(())
+    })?;
+
+    let mut tokens: Vec<TokenCount> = counts
+        .into_iter()
+        .map(|(id, count)| TokenCount {
+            id,
+            token: tokenizer.id_to_token(id),
+            count,
+        })
+        .collect();
+    tokens.sort_by(|a, b| b.count.cmp(&a.count).then(a.id.cmp(&b.id)));
+    let histogram = Histogram {
+        total_tokens: tokens.iter().map(|token| token.count).sum(),
+        unique_tokens: tokens.len(),
+        tokens,
+    };
+
+    let mut output: Box<dyn Write> = if args.output.as_os_str() == "-" {
+        Box::new(BufWriter::new(io::stdout().lock()))
+    } else {
+        Box::new(BufWriter::new(File::create(&args.output)?))
+    };
+    serde_json::to_writer_pretty(&mut output, &histogram)?;
+    writeln!(output)?;
+    output.flush()?;
+    Ok(())
+}
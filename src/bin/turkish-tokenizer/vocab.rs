@@ -0,0 +1,77 @@
+//! `vocab diff`: tokens added, removed and moved between two
+//! `save_pretrained` directories; see `VocabInfo::diff`.
+
+use std::error::Error;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use turkish_tokenizer::TurkishTokenizer;
+
+#[derive(Args)]
+pub struct VocabArgs {
+    #[command(subcommand)]
+    command: VocabCommand,
+}
+
+#[derive(Subcommand)]
+enum VocabCommand {
+    /// Compare the vocabularies of two pretrained directories
+    Diff(DiffArgs),
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Directory of the tokenizer the model was trained with
+    old: PathBuf,
+    /// Directory of the updated tokenizer
+    new: PathBuf,
+    /// Write the differences as JSON
+    #[arg(long)]
+    json: bool,
+    /// Fail if any old token was removed or got a new ID
+    #[arg(long)]
+    check: bool,
+}
+
+pub fn vocab(args: VocabArgs) -> Result<(), Box<dyn Error>> {
+    match args.command {
+        VocabCommand::Diff(args) => diff(args),
+    }
+}
+
+fn diff(args: DiffArgs) -> Result<(), Box<dyn Error>> {
+    let old = TurkishTokenizer::from_pretrained(&args.old)?.vocab_info();
+    let new = TurkishTokenizer::from_pretrained(&args.new)?.vocab_info();
+    let diff = old.diff(&new);
+
+    let mut output = BufWriter::new(io::stdout().lock());
+    if args.json {
+        serde_json::to_writer_pretty(&mut output, &diff)?;
+        writeln!(output)?;
+    } else {
+        for (token, id) in &diff.added {
+            writeln!(output, "+ {} {:?}", id, token)?;
+        }
+        for (token, id) in &diff.removed {
+            writeln!(output, "- {} {:?}", id, token)?;
+        }
+        for changed in &diff.changed {
+            writeln!(output, "~ {} -> {} {:?}", changed.old_id, changed.new_id, changed.token)?;
+        }
+        writeln!(
+            output,
+            "{} added, {} removed, {} re-IDed; {}",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len(),
+            if diff.is_compatible() { "compatible" } else { "NOT compatible" }
+        )?;
+    }
+    output.flush()?;
+
+    if args.check && !diff.is_compatible() {
+        return Err("the new vocabulary removes or re-IDs tokens of the old one".into());
+    }
+    Ok(())
+}
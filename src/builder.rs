@@ -0,0 +1,371 @@
+use crate::{COMMON_ABBREVIATIONS, COMMON_EMOJI, CaseMapping, ChatTemplate, ControlCharMode, EntityKind, EntityMode, EntityModes, ForeignWordMode, NumberMode, PostProcessor, SegmentationMode, TurkishTokenizer, TurkishTokenizerError, UnknownStrategy, WhitespaceMode, DEFAULT_WORD_CACHE_CAPACITY};
+
+/// Configures a [`TurkishTokenizer`] before construction.
+///
+/// ```
+/// use turkish_tokenizer::{TurkishTokenizer, UnknownStrategy};
+///
+/// let tokenizer = TurkishTokenizer::builder()
+///     .split_camel_case(false)
+///     .unknown_strategy(UnknownStrategy::Unknown)
+///     .bos_token("<s>")
+///     .build()
+///     .unwrap();
+/// assert_eq!(tokenizer.bos_token, "<s>");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TurkishTokenizerBuilder {
+    split_camel_case: bool,
+    split_punctuation: bool,
+    join_line_breaks: bool,
+    split_compounds: bool,
+    lowercase: bool,
+    case_mapping: CaseMapping,
+    strict_morphology: bool,
+    morphotactics: bool,
+    consonant_alternation: bool,
+    segmentation: SegmentationMode,
+    emit_case_markers: bool,
+    all_caps_markers: bool,
+    clitics: bool,
+    unknown_strategy: UnknownStrategy,
+    number_mode: NumberMode,
+    whitespace_mode: WhitespaceMode,
+    control_chars: ControlCharMode,
+    entity_modes: EntityModes,
+    foreign_word_mode: ForeignWordMode,
+    restore_diacritics: bool,
+    ascii_fold_matching: bool,
+    word_cache_capacity: usize,
+    model_max_length: Option<usize>,
+    pad_token: Option<String>,
+    eos_token: Option<String>,
+    bos_token: Option<String>,
+    sep_token: Option<String>,
+    cls_token: Option<String>,
+    mask_token: Option<String>,
+    post_processor: PostProcessor,
+    chat_template: Option<ChatTemplate>,
+    emoji_tokens: bool,
+    abbreviations: bool,
+}
+
+impl Default for TurkishTokenizerBuilder {
+    fn default() -> Self {
+        TurkishTokenizerBuilder {
+            split_camel_case: true,
+            split_punctuation: false,
+            join_line_breaks: false,
+            split_compounds: false,
+            lowercase: true,
+            case_mapping: CaseMapping::default(),
+            strict_morphology: false,
+            morphotactics: false,
+            consonant_alternation: false,
+            segmentation: SegmentationMode::default(),
+            emit_case_markers: true,
+            all_caps_markers: false,
+            clitics: false,
+            unknown_strategy: UnknownStrategy::default(),
+            number_mode: NumberMode::default(),
+            whitespace_mode: WhitespaceMode::default(),
+            control_chars: ControlCharMode::default(),
+            entity_modes: EntityModes::default(),
+            foreign_word_mode: ForeignWordMode::Off,
+            restore_diacritics: false,
+            ascii_fold_matching: false,
+            word_cache_capacity: DEFAULT_WORD_CACHE_CAPACITY,
+            model_max_length: None,
+            pad_token: None,
+            eos_token: None,
+            bos_token: None,
+            sep_token: None,
+            cls_token: None,
+            mask_token: None,
+            post_processor: PostProcessor::default(),
+            chat_template: None,
+            emoji_tokens: false,
+            abbreviations: false,
+        }
+    }
+}
+
+impl TurkishTokenizerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new segment at every uppercase letter inside a word (default `true`)
+    pub fn split_camel_case(mut self, enabled: bool) -> Self {
+        self.split_camel_case = enabled;
+        self
+    }
+
+    /// Emit each mark in [`PUNCTUATION`](crate::PUNCTUATION) as its own
+    /// token instead of letting BPE merge it with neighbouring letters
+    /// (default `false`)
+    pub fn split_punctuation(mut self, enabled: bool) -> Self {
+        self.split_punctuation = enabled;
+        self
+    }
+
+    /// Rejoin words hyphenated across lines ("kitap-\nlar") before
+    /// segmenting them (default `false`)
+    pub fn join_line_breaks(mut self, enabled: bool) -> Self {
+        self.join_line_breaks = enabled;
+        self
+    }
+
+    /// Segment the parts of hyphenated compounds ("sosyo-ekonomik") as
+    /// separate words (default `false`)
+    pub fn split_compounds(mut self, enabled: bool) -> Self {
+        self.split_compounds = enabled;
+        self
+    }
+
+    /// Lowercase segments with Turkish rules before matching (default `true`).
+    /// Cased text only matches the lowercase vocabulary through BPE or bytes.
+    pub fn lowercase(mut self, enabled: bool) -> Self {
+        self.lowercase = enabled;
+        self
+    }
+
+    /// How I and i are paired when lowercasing and when decoding case
+    /// markers (default `CaseMapping::Turkish`: İ/i and I/ı)
+    pub fn case_mapping(mut self, mapping: CaseMapping) -> Self {
+        self.case_mapping = mapping;
+        self
+    }
+
+    /// Reject suffix matches that break vowel harmony, backing off to
+    /// shorter suffixes or BPE (default `false`)
+    pub fn strict_morphology(mut self, enabled: bool) -> Self {
+        self.strict_morphology = enabled;
+        self
+    }
+
+    /// Only match suffixes after a root and in a valid order (default
+    /// `false`)
+    pub fn morphotactics(mut self, enabled: bool) -> Self {
+        self.morphotactics = enabled;
+        self
+    }
+
+    /// Match roots whose final consonant was voiced ("kitabı", "rengi") to
+    /// the dictionary form's ID (default `false`)
+    pub fn consonant_alternation(mut self, enabled: bool) -> Self {
+        self.consonant_alternation = enabled;
+        self
+    }
+
+    /// How words are split into vocabulary matches (default
+    /// `SegmentationMode::Greedy`)
+    pub fn segmentation(mut self, mode: SegmentationMode) -> Self {
+        self.segmentation = mode;
+        self
+    }
+
+    /// Emit `<uppercase>` before segments starting with a capital (default `true`)
+    pub fn emit_case_markers(mut self, enabled: bool) -> Self {
+        self.emit_case_markers = enabled;
+        self
+    }
+
+    /// Emit one `<all_caps>` before acronyms like "TBMM" instead of
+    /// `<uppercase>` before each letter (default `false`)
+    pub fn all_caps_markers(mut self, enabled: bool) -> Self {
+        self.all_caps_markers = enabled;
+        self
+    }
+
+    /// Give the question particle ("musun") and the conjunction "de/da"
+    /// their own tokens, harmonized with the previous word on decode
+    /// (default `false`)
+    pub fn clitics(mut self, enabled: bool) -> Self {
+        self.clitics = enabled;
+        self
+    }
+
+    /// How characters that match no vocabulary entry are emitted
+    pub fn unknown_strategy(mut self, strategy: UnknownStrategy) -> Self {
+        self.unknown_strategy = strategy;
+        self
+    }
+
+    /// How digits are tokenized (default `NumberMode::Bpe`)
+    pub fn number_mode(mut self, mode: NumberMode) -> Self {
+        self.number_mode = mode;
+        self
+    }
+
+    /// Which whitespace separates words and how it is emitted (default
+    /// `WhitespaceMode::Space`)
+    pub fn whitespace_mode(mut self, mode: WhitespaceMode) -> Self {
+        self.whitespace_mode = mode;
+        self
+    }
+
+    /// What happens to zero-width, bidi and control characters (default
+    /// `ControlCharMode::Keep`)
+    pub fn control_chars(mut self, mode: ControlCharMode) -> Self {
+        self.control_chars = mode;
+        self
+    }
+
+    /// How URLs, e-mails, mentions or hashtags are tokenized (default
+    /// `EntityMode::Segment`, like ordinary words)
+    pub fn entity_mode(mut self, kind: EntityKind, mode: EntityMode) -> Self {
+        self.entity_modes.set(kind, mode);
+        self
+    }
+
+    /// Which words skip the root and suffix tables for BPE (default
+    /// `ForeignWordMode::Off`)
+    pub fn foreign_word_mode(mut self, mode: ForeignWordMode) -> Self {
+        self.foreign_word_mode = mode;
+        self
+    }
+
+    /// Restore ı, ğ, ü, ş, ö and ç in words typed without them before
+    /// matching (default `false`)
+    pub fn restore_diacritics(mut self, enabled: bool) -> Self {
+        self.restore_diacritics = enabled;
+        self
+    }
+
+    /// Also match roots and suffixes typed without Turkish letters
+    /// ("cocuk" for "çocuk") where that gives a longer match (default
+    /// `false`)
+    pub fn ascii_fold_matching(mut self, enabled: bool) -> Self {
+        self.ascii_fold_matching = enabled;
+        self
+    }
+
+    /// Number of distinct words whose tokenization is cached (default
+    /// `DEFAULT_WORD_CACHE_CAPACITY`); `0` disables the cache
+    pub fn word_cache_capacity(mut self, capacity: usize) -> Self {
+        self.word_cache_capacity = capacity;
+        self
+    }
+
+    /// Longest input the model accepts, saved as `model_max_length` in
+    /// `tokenizer_config.json` (default unset)
+    pub fn model_max_length(mut self, length: usize) -> Self {
+        self.model_max_length = Some(length);
+        self
+    }
+
+    pub fn pad_token(mut self, token: &str) -> Self {
+        self.pad_token = Some(token.to_string());
+        self
+    }
+
+    pub fn eos_token(mut self, token: &str) -> Self {
+        self.eos_token = Some(token.to_string());
+        self
+    }
+
+    pub fn bos_token(mut self, token: &str) -> Self {
+        self.bos_token = Some(token.to_string());
+        self
+    }
+
+    pub fn sep_token(mut self, token: &str) -> Self {
+        self.sep_token = Some(token.to_string());
+        self
+    }
+
+    pub fn cls_token(mut self, token: &str) -> Self {
+        self.cls_token = Some(token.to_string());
+        self
+    }
+
+    pub fn mask_token(mut self, token: &str) -> Self {
+        self.mask_token = Some(token.to_string());
+        self
+    }
+
+    /// Special tokens `encode_plus_with` adds around a text (default
+    /// `PostProcessor::BosEos`)
+    pub fn post_processor(mut self, post_processor: PostProcessor) -> Self {
+        self.post_processor = post_processor;
+        self
+    }
+
+    /// Layout `apply_chat_template` formats conversations with, adding its
+    /// special tokens (default none)
+    pub fn chat_template(mut self, template: ChatTemplate) -> Self {
+        self.chat_template = Some(template);
+        self
+    }
+
+    /// Give each emoji and symbol in [`COMMON_EMOJI`](crate::COMMON_EMOJI)
+    /// a single token (default `false`)
+    pub fn emoji_tokens(mut self, enabled: bool) -> Self {
+        self.emoji_tokens = enabled;
+        self
+    }
+
+    /// Give each abbreviation in
+    /// [`COMMON_ABBREVIATIONS`](crate::COMMON_ABBREVIATIONS) a single token
+    /// (default `false`)
+    pub fn abbreviations(mut self, enabled: bool) -> Self {
+        self.abbreviations = enabled;
+        self
+    }
+
+    /// Build the tokenizer. Special tokens missing from the vocabulary are
+    /// given reserved `special_*` slots.
+    pub fn build(self) -> Result<TurkishTokenizer, TurkishTokenizerError> {
+        let mut tokenizer = TurkishTokenizer::new()?;
+        tokenizer.split_camel_case = self.split_camel_case;
+        tokenizer.split_punctuation = self.split_punctuation;
+        tokenizer.join_line_breaks = self.join_line_breaks;
+        tokenizer.split_compounds = self.split_compounds;
+        tokenizer.lowercase = self.lowercase;
+        tokenizer.set_case_mapping(self.case_mapping);
+        tokenizer.strict_morphology = self.strict_morphology;
+        tokenizer.morphotactics = self.morphotactics;
+        tokenizer.consonant_alternation = self.consonant_alternation;
+        tokenizer.segmentation = self.segmentation;
+        tokenizer.emit_case_markers = self.emit_case_markers;
+        tokenizer.unknown_strategy = self.unknown_strategy;
+        tokenizer.whitespace_mode = self.whitespace_mode;
+        tokenizer.control_chars = self.control_chars;
+        tokenizer.foreign_word_mode = self.foreign_word_mode;
+        tokenizer.restore_diacritics = self.restore_diacritics;
+        tokenizer.ascii_fold_matching = self.ascii_fold_matching;
+        tokenizer.set_word_cache_capacity(self.word_cache_capacity);
+        tokenizer.model_max_length = self.model_max_length;
+
+        let special_tokens = [
+            ("pad_token", self.pad_token),
+            ("eos_token", self.eos_token),
+            ("bos_token", self.bos_token),
+            ("sep_token", self.sep_token),
+            ("cls_token", self.cls_token),
+            ("mask_token", self.mask_token),
+        ];
+        for (role, token) in special_tokens {
+            if let Some(token) = token {
+                tokenizer.assign_special_token(role, &token)?;
+            }
+        }
+        tokenizer.set_number_mode(self.number_mode)?;
+        tokenizer.set_all_caps_markers(self.all_caps_markers)?;
+        tokenizer.set_clitics(self.clitics)?;
+        tokenizer.set_entity_modes(self.entity_modes)?;
+        tokenizer.set_post_processor(self.post_processor)?;
+        if let Some(template) = self.chat_template {
+            tokenizer.set_chat_template(template)?;
+        }
+        if self.emoji_tokens {
+            tokenizer.add_emoji_tokens(COMMON_EMOJI);
+        }
+        if self.abbreviations {
+            tokenizer.add_abbreviations(COMMON_ABBREVIATIONS);
+        }
+
+        Ok(tokenizer)
+    }
+}
@@ -0,0 +1,62 @@
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use crate::Token;
+
+/// Default number of distinct words kept by the word cache
+pub const DEFAULT_WORD_CACHE_CAPACITY: usize = 10_000;
+
+/// LRU cache of word tokenizations, with offsets relative to the word
+/// start. Shared across threads; segmentation runs outside the lock.
+#[derive(Debug)]
+pub(crate) struct WordCache {
+    lru: Option<Mutex<LruCache<String, Arc<[Token]>>>>,
+}
+
+/// A clone gets an empty cache of the same capacity, since its settings
+/// may diverge from the original's
+impl Clone for WordCache {
+    fn clone(&self) -> Self {
+        WordCache::new(self.capacity())
+    }
+}
+
+impl WordCache {
+    /// A cache holding up to `capacity` words; `0` disables caching
+    pub(crate) fn new(capacity: usize) -> Self {
+        WordCache {
+            lru: NonZeroUsize::new(capacity).map(|capacity| Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.lru.is_some()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.lru.as_ref().map_or(0, |lru| lock(lru).cap().get())
+    }
+
+    pub(crate) fn get(&self, word: &str) -> Option<Arc<[Token]>> {
+        self.lru.as_ref().and_then(|lru| lock(lru).get(word).cloned())
+    }
+
+    pub(crate) fn insert(&self, word: &str, tokens: Arc<[Token]>) {
+        if let Some(lru) = &self.lru {
+            lock(lru).put(word.to_string(), tokens);
+        }
+    }
+
+    /// Drop all entries, e.g. after a setting that affects matching changed
+    pub(crate) fn clear(&self) {
+        if let Some(lru) = &self.lru {
+            lock(lru).clear();
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // A panic while holding the lock cannot leave the cache inconsistent
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
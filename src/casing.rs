@@ -0,0 +1,117 @@
+//! Case mapping for lowercasing before matching and for restoring case
+//! from markers when decoding.
+//!
+//! Turkish pairs dotted İ with i and dotless I with ı, so the default
+//! mapping lowercases "IŞIK" to "ışık" and decodes an `<uppercase>` "ilk"
+//! as "İlk". Text in other languages ("Internet", "iPhone") expects the
+//! Unicode pairing instead.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::TurkishTokenizer;
+
+/// Which letters lowercase and uppercase forms are paired with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseMapping {
+    /// İ and i, I and ı
+    #[default]
+    Turkish,
+    /// I and i, as in most languages; İ is lowercased to i as well
+    Unicode,
+}
+
+impl CaseMapping {
+    /// Push the lowercase of `c`
+    pub(crate) fn push_lower(self, c: char, out: &mut Vec<char>) {
+        match (self, c) {
+            (CaseMapping::Turkish, 'İ') => out.push('i'),
+            (CaseMapping::Turkish, 'I') => out.push('ı'),
+            // Not "i" plus a combining dot above, which no entry contains
+            (CaseMapping::Unicode, 'İ') => out.push('i'),
+            (_, c) => out.extend(c.to_lowercase()),
+        }
+    }
+
+    /// Push the uppercase of `c`
+    fn push_upper(self, c: char, out: &mut String) {
+        match (self, c) {
+            (CaseMapping::Turkish, 'i') => out.push('İ'),
+            (CaseMapping::Turkish, 'ı') => out.push('I'),
+            (_, c) => out.extend(c.to_uppercase()),
+        }
+    }
+
+    /// `s` in uppercase
+    pub fn upper(self, s: &str) -> String {
+        let mut upper = String::with_capacity(s.len());
+        for c in s.chars() {
+            self.push_upper(c, &mut upper);
+        }
+        upper
+    }
+
+    /// `s` in lowercase
+    pub fn lower(self, s: &str) -> String {
+        let mut lower = Vec::with_capacity(s.len());
+        for c in s.chars() {
+            self.push_lower(c, &mut lower);
+        }
+        lower.into_iter().collect()
+    }
+
+    /// `s` with its first character uppercased
+    pub fn capitalize(self, s: &str) -> String {
+        let mut chars = s.chars();
+        let mut capitalized = String::with_capacity(s.len());
+        if let Some(first) = chars.next() {
+            self.push_upper(first, &mut capitalized);
+        }
+        capitalized.push_str(chars.as_str());
+        capitalized
+    }
+}
+
+/// Lowercase `s` with Turkish rules for dotted and dotless I
+pub fn tr_lower(s: &str) -> String {
+    CaseMapping::Turkish.lower(s)
+}
+
+/// Uppercase `s` with Turkish rules for dotted and dotless I
+pub fn tr_upper(s: &str) -> String {
+    CaseMapping::Turkish.upper(s)
+}
+
+impl TurkishTokenizer {
+    /// Whether segments are lowercased before matching
+    pub fn lowercase(&self) -> bool {
+        self.lowercase
+    }
+
+    /// Lowercase segments before matching. Disable for a cased model;
+    /// cased text then only matches the lowercase vocabulary through BPE
+    /// or bytes.
+    pub fn set_lowercase(&mut self, enabled: bool) {
+        self.lowercase = enabled;
+        self.word_cache.clear();
+    }
+
+    pub fn case_mapping(&self) -> CaseMapping {
+        self.case_mapping
+    }
+
+    /// Change how I and i are paired when lowercasing before matching and
+    /// when the decoder restores case from markers
+    pub fn set_case_mapping(&mut self, mapping: CaseMapping) {
+        self.case_mapping = mapping;
+        Arc::make_mut(&mut self.decoder).set_case_mapping(mapping);
+        self.word_cache.clear();
+    }
+
+    /// Push the lowercase of `c` under the tokenizer's case mapping
+    pub(crate) fn push_lower(&self, c: char, out: &mut Vec<char>) {
+        self.case_mapping.push_lower(c, out);
+    }
+}
@@ -0,0 +1,235 @@
+//! Chat templates formatting role-tagged messages into a prompt.
+//!
+//! A template gives the text written for each message, with `{role}` and
+//! `{content}` standing for its fields, the text that opens the reply when
+//! a generation prompt is requested, and the special tokens the format
+//! relies on. Those are added with `add_special_tokens`, so they are
+//! matched whole in the rendered prompt and stay out of the BPE table.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{TurkishTokenizer, TurkishTokenizerError};
+
+/// One message of a conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// "system", "user", "assistant" or any role the template accepts
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn new(role: &str, content: &str) -> Self {
+        ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+}
+
+/// Layout of a conversation prompt, e.g. ChatML:
+///
+/// ```
+/// use turkish_tokenizer::{ChatMessage, ChatTemplate, TurkishTokenizer};
+///
+/// let tokenizer = TurkishTokenizer::builder().chat_template(ChatTemplate::chatml()).build().unwrap();
+/// let messages = [ChatMessage::new("user", "Merhaba")];
+/// let prompt = tokenizer.render_chat(&messages, true).unwrap();
+/// assert_eq!(prompt, "<|im_start|>user\nMerhaba<|im_end|>\n<|im_start|>assistant\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ChatTemplateSpec", into = "ChatTemplateSpec")]
+pub struct ChatTemplate {
+    message: String,
+    generation_prompt: String,
+    special_tokens: Vec<String>,
+}
+
+/// Serialized form of a [`ChatTemplate`]
+#[derive(Serialize, Deserialize)]
+struct ChatTemplateSpec {
+    message: String,
+    generation_prompt: String,
+    #[serde(default)]
+    special_tokens: Vec<String>,
+}
+
+impl ChatTemplate {
+    /// A template writing each message as `message`, which must contain
+    /// `{content}` and may contain `{role}`, and opening the reply with
+    /// `generation_prompt`. `special_tokens` are the tokens of the format
+    /// ("<|im_start|>"), added to the vocabulary with the template.
+    pub fn new(message: &str, generation_prompt: &str, special_tokens: &[&str]) -> Result<Self, TurkishTokenizerError> {
+        if !message.contains("{content}") {
+            return Err(TurkishTokenizerError::InvalidConfig(format!(
+                "Chat message template '{}' must contain {{content}}",
+                message
+            )));
+        }
+        Ok(ChatTemplate {
+            message: message.to_string(),
+            generation_prompt: generation_prompt.to_string(),
+            special_tokens: special_tokens.iter().map(|token| token.to_string()).collect(),
+        })
+    }
+
+    /// The ChatML layout: `<|im_start|>role\ncontent<|im_end|>\n`
+    pub fn chatml() -> Self {
+        ChatTemplate {
+            message: "<|im_start|>{role}\n{content}<|im_end|>\n".to_string(),
+            generation_prompt: "<|im_start|>assistant\n".to_string(),
+            special_tokens: vec!["<|im_start|>".to_string(), "<|im_end|>".to_string()],
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn generation_prompt(&self) -> &str {
+        &self.generation_prompt
+    }
+
+    pub fn special_tokens(&self) -> &[String] {
+        &self.special_tokens
+    }
+
+    /// `messages` in the template's layout, followed by the generation
+    /// prompt if `add_generation_prompt`
+    pub fn render(&self, messages: &[ChatMessage], add_generation_prompt: bool) -> String {
+        let mut prompt = String::new();
+        for message in messages {
+            for part in self.message_parts() {
+                prompt.push_str(match part {
+                    Part::Text(text) => text,
+                    Part::Role => &message.role,
+                    Part::Content => &message.content,
+                });
+            }
+        }
+        if add_generation_prompt {
+            prompt.push_str(&self.generation_prompt);
+        }
+        prompt
+    }
+
+    /// The template as a Jinja `chat_template`, the form Hugging Face's
+    /// `apply_chat_template` reads from `tokenizer_config.json`
+    pub fn to_jinja(&self) -> String {
+        let parts: Vec<String> = self
+            .message_parts()
+            .map(|part| match part {
+                Part::Text(text) => jinja_string(text),
+                Part::Role => "message['role']".to_string(),
+                Part::Content => "message['content']".to_string(),
+            })
+            .collect();
+        format!(
+            "{{% for message in messages %}}{{{{ {} }}}}{{% endfor %}}{{% if add_generation_prompt %}}{{{{ {} }}}}{{% endif %}}",
+            parts.join(" + "),
+            jinja_string(&self.generation_prompt)
+        )
+    }
+
+    /// The message template split at its placeholders
+    fn message_parts(&self) -> impl Iterator<Item = Part<'_>> {
+        let mut rest = self.message.as_str();
+        std::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            for (placeholder, part) in [("{role}", Part::Role), ("{content}", Part::Content)] {
+                if let Some(tail) = rest.strip_prefix(placeholder) {
+                    rest = tail;
+                    return Some(part);
+                }
+            }
+            let end = [rest.find("{role}"), rest.find("{content}")].into_iter().flatten().min().unwrap_or(rest.len());
+            let (text, tail) = rest.split_at(end);
+            rest = tail;
+            Some(Part::Text(text))
+        })
+    }
+}
+
+/// Piece of a message template
+enum Part<'a> {
+    Text(&'a str),
+    Role,
+    Content,
+}
+
+/// `text` as a single-quoted Jinja string literal
+fn jinja_string(text: &str) -> String {
+    let mut literal = String::from("'");
+    for c in text.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '\'' => literal.push_str("\\'"),
+            '\n' => literal.push_str("\\n"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+impl TryFrom<ChatTemplateSpec> for ChatTemplate {
+    type Error = String;
+
+    fn try_from(spec: ChatTemplateSpec) -> Result<Self, Self::Error> {
+        let special_tokens: Vec<&str> = spec.special_tokens.iter().map(String::as_str).collect();
+        Self::new(&spec.message, &spec.generation_prompt, &special_tokens).map_err(|e| e.to_string())
+    }
+}
+
+impl From<ChatTemplate> for ChatTemplateSpec {
+    fn from(template: ChatTemplate) -> Self {
+        ChatTemplateSpec {
+            message: template.message,
+            generation_prompt: template.generation_prompt,
+            special_tokens: template.special_tokens,
+        }
+    }
+}
+
+impl TurkishTokenizer {
+    pub fn chat_template(&self) -> Option<&ChatTemplate> {
+        self.chat_template.as_ref()
+    }
+
+    /// Format conversations with `template`, adding its special tokens
+    /// with `add_special_tokens`
+    pub fn set_chat_template(&mut self, template: ChatTemplate) -> Result<(), TurkishTokenizerError> {
+        let special_tokens: Vec<&str> = template.special_tokens.iter().map(String::as_str).collect();
+        self.add_special_tokens(&special_tokens)?;
+        self.chat_template = Some(template);
+        Ok(())
+    }
+
+    /// `messages` formatted with the chat template, followed by the
+    /// opening of the reply if `add_generation_prompt`. Fails if no chat
+    /// template is set.
+    pub fn render_chat(
+        &self,
+        messages: &[ChatMessage],
+        add_generation_prompt: bool,
+    ) -> Result<String, TurkishTokenizerError> {
+        let template = self
+            .chat_template
+            .as_ref()
+            .ok_or_else(|| TurkishTokenizerError::InvalidConfig("No chat template is set".to_string()))?;
+        Ok(template.render(messages, add_generation_prompt))
+    }
+
+    /// IDs of `render_chat`, encoded without BOS or EOS since the template
+    /// places its own special tokens
+    pub fn apply_chat_template(
+        &self,
+        messages: &[ChatMessage],
+        add_generation_prompt: bool,
+    ) -> Result<Vec<u32>, TurkishTokenizerError> {
+        Ok(self.encode(&self.render_chat(messages, add_generation_prompt)?))
+    }
+}
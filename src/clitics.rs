@@ -0,0 +1,129 @@
+//! The question particle and the "de/da" clitic written as separate words.
+//!
+//! In "geliyor musun?" and "ben de geldim" the particle mı/mi/mu/mü and
+//! the conjunction de/da are written apart from the word they follow but
+//! harmonize with it. Segmented like any other word, "musun" becomes the
+//! root "mus" and "da" the locative suffix, which decodes as "ta" after
+//! "kitap". With clitics enabled each gets a dedicated suffix token whose
+//! form the decoder picks from the vowels of the previous word, and the
+//! conjunction never takes the "ta"/"te" forms. Personal endings after the
+//! particle ("musunuz", "miydi") are matched as suffixes. Only lowercase
+//! words are recognised, so the imperative "De!" is left alone.
+
+use std::sync::Arc;
+
+use crate::matcher::PrefixMatcher;
+use crate::{TokenType, TurkishTokenizer, TurkishTokenizerError, WordPiece, CLITIC_DE_TOKEN, QUESTION_PARTICLE_TOKEN};
+
+/// Forms of the question particle, in the decoder's vowel harmony order
+const PARTICLE_FORMS: [&str; 4] = ["mı", "mi", "mu", "mü"];
+
+const CONJUNCTION_FORMS: [&str; 2] = ["da", "de"];
+
+/// Endings written on the question particle, with `I` standing for its
+/// vowel ("musunuz", "miydi")
+const PARTICLE_ENDINGS: &[&str] = &["", "yIm", "sIn", "yIz", "sInIz", "dIr", "ydI", "ydIm", "ydIn", "ydInIz", "ymIş"];
+
+/// Which clitic a word is
+#[derive(Clone, Copy)]
+enum Clitic {
+    Particle,
+    Conjunction,
+}
+
+/// The clitic spelled by `core`, a run of lowercase letters
+fn clitic_of(core: &[char]) -> Option<Clitic> {
+    match core {
+        ['d', 'a' | 'e'] => Some(Clitic::Conjunction),
+        ['m', vowel @ ('ı' | 'i' | 'u' | 'ü'), ending @ ..] => {
+            let matches = |template: &&str| {
+                template.chars().count() == ending.len()
+                    && template
+                        .chars()
+                        .map(|c| if c == 'I' { *vowel } else { c })
+                        .eq(ending.iter().copied())
+            };
+            PARTICLE_ENDINGS.iter().any(matches).then_some(Clitic::Particle)
+        }
+        _ => None,
+    }
+}
+
+impl TurkishTokenizer {
+    /// Whether the question particle and "de/da" get dedicated tokens
+    pub fn clitics(&self) -> bool {
+        self.clitic_ids.is_some()
+    }
+
+    /// Give the question particle ("musun") and the conjunction "de/da"
+    /// their own suffix tokens, claiming reserved slots for them. The
+    /// decoder harmonizes them with the previous word.
+    pub fn set_clitics(&mut self, enabled: bool) -> Result<(), TurkishTokenizerError> {
+        if !enabled {
+            self.clitic_ids = None;
+        } else if self.clitic_ids.is_none() {
+            let mut claim = |token: &str, forms: &[&str]| -> Result<u32, TurkishTokenizerError> {
+                let id = self.claim_special_token(token)?;
+                let forms = forms.iter().map(|form| form.to_string()).collect();
+                Arc::make_mut(&mut self.decoder).register_clitic(id, forms);
+                Ok(id)
+            };
+            let particle = claim(QUESTION_PARTICLE_TOKEN, &PARTICLE_FORMS)?;
+            let conjunction = claim(CLITIC_DE_TOKEN, &CONJUNCTION_FORMS)?;
+            self.clitic_ids = Some([particle, conjunction]);
+        }
+        self.word_cache.clear();
+        Ok(())
+    }
+
+    /// Length of the clitic at the start of `word`, if it is one followed
+    /// by nothing but punctuation
+    pub(crate) fn clitic_len(&self, word: &[char]) -> Option<usize> {
+        self.clitic_ids?;
+        let len = word.iter().take_while(|c| c.is_alphabetic()).count();
+        let core = &word[..len];
+        let clean = core.iter().all(|c| c.is_lowercase()) && !word[len..].iter().any(|c| c.is_alphanumeric());
+        let matched = clean && clitic_of(core).is_some() && self.suffix_cover(&core[2..]).is_some();
+        matched.then_some(len)
+    }
+
+    /// IDs and lengths of the longest suffixes covering `chars`, if they
+    /// cover all of it
+    fn suffix_cover(&self, chars: &[char]) -> Option<Vec<(u32, usize)>> {
+        let mut cover = Vec::new();
+        let mut pos = 0;
+        while pos < chars.len() {
+            let (id, len) = self.suffix_matcher.longest_prefix(&chars[pos..])?;
+            cover.push((id, len));
+            pos += len;
+        }
+        Some(cover)
+    }
+
+    /// Emit the pieces of a clitic found by `clitic_len`
+    pub(crate) fn emit_clitic(&self, core: &[char], emit: &mut impl FnMut(WordPiece<'_>)) {
+        let (Some([particle, conjunction]), Some(clitic)) = (self.clitic_ids, clitic_of(core)) else {
+            return;
+        };
+        let id = match clitic {
+            Clitic::Particle => particle,
+            Clitic::Conjunction => conjunction,
+        };
+        emit(WordPiece::Matched {
+            id,
+            token_type: TokenType::Suffix,
+            pos: 0,
+            chars: &core[..2],
+        });
+        let mut pos = 2;
+        for (id, len) in self.suffix_cover(&core[2..]).unwrap_or_default() {
+            emit(WordPiece::Matched {
+                id,
+                token_type: TokenType::Suffix,
+                pos,
+                chars: &core[pos..pos + len],
+            });
+            pos += len;
+        }
+    }
+}
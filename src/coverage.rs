@@ -0,0 +1,155 @@
+//! Vocabulary coverage statistics over a corpus, for judging whether the
+//! vocabulary fits a new domain.
+//!
+//! A character is out of vocabulary (OOV) when it comes out as the
+//! `<unknown>` token or as byte fallback tokens. OOV characters are grouped
+//! into coarse classes by script or kind, so a report shows at a glance
+//! whether the misses are emoji, Cyrillic text, symbols and so on.
+
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{TokenType, TurkishTokenizer};
+
+/// Token and OOV counts over a corpus
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CoverageReport {
+    pub texts: u64,
+    /// Space-delimited words
+    pub words: u64,
+    pub tokens: u64,
+    /// Tokens belonging to a word, leaving out spaces between words
+    pub word_tokens: u64,
+    /// `<unknown>` and byte fallback tokens
+    pub unknown_tokens: u64,
+    pub root_tokens: u64,
+    pub suffix_tokens: u64,
+    pub bpe_tokens: u64,
+    pub byte_tokens: u64,
+    /// OOV characters by class, such as `emoji` or `cyrillic`
+    pub oov_char_classes: BTreeMap<String, u64>,
+}
+
+impl CoverageReport {
+    /// Share of tokens that are `<unknown>` or byte fallback, from 0 to 1
+    pub fn unknown_rate(&self) -> f64 {
+        ratio(self.unknown_tokens, self.tokens)
+    }
+
+    pub fn tokens_per_word(&self) -> f64 {
+        ratio(self.word_tokens, self.words)
+    }
+
+    /// OOV character classes, most frequent first
+    pub fn top_oov_char_classes(&self) -> Vec<(&str, u64)> {
+        let mut classes: Vec<(&str, u64)> =
+            self.oov_char_classes.iter().map(|(class, &count)| (class.as_str(), count)).collect();
+        classes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        classes
+    }
+
+    /// Add the counts of `other`, a report over more of the corpus
+    pub fn merge(&mut self, other: CoverageReport) {
+        self.texts += other.texts;
+        self.words += other.words;
+        self.tokens += other.tokens;
+        self.word_tokens += other.word_tokens;
+        self.unknown_tokens += other.unknown_tokens;
+        self.root_tokens += other.root_tokens;
+        self.suffix_tokens += other.suffix_tokens;
+        self.bpe_tokens += other.bpe_tokens;
+        self.byte_tokens += other.byte_tokens;
+        for (class, count) in other.oov_char_classes {
+            *self.oov_char_classes.entry(class).or_default() += count;
+        }
+    }
+}
+
+fn ratio(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+/// Coarse class of an OOV character
+fn char_class(c: char) -> &'static str {
+    match c {
+        _ if c.is_whitespace() => "whitespace",
+        _ if c.is_control() => "control",
+        _ if c.is_numeric() => "digit",
+        '\u{1F000}'..='\u{1FAFF}' | '\u{2600}'..='\u{27BF}' | '\u{FE0F}' | '\u{200D}' => "emoji",
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => "greek",
+        '\u{0400}'..='\u{052F}' => "cyrillic",
+        '\u{0590}'..='\u{05FF}' => "hebrew",
+        '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' => "arabic",
+        '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' => "cjk",
+        '\u{00C0}'..='\u{024F}' => "latin",
+        _ if c.is_ascii_alphabetic() => "latin",
+        _ if c.is_alphabetic() => "other letter",
+        _ if c.is_ascii_punctuation() || ('\u{2000}'..='\u{206F}').contains(&c) => "punctuation",
+        _ => "symbol",
+    }
+}
+
+impl TurkishTokenizer {
+    /// Coverage statistics of the vocabulary over `texts`, tokenized in
+    /// parallel
+    pub fn coverage<'a, I>(&self, texts: I) -> CoverageReport
+    where
+        I: IntoIterator<Item = &'a str>,
+        I::IntoIter: Send,
+    {
+        texts
+            .into_iter()
+            .par_bridge()
+            .map(|text| self.text_coverage(text))
+            .reduce(CoverageReport::default, |mut total, report| {
+                total.merge(report);
+                total
+            })
+    }
+
+    fn text_coverage(&self, text: &str) -> CoverageReport {
+        let mut report = CoverageReport {
+            texts: 1,
+            ..CoverageReport::default()
+        };
+        let mut chars: Option<Vec<char>> = None;
+        let mut last_word = None;
+        let mut last_oov_span = None;
+        for token in self.tokenize_iter(text) {
+            report.tokens += 1;
+            match token.token_type {
+                TokenType::Root => report.root_tokens += 1,
+                TokenType::Suffix => report.suffix_tokens += 1,
+                TokenType::Bpe => report.bpe_tokens += 1,
+                TokenType::Byte => report.byte_tokens += 1,
+            }
+            if token.word_id.is_some() {
+                report.word_tokens += 1;
+                if token.word_id != last_word {
+                    report.words += 1;
+                    last_word = token.word_id;
+                }
+            }
+
+            if token.token_type != TokenType::Byte && token.id != self.unknown_marker.id {
+                continue;
+            }
+            report.unknown_tokens += 1;
+            // The byte tokens of one character share its span
+            if last_oov_span == Some((token.start, token.end)) {
+                continue;
+            }
+            last_oov_span = Some((token.start, token.end));
+            let chars = chars.get_or_insert_with(|| text.chars().collect());
+            for &c in &chars[token.start..token.end] {
+                *report.oov_char_classes.entry(char_class(c).to_string()).or_default() += 1;
+            }
+        }
+        report
+    }
+}
@@ -0,0 +1,225 @@
+//! Restoring Turkish letters in text typed without them.
+//!
+//! User-generated text often writes ı, ğ, ü, ş, ö and ç as i, g, u, s, o
+//! and c ("gunaydin" for "günaydın"), so roots stop matching. Each run of
+//! ASCII letters is matched against the root and suffix tables with those
+//! letters folded, as a root followed by suffixes that harmonize with it,
+//! and takes the letters of the entries it matched. Among the covers,
+//! those with fewer pieces and fewer changed letters win. Runs the tables
+//! cannot fully cover are left alone. Runs after an apostrophe continue
+//! the previous run with suffixes ("istanbul'da").
+//! Restoration changes letters one for one, so character offsets into the
+//! original text stay valid.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{morphology, TurkishTokenizer};
+
+/// Turkish letters and the ASCII letters typed for them
+const FOLDS: [(char, char); 6] = [('ı', 'i'), ('ğ', 'g'), ('ü', 'u'), ('ş', 's'), ('ö', 'o'), ('ç', 'c')];
+
+/// Longest run restored; longer runs are not Turkish words
+const MAX_RUN_LEN: usize = 32;
+
+/// Cost of each root or suffix in a cover and of each letter it changes.
+/// Changing a letter costs slightly less than a piece, so "arkadaş" +
+/// "lar" beats "arka" + "da" + "s" + "lar", and "şimdi" beats "sim" +
+/// "di", while "gid" + "i" + "yor" still beats "gıdı" + "yor".
+const PIECE_COST: usize = 10;
+const CHANGE_COST: usize = 9;
+
+/// A restoration of part of a run: its cost and restored letters
+type Cover = (usize, Vec<char>);
+
+/// ASCII letter typed for `c`, or `c` itself
+pub(crate) fn fold(c: char) -> char {
+    FOLDS.iter().find(|&&(turkish, _)| turkish == c).map_or(c, |&(_, ascii)| ascii)
+}
+
+fn is_foldable(c: char) -> bool {
+    FOLDS.iter().any(|&(_, ascii)| ascii == c)
+}
+
+/// Root and suffix forms by their folded spelling, forms spelled like
+/// the key first, then by ID
+#[derive(Debug, Default)]
+pub(crate) struct Deasciifier {
+    roots: HashMap<String, Vec<Vec<char>>>,
+    suffixes: HashMap<String, Vec<Vec<char>>>,
+    max_len: usize,
+}
+
+impl Deasciifier {
+    fn new(roots: &HashMap<String, u32>, suffixes: &HashMap<String, u32>) -> Self {
+        let mut max_len = 0;
+        let mut index = |table: &HashMap<String, u32>| {
+            let mut entries: Vec<(&String, u32)> = table.iter().map(|(form, &id)| (form, id)).collect();
+            entries.sort_by_key(|&(form, id)| (form.chars().any(|c| fold(c) != c), id));
+            let mut folded: HashMap<String, Vec<Vec<char>>> = HashMap::new();
+            for (form, _) in entries {
+                let chars: Vec<char> = form.chars().collect();
+                max_len = max_len.max(chars.len());
+                folded.entry(chars.iter().map(|&c| fold(c)).collect()).or_default().push(chars);
+            }
+            folded
+        };
+        let roots = index(roots);
+        let suffixes = index(suffixes);
+        Deasciifier { roots, suffixes, max_len }
+    }
+
+    /// Cheapest restoration of `run[pos..]`: a root first if `as_root`,
+    /// then suffixes harmonizing with `last_vowel`, the last vowel so far.
+    /// Ties go to the longest first piece. `memo` caches the suffix
+    /// searches.
+    fn cover(
+        &self,
+        run: &[char],
+        pos: usize,
+        as_root: bool,
+        last_vowel: Option<char>,
+        memo: &mut HashMap<(usize, Option<char>), Option<Cover>>,
+    ) -> Option<Cover> {
+        if pos == run.len() {
+            return Some((0, Vec::new()));
+        }
+        if !as_root {
+            if let Some(best) = memo.get(&(pos, last_vowel)) {
+                return best.clone();
+            }
+        }
+        let table = if as_root { &self.roots } else { &self.suffixes };
+        let stem: Vec<char> = last_vowel.into_iter().collect();
+        let mut best: Option<Cover> = None;
+        let mut key = String::new();
+        for len in (1..=self.max_len.min(run.len() - pos)).rev() {
+            let typed = &run[pos..pos + len];
+            key.clear();
+            key.extend(typed);
+            let mut forms: Vec<Vec<char>> = table.get(&key).cloned().unwrap_or_default();
+            if as_root {
+                forms.extend(self.alternated_roots(run, pos, len));
+            }
+            for form in forms {
+                if !as_root && !morphology::harmonizes(&stem, &form) {
+                    continue;
+                }
+                let vowel = form.iter().rev().copied().find(|&c| morphology::is_vowel(c)).or(last_vowel);
+                let Some((rest_cost, rest)) = self.cover(run, pos + len, false, vowel, memo) else {
+                    continue;
+                };
+                let changes = form.iter().zip(typed).filter(|(a, b)| a != b).count();
+                let cost = rest_cost + PIECE_COST + changes * CHANGE_COST;
+                if best.as_ref().is_none_or(|(lowest, _)| cost < *lowest) {
+                    best = Some((cost, form.into_iter().chain(rest).collect()));
+                }
+            }
+        }
+        if !as_root {
+            memo.insert((pos, last_vowel), best.clone());
+        }
+        best
+    }
+
+    /// Roots typed as `run[pos..pos + len]` with their final consonant
+    /// voiced before a vowel ("gid" in "gidiyor" for "git", "kopeg" in
+    /// "kopegi" for "köpek"), spelled with the voiced consonant
+    fn alternated_roots(&self, run: &[char], pos: usize, len: usize) -> Vec<Vec<char>> {
+        let last = pos + len - 1;
+        let vowel_follows = run.get(pos + len).is_some_and(|&c| morphology::is_vowel(c));
+        let prev = last.checked_sub(1).map(|i| run[i]);
+        // A typed "g" may stand for "ğ"
+        let voiced: &[char] = if run[last] == 'g' { &['g', 'ğ'] } else { &[run[last]] };
+        let mut forms = Vec::new();
+        for &voiced in voiced {
+            let Some(partner) = morphology::alternate_consonant(prev, voiced, vowel_follows) else {
+                continue;
+            };
+            let key: String = run[pos..last].iter().copied().chain([fold(partner)]).collect();
+            for form in self.roots.get(&key).into_iter().flatten() {
+                let mut form = form.clone();
+                form[len - 1] = voiced;
+                forms.push(form);
+            }
+        }
+        forms
+    }
+}
+
+impl TurkishTokenizer {
+    /// Whether words are deasciified before matching
+    pub fn restore_diacritics(&self) -> bool {
+        self.restore_diacritics
+    }
+
+    /// Deasciify every word before matching, so "gunaydin" is tokenized as
+    /// "günaydın". Token surfaces keep the text as typed.
+    pub fn set_restore_diacritics(&mut self, enabled: bool) {
+        self.restore_diacritics = enabled;
+        self.word_cache.clear();
+    }
+
+    /// `text` with ı, ğ, ü, ş, ö and ç restored where the root and suffix
+    /// tables show they were typed as ASCII letters. Offsets are unchanged,
+    /// so `encode(&deasciify(text))` deasciifies a single call.
+    pub fn deasciify(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        match self.deasciify_chars(&chars) {
+            Some(restored) => restored.into_iter().collect(),
+            None => text.to_string(),
+        }
+    }
+
+    /// `chars` with Turkish letters restored, or `None` if nothing changed
+    pub(crate) fn deasciify_chars(&self, chars: &[char]) -> Option<Vec<char>> {
+        if !chars.iter().any(|&c| is_foldable(c.to_ascii_lowercase())) {
+            return None;
+        }
+        let deasciifier = self
+            .deasciifier
+            .get_or_init(|| Arc::new(Deasciifier::new(&self.roots, &self.suffixes)));
+        let mut restored = chars.to_vec();
+        let mut changed = false;
+        // Last vowel of the previous run, for suffixes after an apostrophe
+        let mut last_vowel = None;
+        let mut after_apostrophe = false;
+        let mut start = 0;
+        while start < chars.len() {
+            let len = chars[start..].iter().take_while(|c| c.is_alphabetic()).count();
+            if len == 0 {
+                after_apostrophe = matches!(chars[start], '\'' | '’');
+                start += 1;
+                continue;
+            }
+            let run = &chars[start..start + len];
+            let lower: Vec<char> = run.iter().map(|c| c.to_ascii_lowercase()).collect();
+            let stem_vowel = if after_apostrophe { last_vowel } else { None };
+            let restorable = len <= MAX_RUN_LEN && run.iter().all(char::is_ascii) && lower.iter().any(|&c| is_foldable(c));
+            let cover = restorable
+                .then(|| deasciifier.cover(&lower, 0, !after_apostrophe, stem_vowel, &mut HashMap::new()))
+                .flatten();
+            let letters = cover.map_or(lower, |(_, letters)| letters);
+            for ((slot, &typed), &letter) in restored[start..start + len].iter_mut().zip(run).zip(&letters) {
+                if letter != typed.to_ascii_lowercase() {
+                    *slot = if typed.is_uppercase() { tr_upper(letter) } else { letter };
+                    changed = true;
+                }
+            }
+            last_vowel = letters.iter().rev().copied().find(|&c| morphology::is_vowel(c)).or(stem_vowel);
+            after_apostrophe = false;
+            start += len;
+        }
+        changed.then_some(restored)
+    }
+}
+
+/// Uppercase of a lowercase letter with Turkish rules for dotted and
+/// dotless I
+fn tr_upper(c: char) -> char {
+    match c {
+        'i' => 'İ',
+        'ı' => 'I',
+        c => c.to_uppercase().next().unwrap_or(c),
+    }
+}
@@ -0,0 +1,84 @@
+use crate::TurkishTokenizer;
+
+/// Decodes IDs one at a time during generation, returning text as soon as
+/// it can no longer change. Created by
+/// [`TurkishTokenizer::decode_stream`].
+///
+/// A root's form depends on the suffix after it ("kitap", "kitabı"), an
+/// `<uppercase>` marker on the token after it, and a character split into
+/// byte tokens is only complete with its last byte, so IDs are held back
+/// until a space, newline, tab or special token ends the word. Each word is
+/// decoded with the word before it, which suffixes and clitics harmonize
+/// with, and the text is the same as `decode` gives for the whole sequence.
+///
+/// ```
+/// use turkish_tokenizer::TurkishTokenizer;
+///
+/// let tokenizer = TurkishTokenizer::new().unwrap();
+/// let ids = tokenizer.encode("Kitabı okudum");
+/// let mut stream = tokenizer.decode_stream(false);
+/// let mut text: String = ids.iter().filter_map(|&id| stream.push(id)).collect();
+/// text.extend(stream.finish());
+/// assert_eq!(text, tokenizer.decode(&ids));
+/// ```
+pub struct DecodeStream<'a> {
+    tokenizer: &'a TurkishTokenizer,
+    skip_special_tokens: bool,
+    /// IDs of the last decoded word, with the separators after it
+    context: Vec<u32>,
+    /// IDs not decoded yet
+    pending: Vec<u32>,
+}
+
+impl<'a> DecodeStream<'a> {
+    pub(crate) fn new(tokenizer: &'a TurkishTokenizer, skip_special_tokens: bool) -> Self {
+        DecodeStream {
+            tokenizer,
+            skip_special_tokens,
+            context: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Add the next generated ID. Returns the text it completes, if any.
+    pub fn push(&mut self, id: u32) -> Option<String> {
+        let special = self.tokenizer.is_skipped_special(id);
+        if special && self.skip_special_tokens {
+            return None;
+        }
+        self.pending.push(id);
+        let separator = [self.tokenizer.space_marker.id, self.tokenizer.newline_marker.id, self.tokenizer.tab_marker.id];
+        if special || separator.contains(&id) {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// Decode the IDs still held back, at the end of generation
+    pub fn finish(&mut self) -> Option<String> {
+        self.flush()
+    }
+
+    fn flush(&mut self) -> Option<String> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let ids = [self.context.as_slice(), &self.pending].concat();
+        let text = self.tokenizer.decoder.decode_parts(&ids)[self.context.len()..].concat();
+        // Keep the previous word as context across runs of separators
+        if text.chars().any(char::is_alphanumeric) {
+            self.context.clear();
+        }
+        self.context.append(&mut self.pending);
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+impl TurkishTokenizer {
+    /// Decode generated IDs incrementally, a word at a time, optionally
+    /// dropping the tokens `decode_with_options` skips
+    pub fn decode_stream(&self, skip_special_tokens: bool) -> DecodeStream<'_> {
+        DecodeStream::new(self, skip_special_tokens)
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::CaseMapping;
+
+// Vowel and consonant classes used by the suffix selection rules
+const ALL_VOWELS: &str = "aeıioöuüâ";
+const INCE_VOWELS: &str = "eiöü"; // Front vowels
+const AI_VOWELS: &str = "aıâ"; // Back unrounded
+const EI_VOWELS: &str = "ei"; // Front unrounded
+const OU_VOWELS: &str = "ou"; // Back rounded
+const HARD_CONSONANTS: &str = "fstkçşhp"; // Sert ünsüzler
+const WHITESPACE: &str = " \n\t";
+
+/// Rebuilds text from token IDs.
+///
+/// A single ID can stand for several surface forms (e.g. `lar`/`ler`, or
+/// `kitap`/`kitab`), so the decoder picks the form that fits the
+/// surrounding tokens using Turkish vowel harmony and consonant rules.
+#[derive(Debug, Clone)]
+pub struct TurkishDecoder {
+    reverse_dict: HashMap<u32, Vec<String>>,
+    byte_offset: Option<u32>,
+    uppercase_id: Option<u32>,
+    unknown_id: Option<u32>,
+    all_caps_id: Option<u32>,
+    /// IDs of the suffix table, whose forms harmonize with the word so far;
+    /// other IDs with several forms are roots
+    suffix_ids: HashSet<u32>,
+    /// ID of the suffix "yor", before which roots like "işle" narrow their
+    /// last vowel
+    yor_id: Option<u32>,
+    /// Clitics written as separate words that harmonize with the previous
+    /// one
+    clitic_ids: Vec<u32>,
+    /// Pairing of I and i when restoring case from markers
+    case_mapping: CaseMapping,
+}
+
+impl TurkishDecoder {
+    pub fn new(reverse_dict: HashMap<u32, Vec<String>>) -> Self {
+        TurkishDecoder {
+            reverse_dict,
+            byte_offset: None,
+            uppercase_id: None,
+            unknown_id: None,
+            all_caps_id: None,
+            suffix_ids: HashSet::new(),
+            yor_id: None,
+            clitic_ids: Vec::new(),
+            case_mapping: CaseMapping::Turkish,
+        }
+    }
+
+    /// Register the 256 byte-fallback tokens starting at `offset`.
+    /// Consecutive byte tokens are decoded together as UTF-8.
+    pub fn register_byte_tokens(&mut self, offset: u32) {
+        for byte in 0..=u8::MAX {
+            self.reverse_dict
+                .insert(offset + u32::from(byte), vec![byte_token_string(byte)]);
+        }
+        self.byte_offset = Some(offset);
+    }
+
+    /// Treat `id` as the `<uppercase>` marker, capitalizing the token
+    /// after it
+    pub fn register_uppercase_marker(&mut self, id: u32) {
+        self.uppercase_id = Some(id);
+    }
+
+    /// Treat `id` as the `<unknown>` token, decoded as `▁u▁`
+    pub fn register_unknown_marker(&mut self, id: u32) {
+        self.unknown_id = Some(id);
+    }
+
+    /// Decode `ids` as suffixes, choosing among their forms by the vowel
+    /// harmony and consonants of the word so far. The harmony rule of each
+    /// suffix is read from its forms.
+    pub fn register_suffixes(&mut self, ids: impl IntoIterator<Item = u32>) {
+        self.suffix_ids.extend(ids);
+        self.yor_id = self
+            .suffix_ids
+            .iter()
+            .copied()
+            .find(|id| self.forms(*id).is_some_and(|forms| forms == ["yor"]));
+    }
+
+    /// Treat `id` as the all-caps marker: the letters-only tokens after it
+    /// are uppercased until the next space, marker or punctuation
+    pub fn register_all_caps_marker(&mut self, id: u32) {
+        self.all_caps_id = Some(id);
+    }
+
+    /// Decode `id` as whichever of `forms` harmonizes with the previous
+    /// word: two forms for back and front vowels ("da", "de"), or four for
+    /// back unrounded, front unrounded, back rounded and front rounded
+    /// ("mı", "mi", "mu", "mü")
+    pub fn register_clitic(&mut self, id: u32, forms: Vec<String>) {
+        self.reverse_dict.insert(id, forms);
+        if !self.clitic_ids.contains(&id) {
+            self.clitic_ids.push(id);
+        }
+    }
+
+    /// Change how `<uppercase>` and `<all_caps>` markers pair I and i
+    pub fn set_case_mapping(&mut self, mapping: CaseMapping) {
+        self.case_mapping = mapping;
+    }
+
+    /// Replace the surface forms decoded for `id`
+    pub fn set_forms(&mut self, id: u32, forms: Vec<String>) {
+        self.reverse_dict.insert(id, forms);
+    }
+
+    fn byte_value(&self, id: u32) -> Option<u8> {
+        let offset = self.byte_offset?;
+        id.checked_sub(offset).and_then(|b| u8::try_from(b).ok())
+    }
+
+    /// Build the ID to surface forms table from `(form, ID)` entries,
+    /// keeping their order
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        let mut reverse_dict: HashMap<u32, Vec<String>> = HashMap::new();
+        for (token, id) in entries {
+            reverse_dict.entry(id).or_default().push(token.to_string());
+        }
+        Self::new(reverse_dict)
+    }
+
+    /// All surface forms that share the given ID, in vocabulary order
+    pub fn forms(&self, id: u32) -> Option<&[String]> {
+        self.reverse_dict.get(&id).map(|forms| forms.as_slice())
+    }
+
+    fn first_form(&self, id: u32) -> &str {
+        self.forms(id)
+            .and_then(|forms| forms.first())
+            .map(|form| form.as_str())
+            .unwrap_or("")
+    }
+
+    /// Decode a list of token IDs to text
+    pub fn decode(&self, ids: &[u32]) -> String {
+        self.decode_parts(ids).concat()
+    }
+
+    /// Decode token IDs into one text part per ID. Markers and all but the
+    /// last token of a byte run decode to an empty part.
+    pub fn decode_parts(&self, ids: &[u32]) -> Vec<String> {
+        let mut text_parts: Vec<String> = Vec::with_capacity(ids.len());
+        let mut pending_bytes: Vec<u8> = Vec::new();
+        let mut capitalize_next = false;
+        // Parts to uppercase once suffix selection no longer reads them
+        let mut all_caps_parts = Vec::new();
+        let mut in_all_caps = false;
+
+        for (i, &token_id) in ids.iter().enumerate() {
+            // The uppercase marker capitalizes whatever the next token decodes to
+            if Some(token_id) == self.uppercase_id && i < ids.len() - 1 {
+                capitalize_next = true;
+                in_all_caps = false;
+                text_parts.push(String::new());
+                continue;
+            }
+            if Some(token_id) == self.all_caps_id {
+                in_all_caps = true;
+                text_parts.push(String::new());
+                continue;
+            }
+
+            let mut part = if let Some(byte) = self.byte_value(token_id) {
+                pending_bytes.push(byte);
+                let run_continues = ids
+                    .get(i + 1)
+                    .is_some_and(|&next_id| self.byte_value(next_id).is_some());
+                if run_continues {
+                    text_parts.push(String::new());
+                    continue;
+                }
+                let part = String::from_utf8_lossy(&pending_bytes).into_owned();
+                pending_bytes.clear();
+                part
+            } else if Some(token_id) == self.unknown_id {
+                "▁u▁".to_string()
+            } else if let Some(tokens) = self.reverse_dict.get(&token_id) {
+                if tokens.len() > 1 && self.clitic_ids.contains(&token_id) {
+                    let prev_word = preceding_letters(&text_parts);
+                    let index = if tokens.len() == 4 {
+                        vowel_suffix_index(&prev_word)
+                    } else {
+                        usize::from(ends_with_ince(&prev_word))
+                    };
+                    tokens[index].clone()
+                } else if tokens.len() > 1 {
+                    if self.suffix_ids.contains(&token_id) {
+                        // suffix token: harmonize with the word so far
+                        let prev_token = preceding_letters(&text_parts);
+                        self.select_correct_suffix(i, ids, &prev_token).to_string()
+                    } else {
+                        // root token
+                        self.select_correct_root(i, ids).to_string()
+                    }
+                } else {
+                    tokens[0].clone()
+                }
+            } else {
+                "▁".to_string()
+            };
+
+            if capitalize_next {
+                part = self.case_mapping.capitalize(&part);
+                capitalize_next = false;
+            }
+            if in_all_caps {
+                if is_alpha(&part) {
+                    all_caps_parts.push(text_parts.len());
+                } else {
+                    in_all_caps = false;
+                }
+            }
+            text_parts.push(part);
+        }
+
+        for i in all_caps_parts {
+            text_parts[i] = self.case_mapping.upper(&text_parts[i]);
+        }
+        text_parts
+    }
+
+    fn select_correct_suffix(&self, i: usize, ids: &[u32], prev_token: &str) -> &str {
+        let token_id = ids[i];
+        let suffixes = &self.reverse_dict[&token_id];
+
+        // Suffixes with their own consonant rules are known by their first
+        // form, the rest by how many vowels they harmonize with
+        match (suffixes[0].as_str(), suffixes.len()) {
+            // la, le, yla, yle
+            ("la", 4) => {
+                let end_of_word = match ids.get(i + 1) {
+                    Some(&next_id) => WHITESPACE.contains(self.first_form(next_id)),
+                    None => true,
+                };
+                handle_la_le_suffix(prev_token, suffixes, end_of_word)
+            }
+            // da, de, ta, te, dan, den, tan, ten
+            ("da" | "dan", 4) => handle_da_de_suffix(prev_token, suffixes),
+            // dı, di, du, dü, tı, ti, tu, tü, etc.
+            ("dı" | "cı" | "dır", 8) => handle_di_du_suffix(prev_token, suffixes),
+            // lık, lik, luk, lük, etc.
+            ("lık", 8) => self.handle_lik_suffix(i, ids, prev_token, suffixes),
+            // cık, cik, cuk, cük, etc.
+            ("cık", 16) => self.handle_cik_suffix(i, ids, prev_token, suffixes),
+            // mak, mek, may, mey
+            ("mak", 4) => self.handle_mak_suffix(i, ids, prev_token, suffixes),
+            // acak, ecek, etc.
+            ("acak", 8) => self.handle_acak_suffix(i, ids, prev_token, suffixes),
+            // Basic suffix selection based on vowel harmony
+            (_, 2) => &suffixes[usize::from(ends_with_ince(prev_token))],
+            // nın, nin, nun, nün
+            (_, 4) => &suffixes[vowel_suffix_index(prev_token)],
+            _ => &suffixes[0],
+        }
+    }
+
+    fn next_first_form(&self, i: usize, ids: &[u32]) -> Option<&str> {
+        ids.get(i + 1).map(|&next_id| self.first_form(next_id))
+    }
+
+    fn handle_lik_suffix<'a>(
+        &self,
+        i: usize,
+        ids: &[u32],
+        prev_token: &str,
+        suffixes: &'a [String],
+    ) -> &'a str {
+        let Some(next_token) = self.next_first_form(i, ids) else {
+            return &suffixes[0];
+        };
+        let base_index = vowel_suffix_index(prev_token);
+        if starts_with_vowel(next_token) {
+            &suffixes[base_index + 4]
+        } else {
+            &suffixes[base_index]
+        }
+    }
+
+    fn handle_cik_suffix<'a>(
+        &self,
+        i: usize,
+        ids: &[u32],
+        prev_token: &str,
+        suffixes: &'a [String],
+    ) -> &'a str {
+        let Some(next_token) = self.next_first_form(i, ids) else {
+            return &suffixes[0];
+        };
+        let base_index = vowel_suffix_index(prev_token);
+        let offset = match (starts_with_vowel(next_token), ends_with_sert_unsuz(prev_token)) {
+            (true, true) => 12,
+            (true, false) => 8,
+            (false, true) => 4,
+            (false, false) => 0,
+        };
+        &suffixes[base_index + offset]
+    }
+
+    fn handle_mak_suffix<'a>(
+        &self,
+        i: usize,
+        ids: &[u32],
+        prev_token: &str,
+        suffixes: &'a [String],
+    ) -> &'a str {
+        let Some(next_token) = self.next_first_form(i, ids) else {
+            return &suffixes[0];
+        };
+        let base_index = if ends_with_ince(prev_token) { 1 } else { 0 };
+        if starts_with_vowel(next_token) {
+            &suffixes[base_index + 2]
+        } else {
+            &suffixes[base_index]
+        }
+    }
+
+    fn handle_acak_suffix<'a>(
+        &self,
+        i: usize,
+        ids: &[u32],
+        prev_token: &str,
+        suffixes: &'a [String],
+    ) -> &'a str {
+        let is_vowel_ending = ends_with_vowel(prev_token);
+        let is_ince = ends_with_ince(prev_token);
+        let is_vowel_starting = self
+            .next_first_form(i, ids)
+            .map(starts_with_vowel)
+            .unwrap_or(false);
+
+        let index = match (is_vowel_starting, is_vowel_ending) {
+            (true, true) => 6,
+            (true, false) => 2,
+            (false, true) => 4,
+            (false, false) => 0,
+        };
+        &suffixes[index + usize::from(is_ince)]
+    }
+
+    fn select_correct_root(&self, i: usize, ids: &[u32]) -> &str {
+        let token_id = ids[i];
+        let tokens = &self.reverse_dict[&token_id];
+
+        if i + 1 >= ids.len() {
+            return &tokens[0];
+        }
+
+        let next_token = self.first_form(ids[i + 1]);
+
+        if narrows_before_yor(tokens) {
+            // işle, işli
+            if Some(ids[i + 1]) == self.yor_id {
+                &tokens[1]
+            } else {
+                &tokens[0]
+            }
+        } else if !spelling_variants(tokens) && starts_with_vowel(next_token) {
+            // kitap, kitab; bedir, bedr
+            &tokens[1]
+        } else {
+            &tokens[0]
+        }
+    }
+}
+
+/// Vocabulary string for a byte-fallback token, e.g. `<0x41>`
+pub(crate) fn byte_token_string(byte: u8) -> String {
+    format!("<0x{:02X}>", byte)
+}
+
+/// Whether the forms are a verb root ending in a or e and the same root
+/// with the vowel narrowed, as "işle" is written "işli" before "yor"
+fn narrows_before_yor(forms: &[String]) -> bool {
+    let [form, narrowed] = forms else {
+        return false;
+    };
+    let (Some(stem), Some(narrowed_stem)) = (
+        form.strip_suffix(['a', 'e']),
+        narrowed.strip_suffix(['ı', 'i', 'u', 'ü']),
+    ) else {
+        return false;
+    };
+    stem == narrowed_stem
+}
+
+/// Whether the forms differ only by circumflexes, as "zeka" and "zekâ"
+fn spelling_variants(forms: &[String]) -> bool {
+    let plain = |form: &String| form.replace('â', "a").replace('î', "i").replace('û', "u");
+    forms.iter().all(|form| plain(form) == plain(&forms[0]))
+}
+
+fn is_alpha(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(char::is_alphabetic)
+}
+
+/// The letters decoded last: the alphabetic parts, and markers between
+/// them, before any trailing non-alphabetic ones. Suffixes harmonize with
+/// the whole stem, not just the piece before them ("n" in "sun" + "uz").
+fn preceding_letters(parts: &[String]) -> String {
+    let mut letters: Vec<&str> = parts
+        .iter()
+        .rev()
+        .skip_while(|part| !is_alpha(part))
+        .take_while(|part| part.is_empty() || is_alpha(part))
+        .map(String::as_str)
+        .collect();
+    letters.reverse();
+    letters.concat()
+}
+
+fn starts_with_vowel(word: &str) -> bool {
+    word.chars().next().is_some_and(|c| ALL_VOWELS.contains(c))
+}
+
+fn ends_with_vowel(word: &str) -> bool {
+    word.chars().next_back().is_some_and(|c| ALL_VOWELS.contains(c))
+}
+
+/// Walk back from the end of the word until the first vowel and report
+/// whether a character from `charset` was seen on the way
+fn ends_with_any(word: &str, charset: &str) -> bool {
+    for c in word.chars().rev() {
+        if charset.contains(c) {
+            return true;
+        }
+        if ALL_VOWELS.contains(c) {
+            return false;
+        }
+    }
+    false
+}
+
+/// Check if word ends with front vowels (ince ünlü)
+fn ends_with_ince(word: &str) -> bool {
+    if matches!(word, "saat" | "kilovatsaat" | "ziraat" | "itaat" | "istikbal") {
+        return true;
+    }
+    ends_with_any(word, INCE_VOWELS)
+}
+
+/// Check if word ends with a hard consonant
+fn ends_with_sert_unsuz(word: &str) -> bool {
+    word.chars().next_back().is_some_and(|c| HARD_CONSONANTS.contains(c))
+}
+
+/// Get suffix index based on vowel harmony rules
+fn vowel_suffix_index(prev_token: &str) -> usize {
+    if ends_with_any(prev_token, AI_VOWELS) {
+        0
+    } else if ends_with_any(prev_token, EI_VOWELS) {
+        1
+    } else if ends_with_any(prev_token, OU_VOWELS) {
+        2
+    } else {
+        3
+    }
+}
+
+fn handle_la_le_suffix<'a>(prev_token: &str, suffixes: &'a [String], end_of_word: bool) -> &'a str {
+    let is_ince = usize::from(ends_with_ince(prev_token));
+    if ends_with_vowel(prev_token) && end_of_word {
+        &suffixes[2 + is_ince]
+    } else {
+        &suffixes[is_ince]
+    }
+}
+
+fn handle_da_de_suffix<'a>(prev_token: &str, suffixes: &'a [String]) -> &'a str {
+    let is_ince = usize::from(ends_with_ince(prev_token));
+    if ends_with_sert_unsuz(prev_token) {
+        &suffixes[2 + is_ince]
+    } else {
+        &suffixes[is_ince]
+    }
+}
+
+fn handle_di_du_suffix<'a>(prev_token: &str, suffixes: &'a [String]) -> &'a str {
+    let base_index = vowel_suffix_index(prev_token);
+    if ends_with_sert_unsuz(prev_token) {
+        &suffixes[base_index + 4]
+    } else {
+        &suffixes[base_index]
+    }
+}
+
@@ -0,0 +1,112 @@
+//! Whole tokens for emoji and common symbols.
+//!
+//! Emoji are in none of the root, suffix and BPE tables, so social-media
+//! text would otherwise come out as long runs of byte tokens or
+//! `<unknown>`. The emoji table is only consulted for grapheme clusters
+//! starting with a non-alphanumeric character, so it costs nothing on ordinary text. Table entries win
+//! over root, suffix and BPE matches, so an emoji whose base character is
+//! in the BPE table ("❤" in "❤️") still comes out whole. Lookup ignores the
+//! U+FE0F variation selector, which is often dropped or added by
+//! keyboards. Emoji missing from the table still follow the unknown
+//! strategy, byte fallback by default.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{TurkishTokenizer, TurkishTokenizerError};
+
+/// File holding the emoji table inside a tokenizer directory
+pub const EMOJI_TOKENS_FILE: &str = "emoji_tokens.json";
+
+/// Emoji and symbols frequent in Turkish social-media text, for
+/// `add_emoji_tokens` and `TurkishTokenizerBuilder::emoji_tokens`
+pub const COMMON_EMOJI: &[&str] = &[
+    "😂", "🤣", "😊", "😍", "🥰", "😘", "😁", "😀", "😃", "😄", "😅", "😆", "😉", "😎", "🤩", "🥳", "😇", "🙂",
+    "🙃", "😋", "😜", "🤪", "🤔", "🤗", "🤭", "🙄", "😏", "😐", "😑", "😶", "😬", "😒", "😔", "😢", "😭", "😞",
+    "😟", "😩", "😫", "🥺", "😤", "😠", "😡", "🤬", "😱", "😳", "😴", "🤢", "🤮", "🤧", "😷", "🤒", "💀", "🙈",
+    "🙏", "👏", "👍", "👎", "👌", "✌️", "🤞", "🤝", "💪", "👋", "✋", "👊", "🫶", "❤️", "🧡", "💛", "💚", "💙",
+    "💜", "🖤", "🤍", "💔", "💕", "💖", "💗", "💯", "🔥", "✨", "⭐", "🌟", "🎉", "🎊", "🎂", "🎁", "🌹", "🌸",
+    "☀️", "🌙", "⚽", "🏆", "☕", "🍵", "📌", "📍", "📷", "📸", "🎶", "🎵", "✅", "❌", "❗", "❓", "⚠️", "👉",
+    "👈", "👇", "👆", "🇹🇷", "©", "®", "™", "€", "₺", "$", "£", "°", "±", "×", "÷", "→", "←", "↑", "↓", "✓", "•",
+];
+
+/// Lookup key of an emoji: its text without variation selectors
+fn emoji_key(emoji: &str) -> String {
+    emoji.chars().filter(|&c| c != '\u{FE0F}').collect()
+}
+
+impl TurkishTokenizer {
+    /// Give each emoji or symbol a single token. Entries already in the
+    /// vocabulary keep their ID; others get new IDs after it, so the
+    /// model's embeddings must grow to `vocab_size`. Returns how many were
+    /// new to the vocabulary.
+    pub fn add_emoji_tokens(&mut self, tokens: &[&str]) -> usize {
+        let mut added = 0;
+        for &token in tokens {
+            let key = emoji_key(token);
+            if key.is_empty() || self.emoji_tokens.contains_key(&key) {
+                continue;
+            }
+            let id = match self.vocab.get(token) {
+                Some(&id) => id,
+                None => {
+                    added += 1;
+                    self.append_token(token, None)
+                }
+            };
+            Arc::make_mut(&mut self.emoji_tokens).insert(key, id);
+        }
+        self.word_cache.clear();
+        added
+    }
+
+    /// Entries of the emoji table and their IDs, ordered by ID
+    pub fn emoji_tokens(&self) -> Vec<(String, u32)> {
+        let mut ids: Vec<u32> = self.emoji_tokens.values().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter()
+            .filter_map(|id| Some((self.id_to_token(id)?.to_string(), id)))
+            .collect()
+    }
+
+    /// First grapheme cluster in `chars[from..to]` with an emoji table
+    /// entry, as its start, ID and length
+    pub(crate) fn next_emoji(&self, chars: &[char], from: usize, to: usize) -> Option<(usize, u32, usize)> {
+        if self.emoji_tokens.is_empty() {
+            return None;
+        }
+        (from..to).filter(|&i| !chars[i].is_alphanumeric()).find_map(|i| {
+            let rest: String = chars[i..to].iter().collect();
+            let cluster = rest.graphemes(true).next()?;
+            let id = self.emoji_tokens.get(&emoji_key(cluster))?;
+            Some((i, *id, cluster.chars().count()))
+        })
+    }
+
+    /// Re-add emoji table entries saved with their IDs
+    pub(crate) fn restore_emoji_tokens(&mut self, tokens: Vec<(String, u32)>) {
+        for (token, id) in tokens {
+            if !self.vocab.contains_key(&token) {
+                self.append_token(&token, Some(id));
+            }
+            Arc::make_mut(&mut self.emoji_tokens).insert(emoji_key(&token), id);
+        }
+        self.word_cache.clear();
+    }
+
+    pub(crate) fn save_emoji_tokens(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: serde_json::Map<String, serde_json::Value> =
+            self.emoji_tokens().into_iter().map(|(token, id)| (token, id.into())).collect();
+        fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn load_emoji_tokens(&mut self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: HashMap<String, u32> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        self.restore_emoji_tokens(entries.into_iter().collect());
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! Options for `encode_with_options` and `encode_batch_with_options`,
+//! gathered in one struct so new ones can be added without changing the
+//! methods' signatures.
+
+use rayon::prelude::*;
+
+use crate::{EncodingResult, PaddingSide, PaddingStrategy, TruncationStrategy, TurkishTokenizer, TurkishTokenizerError};
+
+/// How texts are encoded: special tokens, truncation and padding. The
+/// default encodes like `encode_plus`.
+///
+/// ```
+/// use turkish_tokenizer::{EncodeOptions, PaddingStrategy, TruncationStrategy, TurkishTokenizer};
+///
+/// let tokenizer = TurkishTokenizer::new().unwrap();
+/// let options = EncodeOptions::new()
+///     .max_length(16)
+///     .truncation(TruncationStrategy::OnlySecond)
+///     .padding(PaddingStrategy::MaxLength);
+/// let encoding = tokenizer
+///     .encode_with_options("Kim yazdı?", Some("Bu kitabı geçen yıl Orhan Pamuk yazdı."), &options)
+///     .unwrap();
+/// assert_eq!(encoding.input_ids.len(), 16);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    add_special_tokens: bool,
+    max_length: Option<usize>,
+    truncation: Option<TruncationStrategy>,
+    padding: PaddingStrategy,
+    padding_side: PaddingSide,
+}
+
+impl EncodeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frame single texts with the post-processor's special tokens
+    /// (default `false`); pairs always get the pair template, as with
+    /// `encode_pair`
+    pub fn add_special_tokens(mut self, enabled: bool) -> Self {
+        self.add_special_tokens = enabled;
+        self
+    }
+
+    /// Length to truncate and pad to (default the tokenizer's
+    /// `model_max_length`)
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Cut sequences longer than the max length with `strategy` (default
+    /// no truncation)
+    pub fn truncation(mut self, strategy: TruncationStrategy) -> Self {
+        self.truncation = Some(strategy);
+        self
+    }
+
+    /// Pad to the longest sequence of a batch or to the max length (default
+    /// `PaddingStrategy::DoNotPad`)
+    pub fn padding(mut self, strategy: PaddingStrategy) -> Self {
+        self.padding = strategy;
+        self
+    }
+
+    /// Which end receives padding (default `PaddingSide::Right`)
+    pub fn padding_side(mut self, side: PaddingSide) -> Self {
+        self.padding_side = side;
+        self
+    }
+}
+
+impl TurkishTokenizer {
+    /// Encode `text`, or the pair of `text` and `text_pair`, as `options`
+    /// say. Fails if truncation cannot fit the max length, or padding to
+    /// the max length has none to pad to.
+    pub fn encode_with_options(
+        &self,
+        text: &str,
+        text_pair: Option<&str>,
+        options: &EncodeOptions,
+    ) -> Result<EncodingResult, TurkishTokenizerError> {
+        let mut encoding = self.encode_unpadded(text, text_pair, options)?;
+        if let Some(length) = self.pad_length(options, encoding.input_ids.len())? {
+            encoding.pad(length, self.pad_token_id, &self.pad_token, options.padding_side);
+        }
+        Ok(encoding)
+    }
+
+    /// `encode_with_options` for many texts, or pairs when `text_pairs` is
+    /// given, in parallel across the rayon thread pool.
+    /// `PaddingStrategy::Longest` pads to the longest in the batch.
+    pub fn encode_batch_with_options(
+        &self,
+        texts: &[&str],
+        text_pairs: Option<&[&str]>,
+        options: &EncodeOptions,
+    ) -> Result<Vec<EncodingResult>, TurkishTokenizerError> {
+        if text_pairs.is_some_and(|pairs| pairs.len() != texts.len()) {
+            return Err(TurkishTokenizerError::InvalidConfig(
+                "texts and text pairs must have the same length".to_string(),
+            ));
+        }
+        let mut encodings: Vec<EncodingResult> = (0..texts.len())
+            .into_par_iter()
+            .map(|i| self.encode_unpadded(texts[i], text_pairs.map(|pairs| pairs[i]), options))
+            .collect::<Result<_, _>>()?;
+        let longest = encodings.iter().map(|e| e.input_ids.len()).max().unwrap_or(0);
+        if let Some(length) = self.pad_length(options, longest)? {
+            for encoding in &mut encodings {
+                encoding.pad(length, self.pad_token_id, &self.pad_token, options.padding_side);
+            }
+        }
+        Ok(encodings)
+    }
+
+    fn encode_unpadded(
+        &self,
+        text: &str,
+        text_pair: Option<&str>,
+        options: &EncodeOptions,
+    ) -> Result<EncodingResult, TurkishTokenizerError> {
+        let truncation = options.truncation.zip(options.max_length.or(self.model_max_length));
+        match (text_pair, truncation) {
+            (Some(pair), Some((strategy, max_length))) => self.encode_pair_truncated(text, pair, max_length, strategy),
+            (Some(pair), None) => Ok(self.encode_pair(text, pair)),
+            (None, Some((strategy, max_length))) => {
+                self.encode_plus_truncated(text, options.add_special_tokens, max_length, strategy)
+            }
+            (None, None) => Ok(self.encode_plus_with(text, options.add_special_tokens)),
+        }
+    }
+
+    /// Length the padding strategy pads to, given the longest sequence
+    fn pad_length(&self, options: &EncodeOptions, longest: usize) -> Result<Option<usize>, TurkishTokenizerError> {
+        match options.padding {
+            PaddingStrategy::DoNotPad => Ok(None),
+            PaddingStrategy::Longest => Ok(Some(longest)),
+            PaddingStrategy::MaxLength => options.max_length.or(self.model_max_length).map(Some).ok_or_else(|| {
+                TurkishTokenizerError::InvalidConfig(
+                    "Padding to max_length requires max_length or model_max_length".to_string(),
+                )
+            }),
+        }
+    }
+}
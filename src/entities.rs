@@ -0,0 +1,239 @@
+//! Rule-based detection of URLs, e-mail addresses, @mentions and
+//! #hashtags before segmentation.
+//!
+//! Run through the root and suffix tables, such strings come out as
+//! nonsensical morphological splits ("www" + "." + "haber" + "ler" ...).
+//! Each kind can instead become a single placeholder token or be matched
+//! against the BPE table alone. An entity is recognised at the start of a
+//! word, after any opening brackets or quotes, and ends before trailing
+//! punctuation or an apostrophe, so "@ahmet'e" is the mention "@ahmet"
+//! followed by the normally segmented "'e".
+
+use serde::{Deserialize, Serialize};
+
+use crate::matcher::PrefixMatcher;
+use crate::{cluster_ends, Token, TokenType, TurkishTokenizer, TurkishTokenizerError, UnknownStrategy, WordPiece};
+
+/// Kinds of strings the entity rules recognise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    /// `http://`, `https://`, `ftp://` or `www.` addresses
+    Url,
+    Email,
+    /// `@` followed by a user name
+    Mention,
+    /// `#` followed by a tag
+    Hashtag,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 4] = [EntityKind::Url, EntityKind::Email, EntityKind::Mention, EntityKind::Hashtag];
+
+    /// Token emitted for this kind in `EntityMode::Placeholder`
+    pub const fn placeholder(self) -> &'static str {
+        match self {
+            EntityKind::Url => "<url>",
+            EntityKind::Email => "<email>",
+            EntityKind::Mention => "<mention>",
+            EntityKind::Hashtag => "<hashtag>",
+        }
+    }
+}
+
+/// How a recognised entity is tokenized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityMode {
+    /// Like any other word, through the root, suffix and BPE tables
+    #[default]
+    Segment,
+    /// One placeholder token such as `<url>`; the text is not recoverable
+    Placeholder,
+    /// Matched against the BPE table, without lowercasing or case markers
+    /// and without morphological splits. Characters BPE lacks use their
+    /// single-character entry, else the unknown strategy.
+    Bpe,
+}
+
+/// Entity mode of each kind, as stored in `tokenizer_config.json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EntityModes {
+    pub url: EntityMode,
+    pub email: EntityMode,
+    pub mention: EntityMode,
+    pub hashtag: EntityMode,
+}
+
+impl EntityModes {
+    pub fn get(&self, kind: EntityKind) -> EntityMode {
+        match kind {
+            EntityKind::Url => self.url,
+            EntityKind::Email => self.email,
+            EntityKind::Mention => self.mention,
+            EntityKind::Hashtag => self.hashtag,
+        }
+    }
+
+    pub fn set(&mut self, kind: EntityKind, mode: EntityMode) {
+        match kind {
+            EntityKind::Url => self.url = mode,
+            EntityKind::Email => self.email = mode,
+            EntityKind::Mention => self.mention = mode,
+            EntityKind::Hashtag => self.hashtag = mode,
+        }
+    }
+
+    /// Whether every kind is segmented like ordinary text
+    pub fn is_default(&self) -> bool {
+        *self == EntityModes::default()
+    }
+}
+
+/// Brackets and quotes skipped before an entity
+const OPENERS: &[char] = &['(', '[', '{', '<', '"', '\'', '«', '“', '‘'];
+
+/// Punctuation that ends a sentence or clause rather than an entity
+const TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '>', '"', '…'];
+
+/// Characters a URL may contain besides letters and digits
+const URL_CHARS: &str = "-._~:/?#[]@!$&()*+,;=%";
+
+fn starts_with_ignore_case(chars: &[char], prefix: &str) -> bool {
+    chars.len() >= prefix.len() && chars.iter().zip(prefix.chars()).all(|(c, p)| c.to_ascii_lowercase() == p)
+}
+
+/// Length of `chars[..len]` without trailing punctuation
+fn trim_trailing(chars: &[char], mut len: usize) -> usize {
+    while len > 0 && TRAILING.contains(&chars[len - 1]) {
+        len -= 1;
+    }
+    len
+}
+
+fn url_len(chars: &[char]) -> Option<usize> {
+    let scheme = ["http://", "https://", "ftp://", "www."]
+        .into_iter()
+        .find(|scheme| starts_with_ignore_case(chars, scheme))?;
+    let len = chars.iter().take_while(|&&c| c.is_alphanumeric() || URL_CHARS.contains(c)).count();
+    let len = trim_trailing(chars, len);
+    (len > scheme.len()).then_some(len)
+}
+
+fn email_len(chars: &[char]) -> Option<usize> {
+    let local = chars
+        .iter()
+        .take_while(|&&c| c.is_alphanumeric() || "._%+-".contains(c))
+        .count();
+    if local == 0 || chars.get(local) != Some(&'@') {
+        return None;
+    }
+    let domain = &chars[local + 1..];
+    let len = domain.iter().take_while(|&&c| c.is_alphanumeric() || c == '-' || c == '.').count();
+    let len = trim_trailing(domain, len);
+    let labels: Vec<&[char]> = domain[..len].split(|&c| c == '.').collect();
+    let tld = labels.last()?;
+    let valid = labels.len() >= 2 && labels.iter().all(|label| !label.is_empty()) && tld.len() >= 2;
+    (valid && tld.iter().all(|c| c.is_alphabetic())).then_some(local + 1 + len)
+}
+
+/// Length of `sigil` followed by a name of letters, digits, `_` and
+/// inner dots
+fn tagged_len(chars: &[char], sigil: char) -> Option<usize> {
+    if chars.first() != Some(&sigil) {
+        return None;
+    }
+    let name = chars[1..].iter().take_while(|&&c| c.is_alphanumeric() || c == '_' || c == '.').count();
+    let len = trim_trailing(chars, 1 + name);
+    (len > 1).then_some(len)
+}
+
+impl TurkishTokenizer {
+    /// How each kind of entity is tokenized
+    pub fn entity_modes(&self) -> EntityModes {
+        self.entity_modes
+    }
+
+    /// Change how one kind of entity is tokenized, claiming a reserved slot
+    /// for its placeholder when switching to `EntityMode::Placeholder`
+    pub fn set_entity_mode(&mut self, kind: EntityKind, mode: EntityMode) -> Result<(), TurkishTokenizerError> {
+        let marker = &self.entity_markers[kind as usize];
+        if mode == EntityMode::Placeholder && marker.is_none() {
+            self.entity_markers[kind as usize] = Some(self.claim_marker(kind.placeholder())?);
+        }
+        self.entity_modes.set(kind, mode);
+        self.word_cache.clear();
+        Ok(())
+    }
+
+    /// Apply every mode of `modes`
+    pub(crate) fn set_entity_modes(&mut self, modes: EntityModes) -> Result<(), TurkishTokenizerError> {
+        for kind in EntityKind::ALL {
+            self.set_entity_mode(kind, modes.get(kind))?;
+        }
+        Ok(())
+    }
+
+    /// Placeholder tokens claimed for entities
+    pub(crate) fn entity_markers(&self) -> impl Iterator<Item = &Token> {
+        self.entity_markers.iter().flatten()
+    }
+
+    /// Start, length and kind of the entity at the start of `word`, after
+    /// any opening brackets or quotes, if its kind is not segmented like
+    /// ordinary text
+    pub(crate) fn find_entity(&self, word: &[char]) -> Option<(usize, usize, EntityKind)> {
+        if self.entity_modes.is_default() {
+            return None;
+        }
+        let start = word.iter().take_while(|c| OPENERS.contains(c)).count();
+        let rest = &word[start..];
+        let (kind, len) = url_len(rest)
+            .map(|len| (EntityKind::Url, len))
+            .or_else(|| email_len(rest).map(|len| (EntityKind::Email, len)))
+            .or_else(|| tagged_len(rest, '@').map(|len| (EntityKind::Mention, len)))
+            .or_else(|| tagged_len(rest, '#').map(|len| (EntityKind::Hashtag, len)))?;
+        (self.entity_modes.get(kind) != EntityMode::Segment).then_some((start, len, kind))
+    }
+
+    /// Emit the pieces of an entity found by `find_entity` at `pos`
+    pub(crate) fn emit_entity(
+        &self,
+        entity: &[char],
+        kind: EntityKind,
+        pos: usize,
+        unknown_strategy: UnknownStrategy,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        if let (EntityMode::Placeholder, Some(marker)) =
+            (self.entity_modes.get(kind), &self.entity_markers[kind as usize])
+        {
+            emit(WordPiece::Marker { marker, pos, len: entity.len() });
+            return;
+        }
+        let clusters = cluster_ends(entity);
+        let mut i = 0;
+        while i < entity.len() {
+            if let Some((id, len)) = self.bpe_matcher.longest_prefix(&entity[i..]) {
+                emit(WordPiece::Matched {
+                    id,
+                    token_type: TokenType::Bpe,
+                    pos: pos + i,
+                    chars: &entity[i..i + len],
+                });
+                i += len;
+            } else if let Some((id, token_type)) = self.single_entry(&entity[i..clusters[i]]) {
+                emit(WordPiece::Matched {
+                    id,
+                    token_type,
+                    pos: pos + i,
+                    chars: &entity[i..clusters[i]],
+                });
+                i = clusters[i];
+            } else {
+                self.emit_unmatched(&entity[i..clusters[i]], pos + i, unknown_strategy, emit);
+                i = clusters[i];
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Error type shared by every fallible tokenizer operation.
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TurkishTokenizerError {
+    /// A marker the tokenizer relies on, such as `<unknown>` or `<pad>`,
+    /// is missing from a custom vocabulary
+    #[error("Special token '{0}' is missing from the vocabulary")]
+    MissingSpecialToken(String),
+    /// A vocabulary table, config file or added-token file is malformed
+    #[error("Failed to parse vocabulary: {0}")]
+    VocabParse(#[from] serde_json::Error),
+    /// A `to_bytes` snapshot or the embedded vocabulary is malformed
+    #[error("Failed to decode tokenizer snapshot: {0}")]
+    Snapshot(#[from] bincode::Error),
+    /// An ID that cannot be given to a new token
+    #[error("ID {0} is reserved for byte fallback")]
+    IdOutOfRange(u32),
+    /// A token string with no vocabulary entry
+    #[error("Unknown token '{0}'")]
+    UnknownToken(String),
+    /// A token that already has a different ID
+    #[error("'{token}' has ID {existing}, not {id}")]
+    ConflictingId { token: String, id: u32, existing: u32 },
+    /// Every reserved `special_*` slot has been claimed
+    #[error("No reserved special token slots left")]
+    NoSpecialSlots,
+    /// A template, special-token role or other setting is not valid
+    #[error("{0}")]
+    InvalidConfig(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,103 @@
+//! C bindings, built with the `ffi` feature. The matching declarations are
+//! in `include/turkish_tokenizer.h`, generated with
+//! `cbindgen --config cbindgen.toml --output include/turkish_tokenizer.h`.
+//!
+//! Text crosses the boundary as NUL-terminated UTF-8. Every buffer returned
+//! here is owned by Rust and must go back through the matching `ttk_free_*`
+//! function.
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use crate::TurkishTokenizer;
+
+/// Create a tokenizer with the embedded vocabulary. Returns null on failure.
+#[no_mangle]
+pub extern "C" fn ttk_new() -> *mut TurkishTokenizer {
+    match TurkishTokenizer::new() {
+        Ok(tokenizer) => Box::into_raw(Box::new(tokenizer)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Release a tokenizer created by `ttk_new`. Null is ignored.
+///
+/// # Safety
+///
+/// `tokenizer` must be null or a pointer returned by `ttk_new` that has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ttk_free(tokenizer: *mut TurkishTokenizer) {
+    if !tokenizer.is_null() {
+        drop(Box::from_raw(tokenizer));
+    }
+}
+
+/// Encode `text` and store the number of IDs in `out_len`. Returns null if
+/// an argument is null or `text` is not valid UTF-8. Release the IDs with
+/// `ttk_free_ids`.
+///
+/// # Safety
+///
+/// `tokenizer` must come from `ttk_new`, `text` must be a NUL-terminated
+/// string and `out_len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn ttk_encode(
+    tokenizer: *const TurkishTokenizer,
+    text: *const c_char,
+    out_len: *mut usize,
+) -> *mut u32 {
+    if tokenizer.is_null() || text.is_null() || out_len.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(text) = CStr::from_ptr(text).to_str() else {
+        return ptr::null_mut();
+    };
+    let ids = (*tokenizer).encode(text).into_boxed_slice();
+    *out_len = ids.len();
+    Box::into_raw(ids) as *mut u32
+}
+
+/// Release IDs returned by `ttk_encode`. Null is ignored.
+///
+/// # Safety
+///
+/// `ids` and `len` must be exactly what `ttk_encode` returned, and the IDs
+/// must not have been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ttk_free_ids(ids: *mut u32, len: usize) {
+    if !ids.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ids, len)));
+    }
+}
+
+/// Decode `len` IDs back to text. Returns null if an argument is null.
+/// Release the string with `ttk_free_string`.
+///
+/// # Safety
+///
+/// `tokenizer` must come from `ttk_new` and `ids` must point to `len`
+/// readable IDs.
+#[no_mangle]
+pub unsafe extern "C" fn ttk_decode(tokenizer: *const TurkishTokenizer, ids: *const u32, len: usize) -> *mut c_char {
+    if tokenizer.is_null() || (ids.is_null() && len > 0) {
+        return ptr::null_mut();
+    }
+    let ids = if len == 0 { &[][..] } else { std::slice::from_raw_parts(ids, len) };
+    // Byte fallback can decode to a literal NUL, which a C string cannot hold
+    let text = (*tokenizer).decode(ids).replace('\0', "");
+    CString::new(text).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Release a string returned by `ttk_decode`. Null is ignored.
+///
+/// # Safety
+///
+/// `text` must be null or a pointer returned by `ttk_decode` that has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ttk_free_string(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
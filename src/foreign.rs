@@ -0,0 +1,94 @@
+//! Passthrough for foreign words embedded in Turkish text.
+//!
+//! Code-switched text like "deploy ettik" or "feedback'leri" sends English
+//! words through the root and suffix tables, which split them into bogus
+//! morphemes ("de" + "ploy"). A word detected as foreign is matched
+//! against the BPE table only. Only the part before an apostrophe is
+//! checked, so the Turkish suffixes in "Google'da" are still segmented
+//! normally.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{TokenType, TurkishTokenizer, UnknownStrategy, WordPiece};
+
+/// How words are detected as foreign
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForeignWordMode {
+    /// Every word goes through the root and suffix tables
+    #[default]
+    Off,
+    /// Words with letters or letter pairs Turkish spelling does not use
+    /// (q, w, x, "ch", "ck", "gh", "ph", "sh", "th"), unless the whole word
+    /// is a root ("ishal")
+    Patterns,
+    /// Those words, and words the root and suffix tables cannot analyse:
+    /// their segmentation does not start with a root or needs BPE pieces
+    Dictionary,
+}
+
+const FOREIGN_LETTERS: &[char] = &['q', 'w', 'x'];
+
+const FOREIGN_PAIRS: &[[char; 2]] = &[['c', 'h'], ['c', 'k'], ['g', 'h'], ['p', 'h'], ['s', 'h'], ['t', 'h']];
+
+fn has_foreign_pattern(stem: &[char]) -> bool {
+    stem.iter().any(|c| FOREIGN_LETTERS.contains(&c.to_ascii_lowercase()))
+        || stem
+            .windows(2)
+            .any(|pair| FOREIGN_PAIRS.contains(&[pair[0].to_ascii_lowercase(), pair[1].to_ascii_lowercase()]))
+}
+
+impl TurkishTokenizer {
+    pub fn foreign_word_mode(&self) -> ForeignWordMode {
+        self.foreign_word_mode
+    }
+
+    /// Change how foreign words are detected and routed to BPE
+    pub fn set_foreign_word_mode(&mut self, mode: ForeignWordMode) {
+        self.foreign_word_mode = mode;
+        self.word_cache.clear();
+    }
+
+    /// Whether `word` is foreign under the current mode; see
+    /// `ForeignWordMode`
+    pub fn is_foreign_word(&self, word: &str) -> bool {
+        let chars: Vec<char> = word.chars().collect();
+        self.foreign_stem(&chars, &mut Vec::new()).is_some()
+    }
+
+    /// Length of the part of `word` before any apostrophe, if it is a
+    /// foreign word. `lowered` is scratch space.
+    pub(crate) fn foreign_stem(&self, word: &[char], lowered: &mut Vec<char>) -> Option<usize> {
+        if self.foreign_word_mode == ForeignWordMode::Off {
+            return None;
+        }
+        let len = word.iter().position(|&c| c == '\'' || c == '’').unwrap_or(word.len());
+        let stem = &word[..len];
+        if len < 2 || !stem.iter().all(|c| c.is_alphabetic()) {
+            return None;
+        }
+        if has_foreign_pattern(stem) {
+            let mut lower = Vec::with_capacity(len);
+            for &c in stem {
+                self.push_lower(c, &mut lower);
+            }
+            if !self.roots.contains_key(&lower.iter().collect::<String>()) {
+                return Some(len);
+            }
+        }
+        if self.foreign_word_mode != ForeignWordMode::Dictionary {
+            return None;
+        }
+        let mut first = true;
+        let mut analysed = true;
+        self.segment_pieces(stem, UnknownStrategy::Unknown, None, lowered, false, &mut |piece| match piece {
+            WordPiece::Uppercase(_) | WordPiece::Marker { .. } => {}
+            WordPiece::Matched { token_type, .. } => {
+                analysed &= if first { token_type == TokenType::Root } else { token_type != TokenType::Bpe };
+                first = false;
+            }
+            _ => analysed = false,
+        });
+        (!analysed).then_some(len)
+    }
+}
@@ -0,0 +1,29 @@
+//! Token frequency counts over a corpus, for data-driven vocabulary
+//! pruning and embedding initialization.
+
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::TurkishTokenizer;
+
+impl TurkishTokenizer {
+    /// How often each token ID occurs in the encodings of `texts`, without
+    /// BOS/EOS. Texts are encoded in parallel; IDs that never occur are
+    /// left out.
+    pub fn count_token_frequencies<'a>(&self, texts: impl Iterator<Item = &'a str> + Send) -> HashMap<u32, u64> {
+        texts
+            .par_bridge()
+            .fold(HashMap::new, |mut counts, text| {
+                for id in self.encode(text) {
+                    *counts.entry(id).or_default() += 1;
+                }
+                counts
+            })
+            .reduce(HashMap::new, |mut total, counts| {
+                for (id, count) in counts {
+                    *total.entry(id).or_default() += count;
+                }
+                total
+            })
+    }
+}
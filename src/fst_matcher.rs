@@ -0,0 +1,54 @@
+use fst::raw::Output;
+use fst::Map;
+use std::collections::HashMap;
+
+use crate::matcher::PrefixMatcher;
+
+/// Vocabulary table compiled into a finite state transducer, walked byte by
+/// byte for longest-prefix matching
+#[derive(Debug, Clone)]
+pub(crate) struct FstMatcher {
+    map: Map<Vec<u8>>,
+}
+
+impl PrefixMatcher for FstMatcher {
+    fn from_table(table: &HashMap<String, u32>) -> Self {
+        let mut entries: Vec<(&String, &u32)> = table.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+        let map = Map::from_iter(entries.into_iter().map(|(key, &id)| (key, u64::from(id))))
+            .expect("keys are sorted and unique");
+        FstMatcher { map }
+    }
+
+    fn longest_prefix(&self, chars: &[char]) -> Option<(u32, usize)> {
+        let fst = self.map.as_fst();
+        let mut node = fst.root();
+        let mut output = Output::zero();
+        let mut best = None;
+        let mut buf = [0u8; 4];
+        for (i, c) in chars.iter().enumerate() {
+            for &byte in c.encode_utf8(&mut buf).as_bytes() {
+                let Some(index) = node.find_input(byte) else {
+                    return best;
+                };
+                let transition = node.transition(index);
+                output = output.cat(transition.out);
+                node = fst.node(transition.addr);
+            }
+            if node.is_final() {
+                let id = output.cat(node.final_output()).value() as u32;
+                best = Some((id, i + 1));
+            }
+        }
+        best
+    }
+
+    fn replace(&mut self, table: &HashMap<String, u32>, _removed: &str, _added: &str, _id: u32) {
+        // Transducers are immutable, so rebuild from the updated table
+        *self = Self::from_table(table);
+    }
+
+    fn add(&mut self, table: &HashMap<String, u32>, _added: &str, _id: u32) {
+        *self = Self::from_table(table);
+    }
+}
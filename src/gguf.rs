@@ -0,0 +1,216 @@
+//! Export of the vocabulary as GGUF `tokenizer.ggml.*` metadata for
+//! llama.cpp.
+//!
+//! The file holds metadata only, no tensors, in the layout llama.cpp reads
+//! for a SentencePiece-style (`llama`) vocabulary: one token string, score
+//! and token type per ID. It is meant to be merged into a converted
+//! model's GGUF so the embedding rows line up with this tokenizer's IDs.
+//!
+//! llama.cpp's own tokenizer will not reproduce the root/suffix priority or
+//! the case markers; scores only steer it towards lower IDs, which follow
+//! the table priority. Spaces are written as `▁`, the SentencePiece space,
+//! and IDs missing from the vocabulary get unused placeholder tokens.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{TurkishTokenizer, TurkishTokenizerError};
+
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+const GGUF_VERSION: u32 = 3;
+const GGUF_ALIGNMENT: u64 = 32;
+
+// Metadata value types
+const GGUF_TYPE_UINT32: u32 = 4;
+const GGUF_TYPE_INT32: u32 = 5;
+const GGUF_TYPE_FLOAT32: u32 = 6;
+const GGUF_TYPE_BOOL: u32 = 7;
+const GGUF_TYPE_STRING: u32 = 8;
+const GGUF_TYPE_ARRAY: u32 = 9;
+
+/// llama.cpp `llama_token_type` values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GgufTokenType {
+    Normal = 1,
+    Unknown = 2,
+    Control = 3,
+    UserDefined = 4,
+    Unused = 5,
+    Byte = 6,
+}
+
+enum MetadataValue {
+    Uint32(u32),
+    Bool(bool),
+    String(String),
+    Strings(Vec<String>),
+    Float32s(Vec<f32>),
+    Int32s(Vec<i32>),
+}
+
+/// Counts the bytes written so the file can be padded to the alignment
+struct GgufWriter<W: Write> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> GgufWriter<W> {
+    fn bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.inner.write_all(bytes)?;
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn u32(&mut self, value: u32) -> io::Result<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn u64(&mut self, value: u64) -> io::Result<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn string(&mut self, value: &str) -> io::Result<()> {
+        self.u64(value.len() as u64)?;
+        self.bytes(value.as_bytes())
+    }
+
+    fn array_header(&mut self, element_type: u32, len: usize) -> io::Result<()> {
+        self.u32(GGUF_TYPE_ARRAY)?;
+        self.u32(element_type)?;
+        self.u64(len as u64)
+    }
+
+    fn value(&mut self, value: &MetadataValue) -> io::Result<()> {
+        match value {
+            MetadataValue::Uint32(value) => {
+                self.u32(GGUF_TYPE_UINT32)?;
+                self.u32(*value)
+            }
+            MetadataValue::Bool(value) => {
+                self.u32(GGUF_TYPE_BOOL)?;
+                self.bytes(&[u8::from(*value)])
+            }
+            MetadataValue::String(value) => {
+                self.u32(GGUF_TYPE_STRING)?;
+                self.string(value)
+            }
+            MetadataValue::Strings(values) => {
+                self.array_header(GGUF_TYPE_STRING, values.len())?;
+                values.iter().try_for_each(|value| self.string(value))
+            }
+            MetadataValue::Float32s(values) => {
+                self.array_header(GGUF_TYPE_FLOAT32, values.len())?;
+                values.iter().try_for_each(|value| self.bytes(&value.to_le_bytes()))
+            }
+            MetadataValue::Int32s(values) => {
+                self.array_header(GGUF_TYPE_INT32, values.len())?;
+                values.iter().try_for_each(|value| self.bytes(&value.to_le_bytes()))
+            }
+        }
+    }
+}
+
+impl TurkishTokenizer {
+    /// Token type llama.cpp should see for each ID, indexed by ID
+    fn gguf_token_types(&self, len: usize) -> Vec<GgufTokenType> {
+        let mut types = vec![GgufTokenType::Unused; len];
+        for (token, &id) in self.vocab.iter() {
+            types[id as usize] = if token.starts_with("special_") && self.roots.contains_key(token) {
+                GgufTokenType::Unused
+            } else {
+                GgufTokenType::Normal
+            };
+        }
+        for byte in 0..=255u32 {
+            types[(self.byte_offset + byte) as usize] = GgufTokenType::Byte;
+        }
+        for (_, id) in &self.user_tokens {
+            types[*id as usize] = GgufTokenType::UserDefined;
+        }
+
+        let markers = [
+            Some(&self.uppercase_marker),
+            self.all_caps_marker.as_ref(),
+            self.number_marker.as_ref(),
+        ];
+        let control_ids = markers.into_iter().flatten().chain(self.entity_markers()).map(|marker| marker.id).chain([
+            self.pad_token_id,
+            self.eos_token_id,
+            self.bos_token_id,
+            self.sep_token_id,
+            self.cls_token_id,
+            self.mask_token_id,
+        ]);
+        for id in control_ids {
+            types[id as usize] = GgufTokenType::Control;
+        }
+        for added in &self.added_tokens {
+            types[added.token.id as usize] = if added.special {
+                GgufTokenType::Control
+            } else {
+                GgufTokenType::UserDefined
+            };
+        }
+        types[self.unknown_marker.id as usize] = GgufTokenType::Unknown;
+        types
+    }
+
+    fn gguf_metadata(&self) -> Vec<(&'static str, MetadataValue)> {
+        let len = self.vocab.values().max().map_or(0, |&max_id| max_id as usize + 1);
+        let types = self.gguf_token_types(len);
+        let tokens = (0..len as u32)
+            .map(|id| match self.id_to_token(id) {
+                Some(token) if types[id as usize] != GgufTokenType::Unused => token.replace(' ', "▁"),
+                _ => format!("[UNUSED_{}]", id),
+            })
+            .collect();
+        // Lower IDs win, which keeps roots ahead of suffixes and BPE tokens
+        let scores = (0..len).map(|id| -(id as f32)).collect();
+
+        vec![
+            ("tokenizer.ggml.model", MetadataValue::String("llama".to_string())),
+            ("tokenizer.ggml.tokens", MetadataValue::Strings(tokens)),
+            ("tokenizer.ggml.scores", MetadataValue::Float32s(scores)),
+            (
+                "tokenizer.ggml.token_type",
+                MetadataValue::Int32s(types.into_iter().map(|t| t as i32).collect()),
+            ),
+            ("tokenizer.ggml.bos_token_id", MetadataValue::Uint32(self.bos_token_id)),
+            ("tokenizer.ggml.eos_token_id", MetadataValue::Uint32(self.eos_token_id)),
+            ("tokenizer.ggml.unknown_token_id", MetadataValue::Uint32(self.unknown_marker.id)),
+            ("tokenizer.ggml.padding_token_id", MetadataValue::Uint32(self.pad_token_id)),
+            // Spelled as llama.cpp spells it
+            ("tokenizer.ggml.seperator_token_id", MetadataValue::Uint32(self.sep_token_id)),
+            ("tokenizer.ggml.cls_token_id", MetadataValue::Uint32(self.cls_token_id)),
+            ("tokenizer.ggml.mask_token_id", MetadataValue::Uint32(self.mask_token_id)),
+            ("tokenizer.ggml.add_bos_token", MetadataValue::Bool(self.add_bos_token)),
+            ("tokenizer.ggml.add_eos_token", MetadataValue::Bool(self.add_eos_token)),
+            ("tokenizer.ggml.add_space_prefix", MetadataValue::Bool(false)),
+        ]
+    }
+
+    /// Write the vocabulary and special tokens to `path` as a tensor-free
+    /// GGUF file holding llama.cpp's `tokenizer.ggml.*` metadata
+    pub fn export_gguf_vocab(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let metadata = self.gguf_metadata();
+        let mut writer = GgufWriter {
+            inner: BufWriter::new(File::create(path)?),
+            written: 0,
+        };
+
+        writer.bytes(GGUF_MAGIC)?;
+        writer.u32(GGUF_VERSION)?;
+        writer.u64(0)?; // tensor count
+        writer.u64(metadata.len() as u64)?;
+        for (key, value) in &metadata {
+            writer.string(key)?;
+            writer.value(value)?;
+        }
+        // The (empty) tensor data section starts at the next aligned offset
+        let padding = (GGUF_ALIGNMENT - writer.written % GGUF_ALIGNMENT) % GGUF_ALIGNMENT;
+        writer.bytes(&vec![0; padding as usize])?;
+        writer.inner.flush()?;
+        Ok(())
+    }
+}
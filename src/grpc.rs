@@ -0,0 +1,211 @@
+//! gRPC tokenization service, built with the `grpc` feature from
+//! `proto/turkish_tokenizer.proto`.
+//!
+//! Requests go through the same encode, tokenize and decode functions as
+//! the HTTP service in `server`, on tokio's blocking pool. The streaming
+//! RPCs answer each batch in the order it arrived. Requests are recorded in
+//! the HTTP service's `/metrics` as `grpc.TokenizeBatch` and
+//! `grpc.DecodeBatch`, one per batch or single request.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::server::{self, Batchable, DecodeRequest, EncodeRequest, TokenizeRequest};
+use crate::{TokenType, TurkishTokenizer};
+
+pub mod proto {
+    tonic::include_proto!("turkish_tokenizer.v1");
+}
+
+use proto::tokenizer_server::{Tokenizer, TokenizerServer};
+
+/// Responses buffered per stream before the server waits for the client
+const STREAM_BUFFER: usize = 16;
+
+impl From<crate::Token> for proto::Token {
+    fn from(token: crate::Token) -> Self {
+        let token_type = match token.token_type {
+            TokenType::Root => proto::TokenType::Root,
+            TokenType::Suffix => proto::TokenType::Suffix,
+            TokenType::Bpe => proto::TokenType::Bpe,
+            TokenType::Byte => proto::TokenType::Byte,
+        };
+        proto::Token {
+            token: token.token,
+            id: token.id,
+            token_type: token_type as i32,
+            start: token.start as u64,
+            end: token.end as u64,
+            word_id: token.word_id.map(|id| id as u64),
+        }
+    }
+}
+
+fn tokenize_batch(tokenizer: &TurkishTokenizer, request: proto::TokenizeBatchRequest) -> proto::TokenizeBatchResponse {
+    let started = Instant::now();
+    let encode_request = EncodeRequest {
+        text: Batchable::Many(request.texts),
+        add_special_tokens: request.add_special_tokens,
+    };
+    let ids = server::encode(tokenizer, &encode_request).ids.into_vec();
+    let mut tokens = if request.return_tokens {
+        let tokenize_request = TokenizeRequest {
+            text: encode_request.text,
+        };
+        server::tokenize(tokenizer, &tokenize_request).tokens.into_vec()
+    } else {
+        Vec::new()
+    };
+    tokens.resize_with(ids.len(), Vec::new);
+    server::record_request("grpc.TokenizeBatch", tokenizer, started, ids.len(), ids.iter().flatten());
+
+    proto::TokenizeBatchResponse {
+        results: ids
+            .into_iter()
+            .zip(tokens)
+            .map(|(ids, tokens)| proto::TokenizeResponse {
+                ids,
+                tokens: tokens.into_iter().map(proto::Token::from).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn decode_batch(tokenizer: &TurkishTokenizer, request: proto::DecodeBatchRequest) -> proto::DecodeBatchResponse {
+    let started = Instant::now();
+    let decode_request = DecodeRequest {
+        ids: Batchable::Many(request.sequences.into_iter().map(|sequence| sequence.ids).collect()),
+        skip_special_tokens: request.skip_special_tokens,
+    };
+    let texts = server::decode(tokenizer, &decode_request).text.into_vec();
+    let ids = decode_request.ids.as_slice();
+    server::record_request("grpc.DecodeBatch", tokenizer, started, ids.len(), ids.iter().flatten());
+    proto::DecodeBatchResponse { texts }
+}
+
+/// `Tokenizer` gRPC service sharing one tokenizer across requests
+pub struct TokenizerService {
+    tokenizer: Arc<TurkishTokenizer>,
+}
+
+impl TokenizerService {
+    pub fn new(tokenizer: Arc<TurkishTokenizer>) -> Self {
+        Self { tokenizer }
+    }
+
+    pub fn into_server(self) -> TokenizerServer<Self> {
+        TokenizerServer::new(self)
+    }
+
+    async fn run<R: Send + 'static>(&self, f: impl FnOnce(&TurkishTokenizer) -> R + Send + 'static) -> Result<R, Status> {
+        server::run_blocking(self.tokenizer.clone(), f)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+
+    /// Answer each message of `requests` with `f`, in order
+    fn stream<Req, Resp>(
+        &self,
+        mut requests: Streaming<Req>,
+        f: fn(&TurkishTokenizer, Req) -> Resp,
+    ) -> ReceiverStream<Result<Resp, Status>>
+    where
+        Req: Send + 'static,
+        Resp: Send + 'static,
+    {
+        let tokenizer = self.tokenizer.clone();
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                let response = match requests.message().await {
+                    Ok(Some(request)) => server::run_blocking(tokenizer.clone(), move |tokenizer| f(tokenizer, request))
+                        .await
+                        .map_err(|e| Status::internal(e.to_string())),
+                    Ok(None) => break,
+                    Err(status) => Err(status),
+                };
+                let failed = response.is_err();
+                if sender.send(response).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        ReceiverStream::new(receiver)
+    }
+}
+
+#[tonic::async_trait]
+impl Tokenizer for TokenizerService {
+    async fn tokenize(
+        &self,
+        request: Request<proto::TokenizeRequest>,
+    ) -> Result<Response<proto::TokenizeResponse>, Status> {
+        let request = request.into_inner();
+        let batch = proto::TokenizeBatchRequest {
+            texts: vec![request.text],
+            add_special_tokens: request.add_special_tokens,
+            return_tokens: request.return_tokens,
+        };
+        let mut response = self.run(move |tokenizer| tokenize_batch(tokenizer, batch)).await?;
+        Ok(Response::new(response.results.remove(0)))
+    }
+
+    async fn tokenize_batch(
+        &self,
+        request: Request<proto::TokenizeBatchRequest>,
+    ) -> Result<Response<proto::TokenizeBatchResponse>, Status> {
+        let request = request.into_inner();
+        Ok(Response::new(self.run(move |tokenizer| tokenize_batch(tokenizer, request)).await?))
+    }
+
+    async fn decode(&self, request: Request<proto::DecodeRequest>) -> Result<Response<proto::DecodeResponse>, Status> {
+        let request = request.into_inner();
+        let batch = proto::DecodeBatchRequest {
+            sequences: vec![proto::Ids { ids: request.ids }],
+            skip_special_tokens: request.skip_special_tokens,
+        };
+        let mut response = self.run(move |tokenizer| decode_batch(tokenizer, batch)).await?;
+        Ok(Response::new(proto::DecodeResponse {
+            text: response.texts.remove(0),
+        }))
+    }
+
+    async fn decode_batch(
+        &self,
+        request: Request<proto::DecodeBatchRequest>,
+    ) -> Result<Response<proto::DecodeBatchResponse>, Status> {
+        let request = request.into_inner();
+        Ok(Response::new(self.run(move |tokenizer| decode_batch(tokenizer, request)).await?))
+    }
+
+    type TokenizeBatchStreamStream = ReceiverStream<Result<proto::TokenizeBatchResponse, Status>>;
+
+    async fn tokenize_batch_stream(
+        &self,
+        request: Request<Streaming<proto::TokenizeBatchRequest>>,
+    ) -> Result<Response<Self::TokenizeBatchStreamStream>, Status> {
+        Ok(Response::new(self.stream(request.into_inner(), tokenize_batch)))
+    }
+
+    type DecodeBatchStreamStream = ReceiverStream<Result<proto::DecodeBatchResponse, Status>>;
+
+    async fn decode_batch_stream(
+        &self,
+        request: Request<Streaming<proto::DecodeBatchRequest>>,
+    ) -> Result<Response<Self::DecodeBatchStreamStream>, Status> {
+        Ok(Response::new(self.stream(request.into_inner(), decode_batch)))
+    }
+}
+
+/// Serve the gRPC service on `listener` until the process stops
+pub async fn serve(listener: TcpListener, tokenizer: Arc<TurkishTokenizer>) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(TokenizerService::new(tokenizer).into_server())
+        .serve_with_incoming(TcpListenerStream::new(listener))
+        .await
+}
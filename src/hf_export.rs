@@ -0,0 +1,104 @@
+//! Export to the Hugging Face `tokenizers` `tokenizer.json` format.
+//!
+//! The standard `tokenizers` components cannot express the three-table
+//! priority matching or the `<uppercase>` markers, so the export is an
+//! approximation built from:
+//!
+//! - a normalizer applying Turkish lowercasing (`İ` → `i`, `I` → `ı`),
+//! - a pre-tokenizer isolating spaces, which are tokens in this vocabulary,
+//! - a `WordPiece` model without a continuation prefix, doing greedy
+//!   longest-match over the combined vocabulary,
+//! - a `Fuse` decoder, since spaces are explicit tokens.
+//!
+//! Greedy matching over the combined vocabulary ignores the root/suffix/BPE
+//! priority, so IDs can differ from `TurkishTokenizer::encode` for longer
+//! words. Case markers, byte fallback and vowel-harmony-aware decoding are
+//! not reproduced.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+use crate::{TurkishTokenizer, TurkishTokenizerError};
+
+pub const HF_TOKENIZER_FILE: &str = "tokenizer.json";
+
+impl TurkishTokenizer {
+    /// Build a `tokenizers`-compatible `tokenizer.json` document
+    pub fn export_hf_tokenizer_json(&self) -> Value {
+        let mut entries: Vec<(&String, &u32)> = self.vocab.iter().collect();
+        entries.sort_by(|a, b| a.1.cmp(b.1).then_with(|| a.0.cmp(b.0)));
+        let mut vocab = serde_json::Map::new();
+        for (token, &id) in entries {
+            vocab.insert(token.clone(), id.into());
+        }
+
+        let mut special_tokens = vec![
+            (self.uppercase_marker.token.as_str(), self.uppercase_marker.id, true),
+            (self.unknown_marker.token.as_str(), self.unknown_marker.id, true),
+            (self.pad_token.as_str(), self.pad_token_id, true),
+            (self.eos_token.as_str(), self.eos_token_id, true),
+            (self.bos_token.as_str(), self.bos_token_id, true),
+            (self.sep_token.as_str(), self.sep_token_id, true),
+            (self.cls_token.as_str(), self.cls_token_id, true),
+            (self.mask_token.as_str(), self.mask_token_id, true),
+        ];
+        special_tokens.extend(
+            self.added_tokens
+                .iter()
+                .map(|added| (added.token.token.as_str(), added.token.id, added.special)),
+        );
+        special_tokens.sort_by_key(|&(_, id, _)| id);
+        special_tokens.dedup_by(|a, b| (a.0, a.1) == (b.0, b.1));
+        let added_tokens: Vec<Value> = special_tokens
+            .into_iter()
+            .map(|(content, id, special)| {
+                json!({
+                    "id": id,
+                    "content": content,
+                    "single_word": false,
+                    "lstrip": false,
+                    "rstrip": false,
+                    "normalized": false,
+                    "special": special,
+                })
+            })
+            .collect();
+
+        json!({
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": added_tokens,
+            "normalizer": {
+                "type": "Sequence",
+                "normalizers": [
+                    {"type": "Replace", "pattern": {"String": "İ"}, "content": "i"},
+                    {"type": "Replace", "pattern": {"String": "I"}, "content": "ı"},
+                    {"type": "Lowercase"},
+                ],
+            },
+            "pre_tokenizer": {
+                "type": "Split",
+                "pattern": {"String": " "},
+                "behavior": "Isolated",
+                "invert": false,
+            },
+            "post_processor": null,
+            "decoder": {"type": "Fuse"},
+            "model": {
+                "type": "WordPiece",
+                "unk_token": self.unknown_marker.token,
+                "continuing_subword_prefix": "",
+                "max_input_chars_per_word": 100,
+                "vocab": vocab,
+            },
+        })
+    }
+
+    /// Write `export_hf_tokenizer_json` to `path`
+    pub fn save_hf_tokenizer_json(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        fs::write(path, serde_json::to_string_pretty(&self.export_hf_tokenizer_json())?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+//! Adapters for the Hugging Face `tokenizers` crate, built with the
+//! `tokenizers` feature.
+//!
+//! Unlike the `tokenizer.json` export, these run the real segmentation, so
+//! a `tokenizers` pipeline built from them produces the same IDs as
+//! `TurkishTokenizer::encode`:
+//!
+//! - `TurkishPreTokenizer` splits at the whitespace the tokenizer treats as
+//!   word separators, following its whitespace mode,
+//! - `TurkishModel` segments each piece into roots, suffixes, BPE tokens
+//!   and case markers, and also decodes with the vowel-harmony-aware
+//!   decoder.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tokenizers::normalizers::NormalizerWrapper;
+use tokenizers::processors::PostProcessorWrapper;
+use tokenizers::tokenizer::{
+    AddedToken, Decoder, Model, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior, Token, Trainer,
+    TokenizerImpl,
+};
+
+use crate::{TurkishTokenizer, WhitespaceMode, BPE_FILE, ROOTS_FILE, SUFFIXES_FILE};
+
+/// `tokenizers` pipeline running the Turkish pre-tokenizer, model and
+/// decoder
+pub type HfTokenizer =
+    TokenizerImpl<TurkishModel, NormalizerWrapper, TurkishPreTokenizer, PostProcessorWrapper, TurkishModel>;
+
+/// `tokenizers::Model` and `tokenizers::Decoder` backed by a
+/// `TurkishTokenizer`
+#[derive(Clone)]
+pub struct TurkishModel {
+    tokenizer: TurkishTokenizer,
+}
+
+impl TurkishModel {
+    pub fn new(tokenizer: TurkishTokenizer) -> Self {
+        Self { tokenizer }
+    }
+
+    pub fn tokenizer(&self) -> &TurkishTokenizer {
+        &self.tokenizer
+    }
+}
+
+impl Model for TurkishModel {
+    type Trainer = TurkishTrainer;
+
+    fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
+        // `tokenizers` offsets are in bytes, ours are in chars
+        let mut byte_offsets: Vec<usize> = sequence.char_indices().map(|(i, _)| i).collect();
+        byte_offsets.push(sequence.len());
+
+        Ok(self
+            .tokenizer
+            .tokenize_text(sequence)
+            .into_iter()
+            .map(|token| Token::new(token.id, token.token, (byte_offsets[token.start], byte_offsets[token.end])))
+            .collect())
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.tokenizer.token_to_id(token)
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.tokenizer.id_to_token(id).map(str::to_string)
+    }
+
+    fn get_vocab(&self) -> HashMap<String, u32> {
+        self.tokenizer.get_vocab().clone()
+    }
+
+    fn get_vocab_size(&self) -> usize {
+        self.tokenizer.vocab_size()
+    }
+
+    /// Write the root, suffix and BPE tables, named `{prefix}-kokler.json`
+    /// and so on when a prefix is given
+    fn save(&self, folder: &Path, prefix: Option<&str>) -> Result<Vec<PathBuf>> {
+        let tables = [
+            (ROOTS_FILE, self.tokenizer.base_roots()),
+            (SUFFIXES_FILE, (*self.tokenizer.suffixes).clone()),
+            (BPE_FILE, (*self.tokenizer.bpe_tokens).clone()),
+        ];
+        let mut paths = Vec::with_capacity(tables.len());
+        for (file, table) in tables {
+            let path = folder.join(prefix.map_or(file.to_string(), |prefix| format!("{prefix}-{file}")));
+            fs::write(&path, self.tokenizer.table_json(&table)?)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    fn get_trainer(&self) -> TurkishTrainer {
+        TurkishTrainer
+    }
+}
+
+impl Decoder for TurkishModel {
+    fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        let ids = self.tokenizer.convert_tokens_to_ids(&tokens);
+        Ok(vec![self.tokenizer.decode(&ids)])
+    }
+}
+
+/// `tokenizers::PreTokenizer` splitting text into words and whitespace the
+/// same way `TurkishTokenizer` does before segmenting words
+#[derive(Clone)]
+pub struct TurkishPreTokenizer {
+    tokenizer: TurkishTokenizer,
+}
+
+impl TurkishPreTokenizer {
+    pub fn new(tokenizer: TurkishTokenizer) -> Self {
+        Self { tokenizer }
+    }
+}
+
+impl PreTokenizer for TurkishPreTokenizer {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        let behavior = match self.tokenizer.whitespace_mode() {
+            WhitespaceMode::Collapse => SplitDelimiterBehavior::Contiguous,
+            WhitespaceMode::Space | WhitespaceMode::Preserve => SplitDelimiterBehavior::Isolated,
+        };
+        pretokenized.split(|_, normalized| {
+            normalized.split(|c: char| self.tokenizer.separator_token(c).is_some(), behavior)
+        })
+    }
+}
+
+/// Placeholder required by `Model::get_trainer`. The vocabulary is built
+/// from the morphological root and suffix lists, so it cannot be trained
+/// from a corpus.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TurkishTrainer;
+
+impl Trainer for TurkishTrainer {
+    type Model = TurkishModel;
+
+    fn should_show_progress(&self) -> bool {
+        false
+    }
+
+    fn train(&self, _model: &mut TurkishModel) -> Result<Vec<AddedToken>> {
+        Err("the Turkish vocabulary cannot be trained".into())
+    }
+
+    fn feed<I, S, F>(&mut self, _iterator: I, _process: F) -> Result<()>
+    where
+        I: Iterator<Item = S> + Send,
+        S: AsRef<str> + Send,
+        F: Fn(&str) -> Result<Vec<String>> + Sync,
+    {
+        Err("the Turkish vocabulary cannot be trained".into())
+    }
+}
+
+impl TurkishTokenizer {
+    /// `tokenizers` pipeline using this tokenizer's pre-tokenization,
+    /// segmentation and decoding. Special tokens added by `encode_with`
+    /// are not applied; attach a `tokenizers` post-processor for those.
+    pub fn to_hf_tokenizer(&self) -> HfTokenizer {
+        let mut hf_tokenizer = TokenizerImpl::new(TurkishModel::new(self.clone()));
+        hf_tokenizer
+            .with_pre_tokenizer(Some(TurkishPreTokenizer::new(self.clone())))
+            .with_decoder(Some(TurkishModel::new(self.clone())));
+        hf_tokenizer
+    }
+}
@@ -0,0 +1,113 @@
+//! Hyphens inside words: line-break hyphenation and hyphenated compounds.
+//!
+//! Text extracted from PDFs splits words across lines with a hyphen
+//! ("kitap-\nlar"), so the halves are matched as unrelated words. With
+//! line-break joining, a hyphen or soft hyphen followed by a newline
+//! between letters does not end the word, and the word is segmented with
+//! the break removed. Token offsets still point into the original text; a
+//! token matched across the break spans it.
+//!
+//! A hyphen between letters ("sosyo-ekonomik", "Türk-Alman") is otherwise
+//! just another character of the word, and the suffix state carries over
+//! it. With compound splitting each part is segmented as a word of its
+//! own, with the hyphen as a separate token.
+
+use crate::sampling::DropoutSampler;
+use crate::{TurkishTokenizer, UnknownStrategy, WordPiece};
+
+pub(crate) const SOFT_HYPHEN: char = '\u{ad}';
+
+/// Length of the line break at `word[i]`: a hyphen followed by a newline,
+/// or a soft hyphen followed by an optional one, between a letter and a
+/// lowercase letter
+pub(crate) fn line_break_len(word: &[char], i: usize) -> Option<usize> {
+    let hyphen = word[i];
+    if !matches!(hyphen, '-' | SOFT_HYPHEN) || i == 0 || !word[i - 1].is_alphabetic() {
+        return None;
+    }
+    let mut end = i + 1;
+    if word.get(end) == Some(&'\r') {
+        end += 1;
+    }
+    if word.get(end) == Some(&'\n') {
+        end += 1;
+    } else if hyphen == '-' {
+        return None;
+    }
+    word.get(end).is_some_and(|c| c.is_lowercase()).then_some(end - i)
+}
+
+impl TurkishTokenizer {
+    /// Whether hyphenated line breaks are joined
+    pub fn join_line_breaks(&self) -> bool {
+        self.join_line_breaks
+    }
+
+    /// Rejoin words hyphenated across lines ("kitap-\nlar" is segmented as
+    /// "kitaplar") and drop soft hyphens inside words
+    pub fn set_join_line_breaks(&mut self, enabled: bool) {
+        self.join_line_breaks = enabled;
+        self.word_cache.clear();
+    }
+
+    /// Whether hyphenated compounds are split into their parts
+    pub fn split_compounds(&self) -> bool {
+        self.split_compounds
+    }
+
+    /// Segment each part of a hyphenated compound ("sosyo-ekonomik") as a
+    /// separate word, with the hyphen as its own token
+    pub fn set_split_compounds(&mut self, enabled: bool) {
+        self.split_compounds = enabled;
+        self.word_cache.clear();
+    }
+
+    /// Whether the newline starting `after` continues a word hyphenated
+    /// at the end of `before`, so it does not separate words
+    pub(crate) fn joins_line_break(&self, before: &str, after: &str) -> bool {
+        if !self.join_line_breaks {
+            return false;
+        }
+        let before = before.strip_suffix('\r').unwrap_or(before);
+        let Some(before) = before.strip_suffix(['-', SOFT_HYPHEN]) else {
+            return false;
+        };
+        let after = after.strip_prefix('\r').unwrap_or(after);
+        let Some(after) = after.strip_prefix('\n') else {
+            return false;
+        };
+        before.chars().next_back().is_some_and(char::is_alphabetic) && after.chars().next().is_some_and(char::is_lowercase)
+    }
+
+    /// `segment_entities` on each part of a hyphenated compound when
+    /// compounds are split, with the hyphens segmented on their own.
+    /// Words holding an entity are left whole.
+    pub(crate) fn segment_compounds(
+        &self,
+        word: &[char],
+        unknown_strategy: UnknownStrategy,
+        mut sampler: Option<&mut DropoutSampler>,
+        lowered: &mut Vec<char>,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        if !self.split_compounds || self.find_entity(word).is_some() {
+            return self.segment_entities(word, unknown_strategy, sampler, lowered, emit);
+        }
+        let mut start = 0;
+        for i in 1..word.len().saturating_sub(1) {
+            if word[i] != '-' || !word[i - 1].is_alphabetic() || !word[i + 1].is_alphabetic() {
+                continue;
+            }
+            for (from, to) in [(start, i), (i, i + 1)] {
+                let sampler = sampler.as_deref_mut();
+                self.segment_entities(&word[from..to], unknown_strategy, sampler, lowered, &mut |piece| {
+                    emit(piece.shifted(from))
+                });
+            }
+            start = i + 1;
+        }
+        self.segment_entities(&word[start..], unknown_strategy, sampler, lowered, &mut |piece| {
+            emit(piece.shifted(start))
+        });
+    }
+}
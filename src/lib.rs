@@ -1,9 +1,170 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use unicode_segmentation::UnicodeSegmentation;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+use rayon::prelude::*;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[pyclass(eq, eq_int)]
+mod abbreviations;
+mod added_tokens;
+mod analysis;
+mod ascii_fold;
+mod builder;
+mod cache;
+mod casing;
+mod chat;
+mod clitics;
+mod coverage;
+mod deasciify;
+mod decode_stream;
+mod decoder;
+mod emoji;
+mod encode_options;
+mod entities;
+mod error;
+mod foreign;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod frequencies;
+mod gguf;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+mod hf_export;
+#[cfg(feature = "tokenizers")]
+mod hf_model;
+mod hyphenation;
+mod logit_bias;
+#[cfg(feature = "fst")]
+mod fst_matcher;
+mod matcher;
+#[cfg(feature = "uniffi")]
+mod mobile;
+mod morphology;
+mod pretrained;
+mod prune;
+#[cfg(feature = "python")]
+mod python;
+mod question_answering;
+mod sampling;
+mod sanitize;
+pub mod sentences;
+#[cfg(feature = "serve")]
+pub mod server;
+mod stop;
+mod stream;
+mod template;
+mod tiktoken;
+mod training;
+mod trie;
+mod truncation;
+mod unigram;
+mod user_tokens;
+mod visualize;
+mod vocab_index;
+mod vocab_info;
+mod vocab_tables;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use abbreviations::{ABBREVIATIONS_FILE, COMMON_ABBREVIATIONS};
+pub use added_tokens::ADDED_TOKENS_FILE;
+pub use analysis::{MorphAnalysis, PartOfSpeech, SuffixCategory, SuffixInfo};
+pub use builder::TurkishTokenizerBuilder;
+pub use cache::DEFAULT_WORD_CACHE_CAPACITY;
+pub use casing::{tr_lower, tr_upper, CaseMapping};
+pub use chat::{ChatMessage, ChatTemplate};
+pub use coverage::CoverageReport;
+pub use decode_stream::DecodeStream;
+pub use decoder::TurkishDecoder;
+pub use emoji::{COMMON_EMOJI, EMOJI_TOKENS_FILE};
+pub use encode_options::EncodeOptions;
+pub use entities::{EntityKind, EntityMode, EntityModes};
+pub use error::TurkishTokenizerError;
+pub use foreign::ForeignWordMode;
+pub use hf_export::HF_TOKENIZER_FILE;
+#[cfg(feature = "tokenizers")]
+pub use hf_model::{HfTokenizer, TurkishModel, TurkishPreTokenizer, TurkishTrainer};
+pub use prune::IdRemapping;
+pub use question_answering::QaEncoding;
+pub use sanitize::ControlCharMode;
+pub use stop::StopCriteria;
+pub use stream::TokenStream;
+pub use template::TemplateProcessing;
+pub use tiktoken::CoreBpe;
+pub use unigram::SCORES_FILE;
+pub use user_tokens::USER_TOKENS_FILE;
+pub use vocab_index::VocabIndex;
+pub use vocab_info::{ChangedId, VocabDiff, VocabInfo};
+// UniFFI scaffolding looks its type tag up at the crate root
+#[cfg(feature = "uniffi")]
+use mobile::UniFfiTag;
+pub use pretrained::{SpecialTokensMap, TokenizerConfig, SPECIAL_TOKENS_MAP_FILE, TOKENIZER_CLASS, TOKENIZER_CONFIG_FILE};
+
+use added_tokens::AddedToken;
+use ascii_fold::FoldedTables;
+use cache::WordCache;
+use deasciify::Deasciifier;
+use decoder::byte_token_string;
+use matcher::{PrefixMatcher, VocabMatcher};
+use morphology::Slot;
+use sampling::{keeps_match, DropoutSampler};
+use unigram::TokenScores;
+use vocab_tables::VocabTables;
+
+/// File names of the vocabulary tables inside a tokenizer directory
+pub const ROOTS_FILE: &str = "kokler.json";
+pub const SUFFIXES_FILE: &str = "ekler.json";
+pub const BPE_FILE: &str = "bpe_tokenler.json";
+
+/// Placeholder for whole numbers in `NumberMode::Placeholder`
+pub const NUMBER_TOKEN: &str = "<number>";
+
+/// Marker preceding a lowercased all-caps word when all-caps markers are enabled
+pub const ALL_CAPS_TOKEN: &str = "<all_caps>";
+
+/// Question particle mı/mi/mu/mü and conjunction de/da when clitics are
+/// enabled
+pub const QUESTION_PARTICLE_TOKEN: &str = "<mi>";
+pub const CLITIC_DE_TOKEN: &str = "<de>";
+
+/// Markers claimed by settings, each given its own reserved slot when a
+/// tokenizer is created so their IDs do not depend on the order the
+/// settings are enabled in
+const MARKER_TOKENS: [&str; 8] = [
+    NUMBER_TOKEN,
+    ALL_CAPS_TOKEN,
+    EntityKind::Url.placeholder(),
+    EntityKind::Email.placeholder(),
+    EntityKind::Mention.placeholder(),
+    EntityKind::Hashtag.placeholder(),
+    QUESTION_PARTICLE_TOKEN,
+    CLITIC_DE_TOKEN,
+];
+
+/// Root entries every vocabulary must contain: the case, unknown and
+/// whitespace markers, then the padding and end-of-sequence tokens
+const REQUIRED_TOKENS: [&str; 7] = ["<uppercase>", "<unknown>", " ", "\n", "\t", "<pad>", "<eos>"];
+
+/// Special tokens claimed from the reserved `special_*` slots when a
+/// tokenizer is created, by their `special_tokens_map.json` key
+const DEFAULT_SPECIAL_TOKENS: [(&str, &str); 3] = [("bos_token", "<bos>"), ("sep_token", "<sep>"), ("cls_token", "<cls>")];
+
+/// Digits, each guaranteed a vocabulary entry for `NumberMode::Digits`
+const DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+/// Punctuation marks split into their own tokens by `split_punctuation`.
+/// Each is guaranteed a vocabulary entry.
+pub const PUNCTUATION: &[char] = &[
+    '.', ',', ';', ':', '!', '?', '(', ')', '[', ']', '{', '}', '"', '\'', '-', '/', '“', '”', '‘', '’', '«',
+    '»', '–', '—', '…',
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyclass(eq, eq_int, hash, frozen))]
 pub enum TokenType {
     #[serde(rename = "ROOT")]
     Root,
@@ -11,52 +172,314 @@ pub enum TokenType {
     Suffix,
     #[serde(rename = "BPE")]
     Bpe,
+    #[serde(rename = "BYTE")]
+    Byte,
 }
 
-#[derive(Debug, Clone)]
-#[pyclass]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyclass(eq, hash, frozen, get_all))]
 pub struct Token {
-    #[pyo3(get)]
     pub token: String,
-    #[pyo3(get)]
     pub id: u32,
-    #[pyo3(get)]
     pub token_type: TokenType,
+    /// Char offset of the token's first character in the input
+    pub start: usize,
+    /// Char offset one past the token's last character in the input
+    pub end: usize,
+    /// Index of the space-delimited word the token came from, or `None`
+    /// for spaces and special tokens
+    pub word_id: Option<usize>,
+    /// The input text at `start..end`, before lowercasing; empty for
+    /// zero-width markers such as `<uppercase>`. Byte tokens of one
+    /// character each carry the whole character.
+    #[serde(default)]
+    pub surface: String,
+}
+
+impl Token {
+    /// Copy of this token placed at the given char span of the input,
+    /// which reads `surface` there
+    fn spanning(&self, start: usize, end: usize, surface: &str) -> Token {
+        Token {
+            start,
+            end,
+            surface: surface.to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+/// A piece of a segmented word; positions are char offsets in the word
+enum WordPiece<'a> {
+    Uppercase(usize),
+    Matched {
+        id: u32,
+        token_type: TokenType,
+        pos: usize,
+        /// Lowercased text of the matched vocabulary entry
+        chars: &'a [char],
+    },
+    /// A claimed marker token covering `len` chars, e.g. `<number>` for a
+    /// run of digits or a zero-width `<all_caps>`
+    Marker { marker: &'a Token, pos: usize, len: usize },
+    Unknown { pos: usize, len: usize },
+    Byte { byte: u8, pos: usize, len: usize },
+    /// A grapheme cluster found in the emoji table
+    Emoji { id: u32, pos: usize, len: usize },
+}
+
+impl WordPiece<'_> {
+    /// The same piece `by` chars further into the word
+    fn shifted(self, by: usize) -> Self {
+        match self {
+            WordPiece::Uppercase(pos) => WordPiece::Uppercase(pos + by),
+            WordPiece::Matched { id, token_type, pos, chars } => WordPiece::Matched { id, token_type, pos: pos + by, chars },
+            WordPiece::Marker { marker, pos, len } => WordPiece::Marker { marker, pos: pos + by, len },
+            WordPiece::Unknown { pos, len } => WordPiece::Unknown { pos: pos + by, len },
+            WordPiece::Byte { byte, pos, len } => WordPiece::Byte { byte, pos: pos + by, len },
+            WordPiece::Emoji { id, pos, len } => WordPiece::Emoji { id, pos: pos + by, len },
+        }
+    }
+}
+
+/// For each position of `chars`, the end of the grapheme cluster holding
+/// it, so a combining sequence or an emoji ZWJ sequence is handled as one
+/// unmatched unit
+fn cluster_ends(chars: &[char]) -> Vec<usize> {
+    let text: String = chars.iter().collect();
+    let mut ends = Vec::with_capacity(chars.len());
+    for grapheme in text.graphemes(true) {
+        let end = ends.len() + grapheme.chars().count();
+        ends.resize(end, end);
+    }
+    ends
+}
+
+/// Length of the number at the start of `chars`: digits, optionally
+/// grouped or split by `.` and `,` ("1.250,75")
+fn number_len(chars: &[char]) -> usize {
+    let mut len = 0;
+    while len < chars.len() {
+        let separated_digit = matches!(chars[len], '.' | ',') && chars.get(len + 1).is_some_and(char::is_ascii_digit);
+        if !(chars[len].is_ascii_digit() || len > 0 && separated_digit) {
+            break;
+        }
+        len += 1;
+    }
+    len
+}
+
+/// End of the acronym starting at `start`: a run of at least two capitals.
+/// A capital followed by lowercase letters starts the next word instead
+/// ("HTTPSunucu" is "HTTP" + "Sunucu").
+fn all_caps_end(word: &[char], start: usize) -> Option<usize> {
+    let mut end = start + word[start..].iter().take_while(|c| c.is_uppercase()).count();
+    if end > start && word.get(end).is_some_and(|c| c.is_lowercase()) {
+        end -= 1;
+    }
+    (end - start >= 2).then_some(end)
 }
 
-#[pyclass]
+/// Path cost of a root or suffix in `SegmentationMode::BestPath`; BPE
+/// pieces cost slightly more and unmatched characters much more, so the
+/// best path has the fewest tokens and prefers morphological units
+const MORPHEME_COST: u32 = 10;
+const BPE_COST: u32 = 12;
+const UNMATCHED_COST: u32 = 30;
+
+fn match_cost(token_type: &TokenType) -> u32 {
+    match token_type {
+        TokenType::Bpe => BPE_COST,
+        _ => MORPHEME_COST,
+    }
+}
+
+/// A match on a best path in `TurkishTokenizer::emit_best_path`
+#[derive(Clone)]
+struct PathStep {
+    id: u32,
+    len: usize,
+    token_type: TokenType,
+}
+
+/// A run of `text` produced by `TurkishTokenizer::split_text`
+enum TextPiece<'a> {
+    /// A whitespace token covering chars `start..end`, which read `run`
+    Whitespace { marker: &'a Token, run: &'a str, start: usize, end: usize },
+    /// A token added with `add_tokens` or `add_special_tokens` covering
+    /// chars `start..end`
+    Added { added: &'a AddedToken, start: usize, end: usize },
+    /// A word starting at char `start`
+    Word(&'a str, usize),
+}
+
+/// Cloning is cheap: the vocabulary tables, matchers and decoder are
+/// shared between clones until one of them changes its vocabulary, and each
+/// clone starts with an empty word cache.
+#[derive(Clone)]
+#[cfg_attr(feature = "python", pyclass(module = "turkish_tokenizer_rs"))]
 pub struct TurkishTokenizer {
-    roots: HashMap<String, u32>,
-    suffixes: HashMap<String, u32>,
-    bpe_tokens: HashMap<String, u32>,
-    vocab: HashMap<String, u32>,
-    max_root_len: usize,
-    max_suffix_len: usize,
-    max_bpe_len: usize,
+    roots: Arc<HashMap<String, u32>>,
+    suffixes: Arc<HashMap<String, u32>>,
+    bpe_tokens: Arc<HashMap<String, u32>>,
+    vocab: Arc<HashMap<String, u32>>,
+    root_matcher: Arc<VocabMatcher>,
+    suffix_matcher: Arc<VocabMatcher>,
+    bpe_matcher: Arc<VocabMatcher>,
+    byte_offset: u32,
+    split_camel_case: bool,
+    split_punctuation: bool,
+    join_line_breaks: bool,
+    split_compounds: bool,
+    lowercase: bool,
+    case_mapping: CaseMapping,
+    strict_morphology: bool,
+    morphotactics: bool,
+    consonant_alternation: bool,
+    segmentation: SegmentationMode,
+    emit_case_markers: bool,
+    unknown_strategy: UnknownStrategy,
     uppercase_marker: Token,
     unknown_marker: Token,
     space_marker: Token,
+    newline_marker: Token,
+    tab_marker: Token,
+    whitespace_mode: WhitespaceMode,
+    control_chars: ControlCharMode,
+    number_mode: NumberMode,
+    /// `<number>` token, claimed when `number_mode` is `Placeholder`
+    number_marker: Option<Token>,
+    /// `<all_caps>` token, claimed when all-caps markers are enabled
+    all_caps_marker: Option<Token>,
+    entity_modes: EntityModes,
+    /// `<url>`, `<email>`, `<mention>` and `<hashtag>`, indexed by
+    /// `EntityKind`, each claimed when its kind becomes a placeholder
+    entity_markers: [Option<Token>; 4],
+    foreign_word_mode: ForeignWordMode,
+    /// IDs of the question particle and conjunction tokens, claimed when
+    /// clitics are enabled
+    clitic_ids: Option<[u32; 2]>,
+    /// Slot ID reserved for each of `MARKER_TOKENS`, while slots last
+    marker_slots: Vec<(&'static str, u32)>,
+    restore_diacritics: bool,
+    /// Folded root and suffix index, built the first time it is needed
+    deasciifier: OnceLock<Arc<Deasciifier>>,
+    ascii_fold_matching: bool,
+    /// Folded root and suffix matchers, built the first time they are needed
+    folded_tables: OnceLock<Arc<FoldedTables>>,
+    /// Log probabilities for `SegmentationMode::Unigram`
+    scores: Option<Arc<TokenScores>>,
+    /// Entries added with `add_user_tokens`, kept out of the roots file
+    user_tokens: Vec<(String, u32)>,
+    /// Emoji table from `add_emoji_tokens`, keyed without variation
+    /// selectors
+    emoji_tokens: Arc<HashMap<String, u32>>,
+    /// Abbreviation table from `add_abbreviations`
+    abbreviations: Arc<HashMap<String, u32>>,
+    /// Tokens split out of the text before segmentation, longest first
+    added_tokens: Vec<AddedToken>,
+    decoder: Arc<TurkishDecoder>,
+    word_cache: WordCache,
     pub pad_token: String,
     pub eos_token: String,
     pub pad_token_id: u32,
     pub eos_token_id: u32,
+    pub bos_token: String,
+    pub bos_token_id: u32,
+    pub sep_token: String,
+    pub sep_token_id: u32,
+    pub cls_token: String,
+    pub cls_token_id: u32,
+    /// Token masked positions are replaced with for masked language modeling
+    pub mask_token: String,
+    pub mask_token_id: u32,
+    post_processor: PostProcessor,
+    chat_template: Option<ChatTemplate>,
+    /// Prepend the BOS token when special tokens are requested
+    pub add_bos_token: bool,
+    /// Append the EOS token when special tokens are requested
+    pub add_eos_token: bool,
+    /// Longest input the model accepts, written to `tokenizer_config.json`
+    /// for Hugging Face and used by Python `tokenizer(...)` calls that
+    /// truncate or pad without a `max_length`
+    pub model_max_length: Option<usize>,
 }
 
-#[pymethods]
 impl TurkishTokenizer {
-    #[new]
-    pub fn new() -> PyResult<Self> {
-        // Load JSON data from embedded files
-        let roots_json = include_str!("../turkish_tokenizer/kokler.json");
-        let suffixes_json = include_str!("../turkish_tokenizer/ekler.json");
-        let bpe_tokens_json = include_str!("../turkish_tokenizer/bpe_tokenler.json");
-
-        let roots: HashMap<String, u32> = serde_json::from_str(roots_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to parse roots: {}", e)))?;
-        let suffixes: HashMap<String, u32> = serde_json::from_str(suffixes_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to parse suffixes: {}", e)))?;
-        let bpe_tokens: HashMap<String, u32> = serde_json::from_str(bpe_tokens_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to parse BPE tokens: {}", e)))?;
+    /// Create a tokenizer from the embedded `VocabSize::Base` vocabulary
+    pub fn new() -> Result<Self, TurkishTokenizerError> {
+        Self::with_vocab(VocabSize::Base)
+    }
+
+    /// Create a tokenizer from one of the embedded vocabularies. Fails if
+    /// the crate was built without that vocabulary's feature.
+    pub fn with_vocab(size: VocabSize) -> Result<Self, TurkishTokenizerError> {
+        let embedded = size.embedded().ok_or_else(|| {
+            TurkishTokenizerError::InvalidConfig(format!(
+                "The {:?} vocabulary is not embedded; build with the `{}` feature",
+                size,
+                size.feature()
+            ))
+        })?;
+        Self::from_vocab_parts(VocabTables::from_bincode(embedded)?)
+    }
+
+    /// Shared tokenizer with the embedded vocabulary and default settings,
+    /// built on first use. Saves short-lived tools and per-request handlers
+    /// from rebuilding the vocabulary each time.
+    pub fn global() -> &'static TurkishTokenizer {
+        static GLOBAL: OnceLock<TurkishTokenizer> = OnceLock::new();
+        GLOBAL.get_or_init(|| Self::new().expect("the embedded vocabulary is valid"))
+    }
+
+    #[deprecated(note = "use `TurkishTokenizer::new`")]
+    pub fn new_rust() -> Result<Self, TurkishTokenizerError> {
+        Self::new()
+    }
+
+    /// Load a tokenizer from root, suffix and BPE vocabulary JSON files
+    /// laid out like the embedded `kokler.json`, `ekler.json` and
+    /// `bpe_tokenler.json`
+    pub fn from_files(
+        roots_path: impl AsRef<Path>,
+        suffixes_path: impl AsRef<Path>,
+        bpe_path: impl AsRef<Path>,
+    ) -> Result<Self, TurkishTokenizerError> {
+        let roots_json = fs::read_to_string(roots_path)?;
+        let suffixes_json = fs::read_to_string(suffixes_path)?;
+        let bpe_tokens_json = fs::read_to_string(bpe_path)?;
+
+        Self::from_json(&roots_json, &suffixes_json, &bpe_tokens_json)
+    }
+
+    /// Load a tokenizer from a directory holding `kokler.json`, `ekler.json`
+    /// and `bpe_tokenler.json`
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, TurkishTokenizerError> {
+        let dir = dir.as_ref();
+        Self::from_files(dir.join(ROOTS_FILE), dir.join(SUFFIXES_FILE), dir.join(BPE_FILE))
+    }
+
+    /// Build a tokenizer from the JSON contents of the three vocabulary files
+    pub fn from_json(
+        roots_json: &str,
+        suffixes_json: &str,
+        bpe_tokens_json: &str,
+    ) -> Result<Self, TurkishTokenizerError> {
+        Self::from_vocab_parts(VocabTables::from_json(roots_json, suffixes_json, bpe_tokens_json)?)
+    }
+
+    /// Shared constructor behind `new`, `from_json`, `from_files` and
+    /// `from_bytes`
+    fn from_vocab_parts(tables: VocabTables) -> Result<Self, TurkishTokenizerError> {
+        let mut decoder = TurkishDecoder::from_entries(
+            [&tables.roots, &tables.suffixes, &tables.bpe_tokens]
+                .into_iter()
+                .flatten()
+                .map(|(token, id)| (token.as_str(), *id)),
+        );
+        let roots: HashMap<String, u32> = tables.roots.into_iter().collect();
+        let suffixes: HashMap<String, u32> = tables.suffixes.into_iter().collect();
+        let bpe_tokens: HashMap<String, u32> = tables.bpe_tokens.into_iter().collect();
 
         // Create combined vocab
         let mut vocab = HashMap::new();
@@ -64,207 +487,467 @@ impl TurkishTokenizer {
         vocab.extend(suffixes.clone());
         vocab.extend(bpe_tokens.clone());
 
-        let max_root_len = roots.keys().map(|k| k.len()).max().unwrap_or(0);
-        let max_suffix_len = suffixes.keys().map(|k| k.len()).max().unwrap_or(0);
-        let max_bpe_len = bpe_tokens.keys().map(|k| k.len()).max().unwrap_or(0);
+        let root_matcher = VocabMatcher::from_table(&roots);
+        let suffix_matcher = VocabMatcher::from_table(&suffixes);
+        let bpe_matcher = VocabMatcher::from_table(&bpe_tokens);
 
-        // Create special tokens
-        let uppercase_marker = Token {
-            token: "<uppercase>".to_string(),
-            id: *roots.get("<uppercase>").unwrap(),
-            token_type: TokenType::Root,
-        };
-        let unknown_marker = Token {
-            token: "<unknown>".to_string(),
-            id: *roots.get("<unknown>").unwrap(),
-            token_type: TokenType::Root,
-        };
-        let space_marker = Token {
-            token: " ".to_string(),
-            id: *roots.get(" ").unwrap(),
-            token_type: TokenType::Root,
-        };
+        // Reserve byte-fallback tokens right after the embedded vocabulary
+        let byte_offset = vocab.values().max().map_or(0, |&max_id| max_id + 1);
+        for byte in 0..=u8::MAX {
+            vocab.insert(byte_token_string(byte), byte_offset + u32::from(byte));
+        }
+        decoder.register_byte_tokens(byte_offset);
+
+        if let Some(missing) = REQUIRED_TOKENS.iter().find(|token| !roots.contains_key(**token)) {
+            return Err(TurkishTokenizerError::MissingSpecialToken(missing.to_string()));
+        }
+        let [uppercase_marker, unknown_marker, space_marker, newline_marker, tab_marker, pad_marker, eos_marker] =
+            REQUIRED_TOKENS.map(|token| Token {
+                token: token.to_string(),
+                id: roots[token],
+                token_type: TokenType::Root,
+                start: 0,
+                end: 0,
+                word_id: None,
+                surface: String::new(),
+            });
+        decoder.register_uppercase_marker(uppercase_marker.id);
+        decoder.register_unknown_marker(unknown_marker.id);
+        decoder.register_suffixes(suffixes.values().copied());
 
-        let pad_token = "<pad>".to_string();
-        let eos_token = "<eos>".to_string();
-        let pad_token_id = *roots.get(&pad_token).unwrap();
-        let eos_token_id = *roots.get(&eos_token).unwrap();
-
-        Ok(TurkishTokenizer {
-            roots,
-            suffixes,
-            bpe_tokens,
-            vocab,
-            max_root_len,
-            max_suffix_len,
-            max_bpe_len,
+        let mut tokenizer = TurkishTokenizer {
+            roots: Arc::new(roots),
+            suffixes: Arc::new(suffixes),
+            bpe_tokens: Arc::new(bpe_tokens),
+            vocab: Arc::new(vocab),
+            root_matcher: Arc::new(root_matcher),
+            suffix_matcher: Arc::new(suffix_matcher),
+            bpe_matcher: Arc::new(bpe_matcher),
+            byte_offset,
+            split_camel_case: true,
+            split_punctuation: false,
+            join_line_breaks: false,
+            split_compounds: false,
+            lowercase: true,
+            case_mapping: CaseMapping::Turkish,
+            strict_morphology: false,
+            morphotactics: false,
+            consonant_alternation: false,
+            segmentation: SegmentationMode::Greedy,
+            emit_case_markers: true,
+            unknown_strategy: UnknownStrategy::ByteFallback,
             uppercase_marker,
             unknown_marker,
             space_marker,
-            pad_token,
-            eos_token,
-            pad_token_id,
-            eos_token_id,
-        })
+            newline_marker,
+            tab_marker,
+            whitespace_mode: WhitespaceMode::Space,
+            control_chars: ControlCharMode::Keep,
+            number_mode: NumberMode::Bpe,
+            number_marker: None,
+            all_caps_marker: None,
+            entity_modes: EntityModes::default(),
+            entity_markers: Default::default(),
+            foreign_word_mode: ForeignWordMode::Off,
+            clitic_ids: None,
+            marker_slots: Vec::new(),
+            restore_diacritics: false,
+            deasciifier: OnceLock::new(),
+            ascii_fold_matching: false,
+            folded_tables: OnceLock::new(),
+            scores: None,
+            user_tokens: Vec::new(),
+            emoji_tokens: Arc::default(),
+            abbreviations: Arc::default(),
+            added_tokens: Vec::new(),
+            decoder: Arc::new(decoder),
+            word_cache: WordCache::new(DEFAULT_WORD_CACHE_CAPACITY),
+            pad_token: pad_marker.token,
+            eos_token: eos_marker.token,
+            pad_token_id: pad_marker.id,
+            eos_token_id: eos_marker.id,
+            bos_token: String::new(),
+            bos_token_id: 0,
+            sep_token: String::new(),
+            sep_token_id: 0,
+            cls_token: String::new(),
+            cls_token_id: 0,
+            mask_token: String::new(),
+            mask_token_id: 0,
+            post_processor: PostProcessor::BosEos,
+            chat_template: None,
+            add_bos_token: false,
+            add_eos_token: true,
+            model_max_length: None,
+        };
+        for (role, token) in DEFAULT_SPECIAL_TOKENS {
+            tokenizer.assign_special_token(role, token)?;
+        }
+        for mark in PUNCTUATION.iter().chain(&DIGITS) {
+            tokenizer.claim_special_token(mark.encode_utf8(&mut [0; 4]))?;
+        }
+        // Claimed after the punctuation and digits so their IDs did not
+        // move when it was introduced
+        tokenizer.assign_special_token("mask_token", "<mask>")?;
+        tokenizer.reserve_marker_slots();
+
+        Ok(tokenizer)
     }
 
-    /// Get the vocabulary as a Python dictionary
-    #[pyo3(name = "get_vocab")]
-    pub fn py_get_vocab(&self) -> HashMap<String, u32> {
-        self.vocab.clone()
+    /// Create a builder for configuring a tokenizer
+    pub fn builder() -> TurkishTokenizerBuilder {
+        TurkishTokenizerBuilder::new()
     }
 
-    /// Get vocabulary size
-    #[pyo3(name = "vocab_size")]
-    pub fn py_vocab_size(&self) -> usize {
-        self.vocab_size()
+    /// Whether unmatched characters become byte tokens instead of `<unknown>`
+    pub fn byte_fallback(&self) -> bool {
+        self.unknown_strategy == UnknownStrategy::ByteFallback
     }
 
-    /// Encode text to token IDs
-    #[pyo3(name = "encode")]
-    pub fn py_encode(&self, text: &str) -> Vec<u32> {
-        self.encode(text)
+    /// Emit UTF-8 byte tokens for unmatched characters (the default), or a
+    /// single `<unknown>` token per character when disabled
+    pub fn set_byte_fallback(&mut self, enabled: bool) {
+        self.unknown_strategy = if enabled {
+            UnknownStrategy::ByteFallback
+        } else {
+            UnknownStrategy::Unknown
+        };
+        self.word_cache.clear();
     }
 
-    /// Tokenize text to string tokens
-    #[pyo3(name = "tokenize")]
-    pub fn py_tokenize(&self, text: &str) -> Vec<String> {
-        self.tokenize(text)
+    /// How digits are tokenized
+    pub fn number_mode(&self) -> NumberMode {
+        self.number_mode
     }
 
-    /// Get detailed token information
-    #[pyo3(name = "tokenize_text")]
-    pub fn py_tokenize_text(&self, text: &str) -> Vec<Token> {
-        self.tokenize_text(text)
+    /// Change how digits are tokenized, claiming a reserved slot for
+    /// `<number>` when switching to `NumberMode::Placeholder`
+    pub fn set_number_mode(&mut self, mode: NumberMode) -> Result<(), TurkishTokenizerError> {
+        if mode == NumberMode::Placeholder && self.number_marker.is_none() {
+            self.number_marker = Some(self.claim_marker(NUMBER_TOKEN)?);
+        }
+        self.number_mode = mode;
+        self.word_cache.clear();
+        Ok(())
     }
 
-    /// Convert tokens to IDs
-    #[pyo3(name = "convert_tokens_to_ids")]
-    pub fn py_convert_tokens_to_ids(&self, tokens: Vec<String>) -> Vec<u32> {
-        self.convert_tokens_to_ids(&tokens)
+    pub fn strict_morphology(&self) -> bool {
+        self.strict_morphology
     }
 
-    /// Get token ID for a specific token
-    #[pyo3(name = "token_to_id")]
-    pub fn py_token_to_id(&self, token: &str) -> Option<u32> {
-        self.token_to_id(token)
+    /// Reject suffix matches that break vowel harmony with the preceding
+    /// part of the word, falling back to shorter suffixes, then BPE
+    pub fn set_strict_morphology(&mut self, enabled: bool) {
+        self.strict_morphology = enabled;
+        self.word_cache.clear();
     }
 
-    /// Check if token exists in vocabulary
-    #[pyo3(name = "contains_token")]
-    pub fn py_contains_token(&self, token: &str) -> bool {
-        self.contains_token(token)
+    pub fn post_processor(&self) -> &PostProcessor {
+        &self.post_processor
     }
 
-    /// Get pad token
-    #[getter]
-    pub fn pad_token(&self) -> &str {
-        &self.pad_token
+    /// Change the special tokens added around texts, claiming reserved
+    /// slots for template tokens missing from the vocabulary
+    pub fn set_post_processor(&mut self, post_processor: PostProcessor) -> Result<(), TurkishTokenizerError> {
+        if let PostProcessor::Template(template) = &post_processor {
+            for token in template.special_tokens() {
+                self.claim_special_token(token)?;
+            }
+        }
+        self.post_processor = post_processor;
+        Ok(())
     }
 
-    /// Get EOS token
-    #[getter]
-    pub fn eos_token(&self) -> &str {
-        &self.eos_token
+    pub fn segmentation(&self) -> SegmentationMode {
+        self.segmentation
     }
 
-    /// Get pad token ID
-    #[getter]
-    pub fn pad_token_id(&self) -> u32 {
-        self.pad_token_id
+    pub fn set_segmentation(&mut self, mode: SegmentationMode) {
+        self.segmentation = mode;
+        self.word_cache.clear();
     }
 
-    /// Get EOS token ID
-    #[getter]
-    pub fn eos_token_id(&self) -> u32 {
-        self.eos_token_id
+    pub fn morphotactics(&self) -> bool {
+        self.morphotactics
     }
 
-    /// Python-style call method for compatibility
-    pub fn __call__(&self, text: &str) -> HashMap<String, Vec<u32>> {
-        let input_ids = self.encode(text);
-        let attention_mask = vec![1u32; input_ids.len()];
-        
-        let mut result = HashMap::new();
-        result.insert("input_ids".to_string(), input_ids);
-        result.insert("attention_mask".to_string(), attention_mask);
-        result
+    /// Only match suffixes after a root and in a valid order (derivation,
+    /// tense, plural, possessive, case, copula), falling back to shorter
+    /// matches, then BPE
+    pub fn set_morphotactics(&mut self, enabled: bool) {
+        self.morphotactics = enabled;
+        self.word_cache.clear();
     }
-}
 
-// Separate implementation block for non-Python methods
-impl TurkishTokenizer {
-    pub fn new_rust() -> Result<Self, Box<dyn std::error::Error>> {
-        // Load JSON data from embedded files
-        let roots_json = include_str!("../turkish_tokenizer/kokler.json");
-        let suffixes_json = include_str!("../turkish_tokenizer/ekler.json");
-        let bpe_tokens_json = include_str!("../turkish_tokenizer/bpe_tokenler.json");
+    pub fn consonant_alternation(&self) -> bool {
+        self.consonant_alternation
+    }
 
-        let roots: HashMap<String, u32> = serde_json::from_str(roots_json)?;
-        let suffixes: HashMap<String, u32> = serde_json::from_str(suffixes_json)?;
-        let bpe_tokens: HashMap<String, u32> = serde_json::from_str(bpe_tokens_json)?;
+    /// Match roots whose final consonant changed by voicing ("kitabı",
+    /// "ağaca", "rengi") to the same root ID as the dictionary form. Token
+    /// text keeps the surface form.
+    pub fn set_consonant_alternation(&mut self, enabled: bool) {
+        self.consonant_alternation = enabled;
+        self.word_cache.clear();
+    }
 
-        // Create combined vocab
-        let mut vocab = HashMap::new();
-        vocab.extend(roots.clone());
-        vocab.extend(suffixes.clone());
-        vocab.extend(bpe_tokens.clone());
+    pub fn whitespace_mode(&self) -> WhitespaceMode {
+        self.whitespace_mode
+    }
 
-        let max_root_len = roots.keys().map(|k| k.len()).max().unwrap_or(0);
-        let max_suffix_len = suffixes.keys().map(|k| k.len()).max().unwrap_or(0);
-        let max_bpe_len = bpe_tokens.keys().map(|k| k.len()).max().unwrap_or(0);
+    /// Change which whitespace separates words and how it is emitted
+    pub fn set_whitespace_mode(&mut self, mode: WhitespaceMode) {
+        self.whitespace_mode = mode;
+    }
 
-        // Create special tokens
-        let uppercase_marker = Token {
-            token: "<uppercase>".to_string(),
-            id: *roots.get("<uppercase>").unwrap(),
-            token_type: TokenType::Root,
-        };
-        let unknown_marker = Token {
-            token: "<unknown>".to_string(),
-            id: *roots.get("<unknown>").unwrap(),
-            token_type: TokenType::Root,
-        };
-        let space_marker = Token {
-            token: " ".to_string(),
-            id: *roots.get(" ").unwrap(),
-            token_type: TokenType::Root,
-        };
+    /// Whether acronyms are emitted as `<all_caps>` plus the lowercased word
+    pub fn all_caps_markers(&self) -> bool {
+        self.all_caps_marker.is_some()
+    }
 
-        let pad_token = "<pad>".to_string();
-        let eos_token = "<eos>".to_string();
-        let pad_token_id = *roots.get(&pad_token).unwrap();
-        let eos_token_id = *roots.get(&eos_token).unwrap();
-
-        Ok(TurkishTokenizer {
-            roots,
-            suffixes,
-            bpe_tokens,
-            vocab,
-            max_root_len,
-            max_suffix_len,
-            max_bpe_len,
-            uppercase_marker,
-            unknown_marker,
-            space_marker,
-            pad_token,
-            eos_token,
-            pad_token_id,
-            eos_token_id,
+    /// Emit `<all_caps>` before runs of two or more capitals ("TBMM",
+    /// "NATO") instead of `<uppercase>` before every letter, claiming a
+    /// reserved slot for the marker. The decoder uppercases the following
+    /// letters up to the next space, marker or punctuation.
+    pub fn set_all_caps_markers(&mut self, enabled: bool) -> Result<(), TurkishTokenizerError> {
+        if !enabled {
+            self.all_caps_marker = None;
+        } else if self.all_caps_marker.is_none() {
+            let marker = self.claim_marker(ALL_CAPS_TOKEN)?;
+            Arc::make_mut(&mut self.decoder).register_all_caps_marker(marker.id);
+            self.all_caps_marker = Some(marker);
+        }
+        self.word_cache.clear();
+        Ok(())
+    }
+
+    /// Zero-width root token for `token`, claiming a reserved slot if needed
+    fn claim_marker(&mut self, token: &str) -> Result<Token, TurkishTokenizerError> {
+        Ok(Token {
+            token: token.to_string(),
+            id: self.claim_special_token(token)?,
+            token_type: TokenType::Root,
+            start: 0,
+            end: 0,
+            word_id: None,
+            surface: String::new(),
         })
     }
 
+    /// Number of distinct words whose tokenization is cached
+    pub fn word_cache_capacity(&self) -> usize {
+        self.word_cache.capacity()
+    }
+
+    /// Resize the word cache, dropping its entries; `0` disables caching
+    pub fn set_word_cache_capacity(&mut self, capacity: usize) {
+        self.word_cache = WordCache::new(capacity);
+    }
+
+    /// How characters that match no vocabulary entry are emitted
+    pub fn unknown_strategy(&self) -> UnknownStrategy {
+        self.unknown_strategy
+    }
+
+    pub fn set_unknown_strategy(&mut self, strategy: UnknownStrategy) {
+        self.unknown_strategy = strategy;
+        self.word_cache.clear();
+    }
+
     pub fn get_vocab(&self) -> &HashMap<String, u32> {
         &self.vocab
     }
 
+    /// Root table, with the special tokens and the entries added with
+    /// `add_user_tokens`
+    pub fn get_roots(&self) -> &HashMap<String, u32> {
+        &self.roots
+    }
+
+    /// Suffix table; the forms of one suffix share its ID
+    pub fn get_suffixes(&self) -> &HashMap<String, u32> {
+        &self.suffixes
+    }
+
+    pub fn get_bpe_vocab(&self) -> &HashMap<String, u32> {
+        &self.bpe_tokens
+    }
+
+    /// Which table `id` comes from, or `None` if it is not in the
+    /// vocabulary. IDs found in several tables count as the first of root,
+    /// suffix and BPE; added tokens count as roots and clitics as
+    /// suffixes, as when tokenized.
+    pub fn token_type_of_id(&self, id: u32) -> Option<TokenType> {
+        if (self.byte_offset..self.byte_offset + 256).contains(&id) {
+            return Some(TokenType::Byte);
+        }
+        if self.clitic_ids.is_some_and(|ids| ids.contains(&id)) {
+            return Some(TokenType::Suffix);
+        }
+        let forms = self.decoder.forms(id)?;
+        let in_table = |table: &HashMap<String, u32>| forms.iter().any(|form| table.get(form) == Some(&id));
+        Some(if in_table(&self.roots) {
+            TokenType::Root
+        } else if in_table(&self.suffixes) {
+            TokenType::Suffix
+        } else if in_table(&self.bpe_tokens) {
+            TokenType::Bpe
+        } else {
+            TokenType::Root
+        })
+    }
+
     pub fn vocab_size(&self) -> usize {
         self.vocab.len()
     }
 
     pub fn encode(&self, text: &str) -> Vec<u32> {
-        let tokens = self.tokenize_text(text);
-        tokens.into_iter().map(|t| t.id).collect()
+        self.encode_fast(text)
+    }
+
+    /// Number of tokens `encode` would produce, without collecting the IDs
+    pub fn count_tokens(&self, text: &str) -> usize {
+        let mut count = 0;
+        self.for_each_id(text, |_| count += 1);
+        count
+    }
+
+    /// Encode text, framing it with BOS/EOS when `add_special_tokens` is set
+    /// (according to `add_bos_token` and `add_eos_token`)
+    pub fn encode_with(&self, text: &str, add_special_tokens: bool) -> Vec<u32> {
+        let mut ids = self.encode(text);
+        if add_special_tokens {
+            if self.add_bos_token {
+                ids.insert(0, self.bos_token_id);
+            }
+            if self.add_eos_token {
+                ids.push(self.eos_token_id);
+            }
+        }
+        ids
+    }
+
+    /// Use `token` as the BOS token, claiming a reserved `special_*` slot
+    /// for it if it is not in the vocabulary yet. Returns its ID.
+    pub fn set_bos_token(&mut self, token: &str) -> Result<u32, TurkishTokenizerError> {
+        let id = self.claim_special_token(token)?;
+        self.bos_token = token.to_string();
+        self.bos_token_id = id;
+        Ok(id)
+    }
+
+    /// Set aside a slot for each of `MARKER_TOKENS` not in the vocabulary
+    /// yet, the lowest free ones in table order
+    fn reserve_marker_slots(&mut self) {
+        let mut free: Vec<u32> = self
+            .roots
+            .iter()
+            .filter(|(key, _)| key.starts_with("special_"))
+            .map(|(_, &id)| id)
+            .collect();
+        free.sort_unstable();
+        let mut free = free.into_iter();
+        self.marker_slots = MARKER_TOKENS
+            .iter()
+            .filter(|marker| !self.vocab.contains_key(**marker))
+            .map_while(|&marker| Some((marker, free.next()?)))
+            .collect();
+    }
+
+    /// ID of `token`, first giving it a reserved `special_*` slot if it is
+    /// not in the vocabulary: its own for a marker, otherwise the lowest
+    /// one not reserved for a marker
+    fn claim_special_token(&mut self, token: &str) -> Result<u32, TurkishTokenizerError> {
+        if let Some(&id) = self.vocab.get(token) {
+            return Ok(id);
+        }
+
+        let reserved = self.marker_slots.iter().find(|&&(marker, _)| marker == token).map(|&(_, id)| id);
+        let (slot, id) = self
+            .roots
+            .iter()
+            .filter(|(key, &id)| {
+                key.starts_with("special_")
+                    && match reserved {
+                        Some(reserved) => id == reserved,
+                        None => self.marker_slots.iter().all(|&(_, slot)| slot != id),
+                    }
+            })
+            .min_by_key(|(_, &id)| id)
+            .map(|(key, &id)| (key.clone(), id))
+            .ok_or(TurkishTokenizerError::NoSpecialSlots)?;
+
+        let roots = Arc::make_mut(&mut self.roots);
+        roots.remove(&slot);
+        roots.insert(token.to_string(), id);
+        let vocab = Arc::make_mut(&mut self.vocab);
+        vocab.remove(&slot);
+        vocab.insert(token.to_string(), id);
+        Arc::make_mut(&mut self.root_matcher).replace(&self.roots, &slot, token, id);
+        Arc::make_mut(&mut self.decoder).set_forms(id, vec![token.to_string()]);
+        self.word_cache.clear();
+        Ok(id)
+    }
+
+    /// Encode many texts in parallel across the rayon thread pool
+    pub fn encode_batch(&self, texts: &[&str]) -> Vec<Vec<u32>> {
+        texts.par_iter().map(|text| self.encode(text)).collect()
+    }
+
+    /// Tokenize many texts in parallel across the rayon thread pool
+    pub fn tokenize_batch(&self, texts: &[&str]) -> Vec<Vec<String>> {
+        texts.par_iter().map(|text| self.tokenize(text)).collect()
+    }
+
+    /// Decode many ID sequences in parallel across the rayon thread pool
+    pub fn decode_batch(&self, sequences: &[Vec<u32>], skip_special_tokens: bool) -> Vec<String> {
+        sequences
+            .par_iter()
+            .map(|ids| self.decode_with_options(ids, skip_special_tokens))
+            .collect()
+    }
+
+    /// Decode token IDs back to text, restoring case from `<uppercase>`
+    /// markers and picking suffix forms by vowel harmony
+    pub fn decode(&self, ids: &[u32]) -> String {
+        self.decoder.decode(ids)
+    }
+
+    /// Decode token IDs, optionally dropping `<pad>`, `<eos>`, `<unknown>`
+    /// and tokens added with `add_special_tokens`
+    pub fn decode_with_options(&self, ids: &[u32], skip_special_tokens: bool) -> String {
+        if !skip_special_tokens {
+            return self.decode(ids);
+        }
+        let kept: Vec<u32> = ids.iter().copied().filter(|&id| !self.is_skipped_special(id)).collect();
+        self.decode(&kept)
+    }
+
+    /// Whether `decode_with_options` drops `id` when skipping special tokens
+    fn is_skipped_special(&self, id: u32) -> bool {
+        [self.pad_token_id, self.eos_token_id, self.unknown_marker.id].contains(&id) || self.is_added_special(id)
+    }
+
+    /// 1 for each ID of an already encoded sequence that is a special
+    /// token (BOS, EOS, padding, separator, classifier, mask, or added with
+    /// `add_special_tokens` or a post-processor template), 0 otherwise.
+    /// Masked language modeling collators use it to leave those positions
+    /// unmasked.
+    pub fn get_special_tokens_mask(&self, ids: &[u32]) -> Vec<u32> {
+        let mut special_ids = vec![
+            self.bos_token_id,
+            self.eos_token_id,
+            self.pad_token_id,
+            self.sep_token_id,
+            self.cls_token_id,
+            self.mask_token_id,
+        ];
+        if let PostProcessor::Template(template) = &self.post_processor {
+            special_ids.extend(template.special_tokens().filter_map(|token| self.token_to_id(token)));
+        }
+        ids.iter()
+            .map(|&id| u32::from(special_ids.contains(&id) || self.is_added_special(id)))
+            .collect()
     }
 
     pub fn tokenize(&self, text: &str) -> Vec<String> {
@@ -273,197 +956,1185 @@ impl TurkishTokenizer {
     }
 
     pub fn tokenize_text(&self, text: &str) -> Vec<Token> {
-        let mut final_tokens = Vec::new();
-        
-        let parts: Vec<&str> = text.split(' ').collect();
-        for (idx, part) in parts.iter().enumerate() {
-            if !part.trim().is_empty() {
-                let tokens = self.tokenize_word(part);
-                final_tokens.extend(tokens);
+        self.tokenize_iter(text).collect()
+    }
+
+    /// Tokenize text lazily, one word at a time, so callers can stop early
+    pub fn tokenize_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Token> + 'a {
+        let mut next_word_id = 0;
+        self.split_text(text).flat_map(move |piece| match piece {
+            TextPiece::Whitespace { marker, run, start, end } => vec![marker.spanning(start, end, run)],
+            TextPiece::Added { added, start, end } => {
+                let mut token = added.token.spanning(start, end, &added.token.token);
+                if !added.special {
+                    token.word_id = Some(next_word_id);
+                    next_word_id += 1;
+                }
+                vec![token]
             }
-            if idx < parts.len() - 1 {
-                final_tokens.push(self.space_marker.clone());
+            TextPiece::Word(word, start) => {
+                let word_id = next_word_id;
+                next_word_id += 1;
+                self.tokenize_word(word, start)
+                    .into_iter()
+                    .map(|t| Token {
+                        word_id: Some(word_id),
+                        ..t
+                    })
+                    .collect()
             }
-        }
-        
-        final_tokens
+        })
     }
 
-    fn tokenize_word(&self, word: &str) -> Vec<Token> {
-        let mut result = Vec::new();
-        let segments = self.camel_split_with_positions(word);
-        
-        for (seg, orig_pos) in segments {
-            if orig_pos < word.len() && word.chars().nth(orig_pos).unwrap().is_uppercase() {
-                result.push(self.uppercase_marker.clone());
+    /// Split `text` into added tokens, words and whitespace tokens
+    /// following the whitespace mode. Added tokens are cut out first, even
+    /// inside words. Words made only of other whitespace are dropped.
+    fn split_text<'a>(&'a self, text: &'a str) -> impl Iterator<Item = TextPiece<'a>> + 'a {
+        let mut rest = text;
+        let mut offset = 0;
+        std::iter::from_fn(move || loop {
+            let first = rest.chars().next()?;
+            if let Some(added) = self.added_token_at(rest) {
+                let len = added.token.token.len();
+                let count = added.token.token.chars().count();
+                rest = &rest[len..];
+                offset += count;
+                return Some(TextPiece::Added {
+                    added,
+                    start: offset - count,
+                    end: offset,
+                });
             }
-            
-            let mut pos = 0;
-            let seg_chars: Vec<char> = seg.chars().collect();
-            
-            while pos < seg_chars.len() {
-                let substr: String = seg_chars[pos..].iter().collect();
-                
-                // Try root lookup
-                if let Some((id, token)) = self.longest_prefix_lookup(&substr, &self.roots, Some(self.max_root_len)) {
-                    let token_len = token.chars().count();
-                    result.push(Token {
-                        token,
-                        id,
-                        token_type: TokenType::Root,
-                    });
-                    pos += token_len;
-                    continue;
-                }
-                
-                // Try suffix lookup
-                if let Some((id, token)) = self.longest_prefix_lookup(&substr, &self.suffixes, Some(self.max_suffix_len)) {
-                    let token_len = token.chars().count();
-                    result.push(Token {
-                        token,
-                        id,
-                        token_type: TokenType::Suffix,
-                    });
-                    pos += token_len;
-                    continue;
-                }
-                
-                // Try BPE lookup
-                if let Some((id, token)) = self.longest_prefix_lookup(&substr, &self.bpe_tokens, Some(self.max_bpe_len)) {
-                    let token_len = token.chars().count();
-                    result.push(Token {
-                        token,
-                        id,
-                        token_type: TokenType::Bpe,
-                    });
-                    pos += token_len;
-                    continue;
-                }
-                
-                // No match found, add unknown token
-                result.push(self.unknown_marker.clone());
-                pos += 1;
+            if let Some(marker) = self.separator_token(first) {
+                let (len, count, marker) = if self.whitespace_mode == WhitespaceMode::Collapse {
+                    let len = rest.find(|c| self.separator_token(c).is_none()).unwrap_or(rest.len());
+                    let run = &rest[..len];
+                    let marker = if run.contains('\n') { &self.newline_marker } else { &self.space_marker };
+                    (len, run.chars().count(), marker)
+                } else {
+                    (first.len_utf8(), 1, marker)
+                };
+                let run = &rest[..len];
+                rest = &rest[len..];
+                offset += count;
+                return Some(TextPiece::Whitespace {
+                    marker,
+                    run,
+                    start: offset - count,
+                    end: offset,
+                });
             }
-        }
-        
-        result
-    }
 
-    fn longest_prefix_lookup(
-        &self,
-        s: &str,
-        table: &HashMap<String, u32>,
-        max_len: Option<usize>,
-    ) -> Option<(u32, String)> {
-        let chars: Vec<char> = s.chars().collect();
-        let end = if let Some(max_len) = max_len {
-            std::cmp::min(chars.len(), max_len)
-        } else {
-            chars.len()
-        };
-        
-        for i in (1..=end).rev() {
-            let candidate: String = chars[..i].iter().collect();
-            if let Some(&id) = table.get(&candidate) {
-                return Some((id, candidate));
+            let len = rest
+                .char_indices()
+                .find(|&(i, c)| {
+                    self.separator_token(c).is_some() && !self.joins_line_break(&rest[..i], &rest[i..])
+                        || i > 0 && !self.added_tokens.is_empty() && self.added_token_at(&rest[i..]).is_some()
+                })
+                .map_or(rest.len(), |(i, _)| i);
+            let (word, tail) = rest.split_at(len);
+            rest = tail;
+            let start = offset;
+            offset += word.chars().count();
+            if !self.is_blank(word) {
+                return Some(TextPiece::Word(word, start));
             }
+        })
+    }
+
+    /// The token emitted for `c` if it separates words
+    fn separator_token(&self, c: char) -> Option<&Token> {
+        match (self.whitespace_mode, c) {
+            (_, ' ') => Some(&self.space_marker),
+            (_, c) if self.is_mapped_space(c) => Some(&self.space_marker),
+            (WhitespaceMode::Space, _) => None,
+            (_, '\n') => Some(&self.newline_marker),
+            (_, '\t') => Some(&self.tab_marker),
+            _ => None,
         }
-        None
     }
 
-    fn tr_lower(&self, word: &str) -> String {
-        word.replace('İ', "i").replace('I', "ı").to_lowercase()
+    /// Whether byte `b` of UTF-8 text is a word separator
+    pub(crate) fn is_separator_byte(&self, b: u8) -> bool {
+        b.is_ascii() && self.separator_token(char::from(b)).is_some()
+    }
+
+    /// Tokenize text from `reader` lazily, yielding the same tokens as
+    /// `tokenize_text` on the whole input without loading it into memory
+    pub fn tokenize_stream<R: Read>(&self, reader: R) -> TokenStream<'_, R> {
+        TokenStream::new(self, reader)
     }
 
-    fn camel_split_with_positions(&self, word: &str) -> Vec<(String, usize)> {
-        if word.is_empty() {
-            return Vec::new();
+    /// Tokenize text so that `decode` reproduces it exactly.
+    ///
+    /// Unlike `tokenize_text`, whitespace other than spaces is kept and
+    /// unmatched characters are emitted as UTF-8 byte tokens. Words whose
+    /// surface form the decoder's suffix rules would not reproduce are
+    /// emitted entirely as byte tokens.
+    pub fn tokenize_lossless(&self, text: &str) -> Vec<Token> {
+        let parts: Vec<&str> = text.split(' ').collect();
+        let mut starts = Vec::with_capacity(parts.len());
+        let mut word_ids = Vec::with_capacity(parts.len());
+        let mut offset = 0;
+        let mut next_word_id = 0;
+        for part in &parts {
+            starts.push(offset);
+            offset += part.chars().count() + 1;
+            if part.trim().is_empty() {
+                word_ids.push(None);
+            } else {
+                word_ids.push(Some(next_word_id));
+                next_word_id += 1;
+            }
         }
-        
-        let mut parts = Vec::new();
-        let mut start = 0;
-        let chars: Vec<char> = word.chars().collect();
-        
-        for i in 1..chars.len() {
-            if chars[i].is_uppercase() {
-                if start < i {
-                    let segment: String = chars[start..i].iter().collect();
-                    parts.push((self.tr_lower(&segment), start));
+
+        let mut words: Vec<Vec<Token>> = parts
+            .iter()
+            .zip(&starts)
+            .map(|(part, &start)| self.tokenize_word_with(part, start, UnknownStrategy::ByteFallback))
+            .collect();
+
+        loop {
+            let tokens = self.join_words(&words, &starts, &word_ids);
+            let ids: Vec<u32> = tokens.iter().map(|t| t.id).collect();
+            let decoded = self.decoder.decode_parts(&ids);
+
+            // Each word is followed by exactly one space marker
+            let mut offset = 0;
+            let mut changed = false;
+            for ((word, part), &start) in words.iter_mut().zip(&parts).zip(&starts) {
+                let end = offset + word.len();
+                if decoded[offset..end].concat() != *part {
+                    *word = self.byte_tokens(part, start);
+                    changed = true;
                 }
-                start = i;
+                offset = end + 1;
+            }
+
+            if !changed {
+                return tokens;
             }
-        }
-        
-        if start < chars.len() {
-            let segment: String = chars[start..].iter().collect();
-            parts.push((self.tr_lower(&segment), start));
-        }
-        
-        if parts.is_empty() {
-            vec![(self.tr_lower(word), 0)]
-        } else {
-            parts
         }
     }
 
-    pub fn convert_tokens_to_ids(&self, tokens: &[String]) -> Vec<u32> {
-        tokens.iter().map(|token| self.vocab[token]).collect()
+    /// Encode text so that `decode` reproduces it exactly
+    pub fn encode_lossless(&self, text: &str) -> Vec<u32> {
+        self.tokenize_lossless(text).into_iter().map(|t| t.id).collect()
     }
 
-    /// Get the token ID for a specific token string
-    pub fn token_to_id(&self, token: &str) -> Option<u32> {
-        self.vocab.get(token).copied()
+    /// Join per-word tokens with space markers; `starts` and `word_ids`
+    /// hold each word's char offset and word index
+    fn join_words(&self, words: &[Vec<Token>], starts: &[usize], word_ids: &[Option<usize>]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        for (idx, word) in words.iter().enumerate() {
+            if idx > 0 {
+                tokens.push(self.space_marker.spanning(starts[idx] - 1, starts[idx], " "));
+            }
+            tokens.extend(word.iter().map(|t| Token {
+                word_id: word_ids[idx],
+                ..t.clone()
+            }));
+        }
+        tokens
     }
 
-    /// Check if a token exists in the vocabulary
-    pub fn contains_token(&self, token: &str) -> bool {
-        self.vocab.contains_key(token)
+    /// Byte-fallback tokens for the UTF-8 encoding of `s`, which starts at
+    /// char offset `start`. Every byte token spans its whole character.
+    fn byte_tokens(&self, s: &str, start: usize) -> Vec<Token> {
+        let mut tokens = Vec::with_capacity(s.len());
+        for (i, ch) in s.chars().enumerate() {
+            let mut buf = [0u8; 4];
+            for &byte in ch.encode_utf8(&mut buf).as_bytes() {
+                tokens.push(Token {
+                    token: byte_token_string(byte),
+                    id: self.byte_offset + u32::from(byte),
+                    token_type: TokenType::Byte,
+                    start: start + i,
+                    end: start + i + 1,
+                    word_id: None,
+                    surface: ch.to_string(),
+                });
+            }
+        }
+        tokens
     }
 
-    /// Encode text and return both tokens and IDs for compatibility
-    pub fn encode_plus(&self, text: &str) -> EncodingResult {
-        let tokens = self.tokenize_text(text);
-        let token_strings: Vec<String> = tokens.iter().map(|t| t.token.clone()).collect();
-        let token_ids: Vec<u32> = tokens.iter().map(|t| t.id).collect();
-        let attention_mask: Vec<u32> = vec![1; token_ids.len()];
-        
-        EncodingResult {
-            input_ids: token_ids,
-            tokens: token_strings,
-            attention_mask,
+    fn tokenize_word(&self, word: &str, start: usize) -> Vec<Token> {
+        if !self.word_cache.is_enabled() {
+            return self.tokenize_word_with(word, start, self.unknown_strategy);
         }
+        self.cached_word(word)
+            .iter()
+            .map(|t| Token {
+                start: start + t.start,
+                end: start + t.end,
+                ..t.clone()
+            })
+            .collect()
     }
-}
-
-/// Result structure for encoding operations
-#[derive(Debug, Clone)]
-pub struct EncodingResult {
-    pub input_ids: Vec<u32>,
-    pub tokens: Vec<String>,
-    pub attention_mask: Vec<u32>,
-}
 
-impl Default for TurkishTokenizer {
-    fn default() -> Self {
-        Self::new_rust().expect("Failed to create TurkishTokenizer")
+    /// Tokens of `word` relative to its start, from the word cache when
+    /// possible
+    fn cached_word(&self, word: &str) -> Arc<[Token]> {
+        if let Some(tokens) = self.word_cache.get(word) {
+            return tokens;
+        }
+        let tokens: Arc<[Token]> = self.tokenize_word_with(word, 0, self.unknown_strategy).into();
+        self.word_cache.insert(word, Arc::clone(&tokens));
+        tokens
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Tokenize a single space-free word starting at char offset `start`
+    fn tokenize_word_with(&self, word: &str, start: usize, unknown_strategy: UnknownStrategy) -> Vec<Token> {
+        let chars: Vec<char> = word.chars().collect();
+        let cleaned = self.cleaned_word(&chars);
+        let mut lowered = Vec::new();
+        let mut result = Vec::new();
+        // Pieces of a cleaned word are placed in the cleaned word
+        let segmented = cleaned.as_ref().map_or(chars.as_slice(), |(cleaned, _)| cleaned);
+        self.segment_word(&chars, unknown_strategy, None, &mut lowered, &mut |piece| {
+            result.push(self.piece_token(piece, start, segmented));
+        });
+        if let Some((_, origins)) = cleaned {
+            sanitize::restore_offsets(&mut result, start, &chars, &origins);
+        }
+        result
+    }
 
-    #[test]
-    fn test_tokenizer_creation() {
-        let tokenizer = TurkishTokenizer::new_rust().unwrap();
-        assert!(tokenizer.vocab_size() > 0);
-        assert_eq!(tokenizer.pad_token, "<pad>");
-        assert_eq!(tokenizer.eos_token, "<eos>");
+    /// IDs of `tokenize_text(text)` without building tokens or strings
+    fn encode_fast(&self, text: &str) -> Vec<u32> {
+        let mut ids = Vec::with_capacity(text.len() / 2);
+        self.for_each_id(text, |id| ids.push(id));
+        ids
     }
 
-    #[test]
-    fn test_basic_encoding() {
-        let tokenizer = TurkishTokenizer::new_rust().unwrap();
+    /// Call `f` with each ID `tokenize_text` would produce, from the word
+    /// cache or by segmenting into one reused pair of char buffers
+    fn for_each_id(&self, text: &str, mut f: impl FnMut(u32)) {
+        let mut word = Vec::new();
+        let mut lowered = Vec::new();
+        for piece in self.split_text(text) {
+            let part = match piece {
+                TextPiece::Whitespace { marker, .. } | TextPiece::Added { added: AddedToken { token: marker, .. }, .. } => {
+                    f(marker.id);
+                    continue;
+                }
+                TextPiece::Word(part, _) => part,
+            };
+            if self.word_cache.is_enabled() {
+                self.cached_word(part).iter().for_each(|t| f(t.id));
+                continue;
+            }
+            word.clear();
+            word.extend(part.chars());
+            self.segment_word(&word, self.unknown_strategy, None, &mut lowered, &mut |piece| f(self.piece_id(&piece)));
+        }
+    }
+
+    /// Split a word into camel-case segments and match each against the
+    /// root, suffix and BPE tables, passing every piece to `emit`. Matches
+    /// may be dropped by `sampler`. `lowered` is scratch space for the
+    /// lowercased segment. Positions are in the word as cleaned by
+    /// `cleaned_word`, if it removes anything.
+    fn segment_word(
+        &self,
+        word: &[char],
+        unknown_strategy: UnknownStrategy,
+        sampler: Option<&mut DropoutSampler>,
+        lowered: &mut Vec<char>,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        let cleaned = self.cleaned_word(word);
+        let word = cleaned.as_ref().map_or(word, |(cleaned, _)| cleaned);
+        let restored = self.restore_diacritics.then(|| self.deasciify_chars(word)).flatten();
+        let word = restored.as_deref().unwrap_or(word);
+        if unknown_strategy != UnknownStrategy::UnknownRun {
+            return self.segment_compounds(word, unknown_strategy, sampler, lowered, emit);
+        }
+        // Hold back each unknown token until the next piece shows whether
+        // the run goes on
+        let mut run: Option<(usize, usize)> = None;
+        self.segment_compounds(word, unknown_strategy, sampler, lowered, &mut |piece| match piece {
+            WordPiece::Unknown { pos, len } => match &mut run {
+                Some((start, run_len)) if *start + *run_len == pos => *run_len += len,
+                _ => {
+                    if let Some((pos, len)) = run.replace((pos, len)) {
+                        emit(WordPiece::Unknown { pos, len });
+                    }
+                }
+            },
+            piece => {
+                if let Some((pos, len)) = run.take() {
+                    emit(WordPiece::Unknown { pos, len });
+                }
+                emit(piece);
+            }
+        });
+        if let Some((pos, len)) = run {
+            emit(WordPiece::Unknown { pos, len });
+        }
+    }
+
+    /// `segment_pieces`, except that a URL, e-mail, mention or hashtag
+    /// found by `find_entity` is emitted following its entity mode, an
+    /// abbreviation found by `find_abbreviation` or a clitic found by
+    /// `clitic_len` gets its token, and a
+    /// foreign stem found by `foreign_stem` is matched against BPE only
+    fn segment_entities(
+        &self,
+        word: &[char],
+        unknown_strategy: UnknownStrategy,
+        mut sampler: Option<&mut DropoutSampler>,
+        lowered: &mut Vec<char>,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        let (start, end) = if let Some((start, len, kind)) = self.find_entity(word) {
+            if start > 0 {
+                self.segment_pieces(&word[..start], unknown_strategy, sampler.as_deref_mut(), lowered, false, emit);
+            }
+            self.emit_entity(&word[start..start + len], kind, start, unknown_strategy, emit);
+            (start, start + len)
+        } else if let Some((start, len, id, capitalized)) = self.find_abbreviation(word) {
+            if start > 0 {
+                self.segment_pieces(&word[..start], unknown_strategy, sampler.as_deref_mut(), lowered, false, emit);
+            }
+            self.emit_abbreviation(&word[start..start + len], (id, capitalized), start, lowered, emit);
+            (start, start + len)
+        } else if let Some(len) = self.clitic_len(word) {
+            self.emit_clitic(&word[..len], emit);
+            (0, len)
+        } else if let Some(len) = self.foreign_stem(word, lowered) {
+            self.segment_pieces(&word[..len], unknown_strategy, sampler.as_deref_mut(), lowered, true, emit);
+            (0, len)
+        } else {
+            return self.segment_pieces(word, unknown_strategy, sampler, lowered, false, emit);
+        };
+        debug_assert!(start < end);
+        if end < word.len() {
+            self.segment_pieces(&word[end..], unknown_strategy, sampler, lowered, false, &mut |piece| {
+                emit(piece.shifted(end))
+            });
+        }
+    }
+
+    /// `segment_word` with one piece per unmatched character. With
+    /// `bpe_only` the root and suffix tables are skipped, and characters
+    /// BPE lacks use their single-character entry (`single_entry`).
+    fn segment_pieces(
+        &self,
+        word: &[char],
+        unknown_strategy: UnknownStrategy,
+        mut sampler: Option<&mut DropoutSampler>,
+        lowered: &mut Vec<char>,
+        bpe_only: bool,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        let mut seg_start = 0;
+        while seg_start < word.len() {
+            let all_caps = match (&self.all_caps_marker, self.emit_case_markers) {
+                (Some(marker), true) => all_caps_end(word, seg_start).map(|end| (marker, end)),
+                _ => None,
+            };
+            let seg_end = if let Some((_, end)) = all_caps {
+                end
+            } else if self.split_camel_case {
+                (seg_start + 1..word.len())
+                    .find(|&i| word[i].is_uppercase())
+                    .unwrap_or(word.len())
+            } else {
+                word.len()
+            };
+            if let Some((marker, _)) = all_caps {
+                emit(WordPiece::Marker { marker, pos: seg_start, len: 0 });
+            } else if self.emit_case_markers && word[seg_start].is_uppercase() {
+                emit(WordPiece::Uppercase(seg_start));
+            }
+
+            lowered.clear();
+            if self.lowercase {
+                for &c in &word[seg_start..seg_end] {
+                    self.push_lower(c, lowered);
+                }
+            } else {
+                lowered.extend_from_slice(&word[seg_start..seg_end]);
+            }
+
+            let mut pos = 0;
+            let mut slot = Slot::Start;
+            // Grapheme cluster ends, found the first time a character is
+            // left unmatched
+            let mut clusters: Option<Vec<usize>> = None;
+            while pos < lowered.len() {
+                if let (NumberMode::Placeholder, Some(marker)) = (self.number_mode, &self.number_marker) {
+                    if lowered[pos].is_ascii_digit() {
+                        let len = number_len(&lowered[pos..]);
+                        emit(WordPiece::Marker { marker, pos: seg_start + pos, len });
+                        slot = Slot::Stem;
+                        pos += len;
+                        continue;
+                    }
+                }
+                let mut end = self.match_end(lowered, pos);
+                if let Some((start, id, len)) = self.next_emoji(lowered, pos, end) {
+                    if start == pos {
+                        emit(WordPiece::Emoji { id, pos: seg_start + pos, len });
+                        slot = Slot::Stem;
+                        pos += len;
+                        continue;
+                    }
+                    end = start;
+                }
+
+                if self.segmentation != SegmentationMode::Greedy {
+                    let sampler = sampler.as_deref_mut();
+                    self.emit_best_path(lowered, pos..end, unknown_strategy, sampler, bpe_only, &mut |piece| {
+                        emit(piece.shifted(seg_start))
+                    });
+                    slot = Slot::Stem;
+                    pos = end;
+                    continue;
+                }
+
+                // Roots take priority over suffixes, suffixes over BPE
+                let tables = [
+                    (&self.root_matcher, TokenType::Root),
+                    (&self.suffix_matcher, TokenType::Suffix),
+                    (&self.bpe_matcher, TokenType::Bpe),
+                ];
+                let skipped = if bpe_only { 2 } else { 0 };
+                let matched = tables.into_iter().skip(skipped).find_map(|(matcher, token_type)| {
+                    // Back off to shorter matches while strict morphology or
+                    // morphotactics reject the longer ones
+                    let mut found = matcher.prefixes(&lowered[pos..end]).find_map(|(id, len)| {
+                        let next = self.accept_match(lowered, pos, len, id, &token_type, slot)?;
+                        keeps_match(&mut sampler, len).then_some((id, len, next))
+                    });
+                    if token_type == TokenType::Root && self.consonant_alternation {
+                        let min_len = found.map_or(0, |(_, len, _)| len);
+                        let alternated = self.alternated_root(lowered, pos, end, min_len);
+                        found = alternated
+                            .filter(|&(_, len)| keeps_match(&mut sampler, len))
+                            .map(|(id, len)| (id, len, Slot::Stem))
+                            .or(found);
+                    }
+                    if self.ascii_fold_matching {
+                        let min_len = found.map_or(0, |(_, len, _)| len);
+                        let folded = self.folded_match(lowered, pos, end, &token_type, min_len).and_then(|(id, len)| {
+                            let next = self.accept_match(lowered, pos, len, id, &token_type, slot)?;
+                            keeps_match(&mut sampler, len).then_some((id, len, next))
+                        });
+                        found = folded.or(found);
+                    }
+                    found.map(|(id, len, next)| (id, len, token_type, next))
+                });
+                if let Some((id, len, token_type, next)) = matched {
+                    slot = next;
+                    emit(WordPiece::Matched {
+                        id,
+                        token_type,
+                        pos: seg_start + pos,
+                        chars: &lowered[pos..pos + len],
+                    });
+                    pos += len;
+                    continue;
+                }
+
+                let cluster_end = clusters.get_or_insert_with(|| cluster_ends(lowered))[pos].min(end);
+                let cluster = &lowered[pos..cluster_end];
+                match bpe_only.then(|| self.single_entry(cluster)).flatten() {
+                    Some((id, token_type)) => emit(WordPiece::Matched {
+                        id,
+                        token_type,
+                        pos: seg_start + pos,
+                        chars: cluster,
+                    }),
+                    None => self.emit_unmatched(cluster, seg_start + pos, unknown_strategy, emit),
+                }
+                slot = Slot::Stem;
+                pos = cluster_end;
+            }
+            seg_start = seg_end;
+        }
+    }
+
+    /// Every root, suffix and BPE match at `chars[i..to]` that survives
+    /// dropout, roots first and longest first within each table
+    fn path_candidates(
+        &self,
+        chars: &[char],
+        i: usize,
+        to: usize,
+        sampler: &mut Option<&mut DropoutSampler>,
+        candidates: &mut Vec<PathStep>,
+    ) {
+        candidates.clear();
+        let tables = [
+            (&self.root_matcher, TokenType::Root),
+            (&self.suffix_matcher, TokenType::Suffix),
+            (&self.bpe_matcher, TokenType::Bpe),
+        ];
+        for (matcher, token_type) in tables {
+            for (id, len) in matcher.prefixes(&chars[i..to]) {
+                if keeps_match(sampler, len) {
+                    candidates.push(PathStep { id, len, token_type: token_type.clone() });
+                }
+            }
+            if token_type == TokenType::Root && self.consonant_alternation {
+                if let Some((id, len)) = self.alternated_root(chars, i, to, 0) {
+                    if keeps_match(sampler, len) {
+                        candidates.push(PathStep { id, len, token_type: TokenType::Root });
+                    }
+                }
+            }
+            if self.ascii_fold_matching {
+                if let Some((id, len)) = self.folded_match(chars, i, to, &token_type, 0) {
+                    if keeps_match(sampler, len) {
+                        candidates.push(PathStep { id, len, token_type: token_type.clone() });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cost of a match on a best path: fixed per token type, or the
+    /// negated log probability under `SegmentationMode::Unigram`
+    fn step_cost(&self, step: &PathStep) -> f64 {
+        match self.segmentation {
+            SegmentationMode::Unigram => self.unigram_cost(step.id),
+            _ => f64::from(match_cost(&step.token_type)),
+        }
+    }
+
+    /// Cost of a character no table matches
+    fn unmatched_cost(&self) -> f64 {
+        match self.segmentation {
+            SegmentationMode::Unigram => self.unigram_unmatched_cost(),
+            _ => f64::from(UNMATCHED_COST),
+        }
+    }
+
+    /// Emit the lowest-cost segmentation of `chars[span]`, found by
+    /// dynamic programming over every root, suffix and BPE match. Ties go
+    /// to the longest root, as in greedy matching. Under morphotactics the
+    /// search also tracks the suffix slot each path has reached. Piece
+    /// positions are offsets in `chars`; `bpe_only` is as for
+    /// `segment_pieces`.
+    fn emit_best_path(
+        &self,
+        chars: &[char],
+        span: std::ops::Range<usize>,
+        unknown_strategy: UnknownStrategy,
+        mut sampler: Option<&mut DropoutSampler>,
+        bpe_only: bool,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        let (from, to) = (span.start, span.end);
+        // Without morphotactics every slot behaves the same, so one suffices
+        let states = if self.morphotactics { Slot::ALL.len() } else { 1 };
+        let state = |slot: Slot| if self.morphotactics { slot as usize } else { 0 };
+        let unmatched_cost = self.unmatched_cost();
+
+        // Unmatched characters are taken a grapheme cluster at a time
+        let cluster_end = {
+            let ends = cluster_ends(&chars[from..to]);
+            move |i: usize| from + ends[i - from]
+        };
+
+        // Cost of the cheapest segmentation of chars[i..to] from each slot
+        // and its first match, `None` for an unmatched cluster
+        let mut costs = vec![[0.0; Slot::ALL.len()]; to - from + 1];
+        let mut steps: Vec<[Option<PathStep>; Slot::ALL.len()]> = vec![Default::default(); to - from];
+        let mut candidates = Vec::new();
+        for i in (from..to).rev() {
+            self.path_candidates(chars, i, to, &mut sampler, &mut candidates);
+            if bpe_only {
+                candidates.retain(|step| step.token_type == TokenType::Bpe);
+                let end = cluster_end(i);
+                if let Some((id, token_type)) = self.single_entry(&chars[i..end]) {
+                    candidates.push(PathStep { id, len: end - i, token_type });
+                }
+            }
+            for (s, &slot) in Slot::ALL[..states].iter().enumerate() {
+                let mut choice = (unmatched_cost + costs[cluster_end(i) - from][state(Slot::Stem)], None);
+                for step in &candidates {
+                    let Some(next) = self.accept_match(chars, i, step.len, step.id, &step.token_type, slot) else {
+                        continue;
+                    };
+                    let cost = self.step_cost(step) + costs[i + step.len - from][state(next)];
+                    if cost < choice.0 {
+                        choice = (cost, Some(step.clone()));
+                    }
+                }
+                (costs[i - from][s], steps[i - from][s]) = choice;
+            }
+        }
+
+        let mut i = from;
+        let mut slot = if from == 0 { Slot::Start } else { Slot::Stem };
+        while i < to {
+            match steps[i - from][state(slot)].take() {
+                Some(PathStep { id, len, token_type }) => {
+                    slot = self.accept_match(chars, i, len, id, &token_type, slot).unwrap_or(Slot::Stem);
+                    emit(WordPiece::Matched {
+                        id,
+                        token_type,
+                        pos: i,
+                        chars: &chars[i..i + len],
+                    });
+                    i += len;
+                }
+                None => {
+                    let end = cluster_end(i);
+                    self.emit_unmatched(&chars[i..end], i, unknown_strategy, emit);
+                    slot = Slot::Stem;
+                    i = end;
+                }
+            }
+        }
+    }
+
+    /// The suffix slot after a `token_type` match of `len` chars at `pos`,
+    /// or `None` if the match is not allowed: under strict morphology
+    /// suffixes must harmonize with what precedes, and under morphotactics
+    /// they must follow a stem in a valid order
+    fn accept_match(
+        &self,
+        chars: &[char],
+        pos: usize,
+        len: usize,
+        id: u32,
+        token_type: &TokenType,
+        slot: Slot,
+    ) -> Option<Slot> {
+        if *token_type != TokenType::Suffix {
+            return Some(Slot::Stem);
+        }
+        if self.strict_morphology && !morphology::harmonizes(&chars[..pos], &chars[pos..pos + len]) {
+            return None;
+        }
+        if self.morphotactics {
+            morphology::next_slot(slot, id)
+        } else {
+            Some(Slot::Stem)
+        }
+    }
+
+    /// Emit byte tokens or the unknown token for a grapheme cluster no
+    /// table matches, or nothing when skipping unknown characters
+    fn emit_unmatched(
+        &self,
+        cluster: &[char],
+        pos: usize,
+        unknown_strategy: UnknownStrategy,
+        emit: &mut impl FnMut(WordPiece<'_>),
+    ) {
+        let len = cluster.len();
+        match unknown_strategy {
+            UnknownStrategy::ByteFallback => {
+                let mut buf = [0u8; 4];
+                for c in cluster {
+                    for &byte in c.encode_utf8(&mut buf).as_bytes() {
+                        emit(WordPiece::Byte { byte, pos, len });
+                    }
+                }
+            }
+            UnknownStrategy::Unknown | UnknownStrategy::UnknownRun => emit(WordPiece::Unknown { pos, len }),
+            UnknownStrategy::Skip => {}
+        }
+    }
+
+    /// Entry for a single grapheme cluster when matching against BPE only,
+    /// since the BPE table lacks single letters. Suffixes sharing their ID
+    /// with other forms are not used, as the decoder would pick a form by
+    /// vowel harmony.
+    fn single_entry(&self, cluster: &[char]) -> Option<(u32, TokenType)> {
+        let cluster: String = cluster.iter().collect();
+        if let Some(&id) = self.roots.get(&cluster) {
+            return Some((id, TokenType::Root));
+        }
+        let id = *self.suffixes.get(&cluster)?;
+        (self.decoder.forms(id)?.len() == 1).then_some((id, TokenType::Suffix))
+    }
+
+    /// Longest root in `chars[pos..end]` longer than `min_len` that only
+    /// matches once its final consonant is alternated ("kitab" in "kitabı"
+    /// for "kitap")
+    fn alternated_root(&self, chars: &[char], pos: usize, end: usize, min_len: usize) -> Option<(u32, usize)> {
+        let mut variant = chars[pos..end].to_vec();
+        for root_end in (pos + min_len + 1..=end).rev() {
+            let last = root_end - 1;
+            let vowel_follows = chars.get(root_end).is_some_and(|&c| morphology::is_vowel(c));
+            let prev = last.checked_sub(1).map(|i| chars[i]);
+            let Some(partner) = morphology::alternate_consonant(prev, chars[last], vowel_follows) else {
+                continue;
+            };
+            let len = root_end - pos;
+            variant[len - 1] = partner;
+            let found = self.root_matcher.longest_prefix(&variant[..len]);
+            variant[len - 1] = chars[last];
+            if let Some((id, matched)) = found.filter(|&(_, matched)| matched == len) {
+                return Some((id, matched));
+            }
+        }
+        None
+    }
+
+    /// End of the span a match starting at `pos` may cover: split-off
+    /// punctuation and digits are matched on their own
+    fn match_end(&self, chars: &[char], pos: usize) -> usize {
+        let isolated = |c: &char| {
+            (self.split_punctuation && PUNCTUATION.contains(c))
+                || (self.number_mode != NumberMode::Bpe && c.is_ascii_digit())
+        };
+        if isolated(&chars[pos]) {
+            pos + 1
+        } else {
+            chars[pos..].iter().position(isolated).map_or(chars.len(), |p| pos + p)
+        }
+    }
+
+    fn piece_id(&self, piece: &WordPiece<'_>) -> u32 {
+        match *piece {
+            WordPiece::Uppercase(_) => self.uppercase_marker.id,
+            WordPiece::Matched { id, .. } => id,
+            WordPiece::Marker { marker, .. } => marker.id,
+            WordPiece::Unknown { .. } => self.unknown_marker.id,
+            WordPiece::Byte { byte, .. } => self.byte_offset + u32::from(byte),
+            WordPiece::Emoji { id, .. } => id,
+        }
+    }
+
+    /// Token for a piece of `word`, which starts at char offset `start`
+    fn piece_token(&self, piece: WordPiece<'_>, start: usize, word: &[char]) -> Token {
+        let surface = |pos: usize, len: usize| word.get(pos..pos + len).unwrap_or_default().iter().collect::<String>();
+        match piece {
+            WordPiece::Uppercase(pos) => self.uppercase_marker.spanning(start + pos, start + pos, ""),
+            WordPiece::Matched { id, token_type, pos, chars } => Token {
+                token: chars.iter().collect(),
+                id,
+                token_type,
+                start: start + pos,
+                end: start + pos + chars.len(),
+                word_id: None,
+                surface: surface(pos, chars.len()),
+            },
+            WordPiece::Marker { marker, pos, len } => marker.spanning(start + pos, start + pos + len, &surface(pos, len)),
+            WordPiece::Unknown { pos, len } => {
+                self.unknown_marker.spanning(start + pos, start + pos + len, &surface(pos, len))
+            }
+            WordPiece::Byte { byte, pos, len } => Token {
+                token: byte_token_string(byte),
+                id: self.byte_offset + u32::from(byte),
+                token_type: TokenType::Byte,
+                start: start + pos,
+                end: start + pos + len,
+                word_id: None,
+                surface: surface(pos, len),
+            },
+            WordPiece::Emoji { id, pos, len } => Token {
+                token: self.id_to_token(id).unwrap_or_default().to_string(),
+                id,
+                token_type: TokenType::Root,
+                start: start + pos,
+                end: start + pos + len,
+                word_id: None,
+                surface: surface(pos, len),
+            },
+        }
+    }
+
+    /// Convert token strings to IDs; tokens outside the vocabulary become
+    /// the `<unknown>` ID, as in Hugging Face tokenizers
+    pub fn convert_tokens_to_ids(&self, tokens: &[String]) -> Vec<u32> {
+        tokens
+            .iter()
+            .map(|token| self.token_to_id(token).unwrap_or(self.unknown_marker.id))
+            .collect()
+    }
+
+    /// Convert token strings to IDs, failing on the first token outside
+    /// the vocabulary
+    pub fn try_convert_tokens_to_ids(&self, tokens: &[String]) -> Result<Vec<u32>, TurkishTokenizerError> {
+        tokens
+            .iter()
+            .map(|token| {
+                self.token_to_id(token)
+                    .ok_or_else(|| TurkishTokenizerError::UnknownToken(token.clone()))
+            })
+            .collect()
+    }
+
+    /// Convert IDs to token strings; IDs outside the vocabulary become `<unknown>`
+    pub fn convert_ids_to_tokens(&self, ids: &[u32]) -> Vec<String> {
+        ids.iter()
+            .map(|&id| self.id_to_token(id).unwrap_or(&self.unknown_marker.token).to_string())
+            .collect()
+    }
+
+    /// Get the token string for an ID. When several surface forms share an
+    /// ID (e.g. `lar`/`ler`), the first one in the vocabulary is returned.
+    pub fn id_to_token(&self, id: u32) -> Option<&str> {
+        self.decoder
+            .forms(id)
+            .and_then(|forms| forms.first())
+            .map(String::as_str)
+    }
+
+    /// Get the token ID for a specific token string
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.vocab.get(token).copied()
+    }
+
+    /// The token unmatched text maps to when byte fallback is off
+    pub fn unk_token(&self) -> &str {
+        &self.unknown_marker.token
+    }
+
+    pub fn unk_token_id(&self) -> u32 {
+        self.unknown_marker.id
+    }
+
+    /// Check if a token exists in the vocabulary
+    pub fn contains_token(&self, token: &str) -> bool {
+        self.vocab.contains_key(token)
+    }
+
+    /// `encode_plus`, framing the sequence as the post-processor says
+    /// (BOS/EOS by default) when `add_special_tokens` is set
+    pub fn encode_plus_with(&self, text: &str, add_special_tokens: bool) -> EncodingResult {
+        let result = self.encode_plus(text);
+        if add_special_tokens {
+            self.post_process(&result, None)
+        } else {
+            result
+        }
+    }
+
+    /// Encode text and return both tokens and IDs for compatibility
+    pub fn encode_plus(&self, text: &str) -> EncodingResult {
+        let tokens = self.tokenize_text(text);
+        let token_strings: Vec<String> = tokens.iter().map(|t| t.token.clone()).collect();
+        let token_ids: Vec<u32> = tokens.iter().map(|t| t.id).collect();
+        let attention_mask: Vec<u32> = vec![1; token_ids.len()];
+        let token_type_ids: Vec<u32> = vec![0; token_ids.len()];
+        let word_ids: Vec<Option<usize>> = tokens.iter().map(|t| t.word_id).collect();
+        let offset_mapping: Vec<(usize, usize)> = tokens.iter().map(|t| (t.start, t.end)).collect();
+        let special_tokens_mask: Vec<u32> = vec![0; token_ids.len()];
+        
+        EncodingResult {
+            input_ids: token_ids,
+            tokens: token_strings,
+            attention_mask,
+            token_type_ids,
+            word_ids,
+            offset_mapping,
+            special_tokens_mask,
+        }
+    }
+
+    /// Split the encoding of `text` into windows of at most `max_length`
+    /// tokens, each repeating the last `stride` tokens of the previous one
+    /// (Hugging Face's `return_overflowing_tokens`). Offsets and word IDs
+    /// stay relative to the whole text. Fails if `stride` is not smaller
+    /// than `max_length`.
+    pub fn encode_chunks(
+        &self,
+        text: &str,
+        max_length: usize,
+        stride: usize,
+    ) -> Result<Vec<EncodingResult>, TurkishTokenizerError> {
+        if stride >= max_length {
+            return Err(TurkishTokenizerError::InvalidConfig(format!(
+                "stride {} must be smaller than max_length {}",
+                stride, max_length
+            )));
+        }
+        let full = self.encode_plus(text);
+        let len = full.input_ids.len();
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + max_length).min(len);
+            chunks.push(full.window(start..end));
+            if end == len {
+                return Ok(chunks);
+            }
+            start = end - stride;
+        }
+    }
+
+    /// Encode a text pair with the post-processor's pair template, by
+    /// default `<cls> A <sep> B <sep>`. `token_type_ids` is 0 for the first
+    /// segment (including `<cls>` and its `<sep>`) and 1 for the second.
+    pub fn encode_pair(&self, text_a: &str, text_b: &str) -> EncodingResult {
+        self.post_process(&self.encode_plus(text_a), Some(&self.encode_plus(text_b)))
+    }
+}
+
+/// Result structure for encoding operations
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodingResult {
+    pub input_ids: Vec<u32>,
+    pub tokens: Vec<String>,
+    pub attention_mask: Vec<u32>,
+    /// Segment of each token: 0 for the first text, 1 for the second of a pair
+    pub token_type_ids: Vec<u32>,
+    /// Source word of each token within its text, `None` for spaces and special tokens
+    pub word_ids: Vec<Option<usize>>,
+    /// Char span of each token within its text, `(0, 0)` for special tokens
+    pub offset_mapping: Vec<(usize, usize)>,
+    /// 1 for special tokens added by the post-processor and for padding
+    pub special_tokens_mask: Vec<u32>,
+}
+
+impl EncodingResult {
+    /// Append a special token to segment `type_id`
+    fn push_special(&mut self, id: u32, token: &str, type_id: u32) {
+        self.input_ids.push(id);
+        self.tokens.push(token.to_string());
+        self.attention_mask.push(1);
+        self.token_type_ids.push(type_id);
+        self.word_ids.push(None);
+        self.offset_mapping.push((0, 0));
+        self.special_tokens_mask.push(1);
+    }
+
+    /// Append the tokens of `other` as segment `type_id`
+    fn extend(&mut self, other: &EncodingResult, type_id: u32) {
+        self.input_ids.extend_from_slice(&other.input_ids);
+        self.tokens.extend_from_slice(&other.tokens);
+        self.attention_mask.extend_from_slice(&other.attention_mask);
+        self.token_type_ids.extend(std::iter::repeat_n(type_id, other.input_ids.len()));
+        self.word_ids.extend_from_slice(&other.word_ids);
+        self.offset_mapping.extend_from_slice(&other.offset_mapping);
+        self.special_tokens_mask.extend_from_slice(&other.special_tokens_mask);
+    }
+
+    /// The tokens in `range`
+    fn window(&self, range: std::ops::Range<usize>) -> EncodingResult {
+        EncodingResult {
+            input_ids: self.input_ids[range.clone()].to_vec(),
+            tokens: self.tokens[range.clone()].to_vec(),
+            attention_mask: self.attention_mask[range.clone()].to_vec(),
+            token_type_ids: self.token_type_ids[range.clone()].to_vec(),
+            word_ids: self.word_ids[range.clone()].to_vec(),
+            offset_mapping: self.offset_mapping[range.clone()].to_vec(),
+            special_tokens_mask: self.special_tokens_mask[range].to_vec(),
+        }
+    }
+
+    /// Drop tokens beyond `max_length`
+    pub fn truncate(&mut self, max_length: usize) {
+        self.input_ids.truncate(max_length);
+        self.tokens.truncate(max_length);
+        self.attention_mask.truncate(max_length);
+        self.token_type_ids.truncate(max_length);
+        self.word_ids.truncate(max_length);
+        self.offset_mapping.truncate(max_length);
+        self.special_tokens_mask.truncate(max_length);
+    }
+
+    /// Pad up to `length` with the pad token, masking the padded positions.
+    /// Sequences already at least `length` long are left unchanged.
+    pub fn pad(&mut self, length: usize, pad_id: u32, pad_token: &str, side: PaddingSide) {
+        let missing = length.saturating_sub(self.input_ids.len());
+        if missing == 0 {
+            return;
+        }
+        match side {
+            PaddingSide::Right => {
+                self.input_ids.extend(std::iter::repeat_n(pad_id, missing));
+                self.tokens.extend(std::iter::repeat_n(pad_token.to_string(), missing));
+                self.attention_mask.extend(std::iter::repeat_n(0, missing));
+                self.token_type_ids.extend(std::iter::repeat_n(0, missing));
+                self.word_ids.extend(std::iter::repeat_n(None, missing));
+                self.offset_mapping.extend(std::iter::repeat_n((0, 0), missing));
+                self.special_tokens_mask.extend(std::iter::repeat_n(1, missing));
+            }
+            PaddingSide::Left => {
+                self.input_ids.splice(0..0, std::iter::repeat_n(pad_id, missing));
+                self.tokens.splice(0..0, std::iter::repeat_n(pad_token.to_string(), missing));
+                self.attention_mask.splice(0..0, std::iter::repeat_n(0, missing));
+                self.token_type_ids.splice(0..0, std::iter::repeat_n(0, missing));
+                self.word_ids.splice(0..0, std::iter::repeat_n(None, missing));
+                self.offset_mapping.splice(0..0, std::iter::repeat_n((0, 0), missing));
+                self.special_tokens_mask.splice(0..0, std::iter::repeat_n(1, missing));
+            }
+        }
+    }
+}
+
+/// Vocabulary scales the crate can embed, each behind its own feature so
+/// unused ones add nothing to the binary. Other vocabularies are loaded at
+/// runtime with `TurkishTokenizer::from_dir`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VocabSize {
+    /// The 32,768-token vocabulary, embedded by the default `vocab-base`
+    /// feature
+    #[default]
+    Base,
+}
+
+impl VocabSize {
+    pub const ALL: [VocabSize; 1] = [VocabSize::Base];
+
+    /// Cargo feature that embeds this vocabulary
+    pub fn feature(self) -> &'static str {
+        match self {
+            VocabSize::Base => "vocab-base",
+        }
+    }
+
+    /// Whether this build embeds the vocabulary
+    pub fn is_embedded(self) -> bool {
+        self.embedded().is_some()
+    }
+
+    /// Tables precompiled from the JSON files by build.rs
+    fn embedded(self) -> Option<&'static [u8]> {
+        match self {
+            #[cfg(feature = "vocab-base")]
+            VocabSize::Base => Some(include_bytes!(concat!(env!("OUT_DIR"), "/vocab_base.bin"))),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+/// How characters that match no root, suffix or BPE entry are emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownStrategy {
+    /// One `<unknown>` token per character
+    Unknown,
+    /// One `<unknown>` token per run of adjacent unmatched characters
+    /// within a word
+    UnknownRun,
+    /// The character's UTF-8 bytes as byte tokens
+    #[default]
+    ByteFallback,
+    /// Nothing; the characters are dropped and do not decode
+    Skip,
+}
+
+/// How digits are tokenized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberMode {
+    /// Match digits like any other text, usually through BPE
+    #[default]
+    Bpe,
+    /// One token per digit
+    Digits,
+    /// One `<number>` token per number; the digits are not recoverable
+    Placeholder,
+}
+
+/// How a word is split into vocabulary matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentationMode {
+    /// Take the longest root at each position, else the longest suffix,
+    /// else the longest BPE piece
+    #[default]
+    Greedy,
+    /// Choose the segmentation with the lowest total cost over all matches,
+    /// which favours fewer tokens and roots and suffixes over BPE
+    BestPath,
+    /// Choose the segmentation with the highest total log probability
+    /// under the token scores set with `set_scores`, as a unigram language
+    /// model does, so frequent roots and suffixes win over accidental
+    /// longest matches
+    Unigram,
+}
+
+/// Special tokens `encode_plus_with` and `encode_pair` wrap texts in
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessor {
+    /// `<bos> A <eos>`, following `add_bos_token` and `add_eos_token`;
+    /// pairs as `<cls> A <sep> B <sep>`
+    #[default]
+    BosEos,
+    /// `<cls> A <sep>`, as BERT-style encoders expect; pairs as
+    /// `<cls> A <sep> B <sep>`
+    ClsSep,
+    /// Custom templates for single texts and pairs
+    Template(TemplateProcessing),
+}
+
+/// Which whitespace separates words and how it is emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WhitespaceMode {
+    /// Only spaces separate words, one space token each; tabs and newlines
+    /// are tokenized as part of the neighbouring word
+    #[default]
+    Space,
+    /// Spaces, tabs and newlines separate words and each becomes its own
+    /// token, so the whitespace decodes exactly
+    Preserve,
+    /// Each run of spaces, tabs and newlines becomes a single token: a
+    /// newline if the run contains one, otherwise a space
+    Collapse,
+}
+
+/// How sequences are padded, following Hugging Face's `padding` argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaddingStrategy {
+    /// Leave sequences at their natural length
+    #[default]
+    DoNotPad,
+    /// Pad to the longest sequence in the batch
+    Longest,
+    /// Pad to an explicit `max_length`
+    MaxLength,
+}
+
+/// Which end of the sequence receives padding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaddingSide {
+    Left,
+    #[default]
+    Right,
+}
+
+/// Which sequence of a pair loses tokens when it is cut to `max_length`,
+/// following Hugging Face's `truncation` argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TruncationStrategy {
+    /// Cut the longer sequence, then both evenly once they are the same
+    /// length
+    #[default]
+    LongestFirst,
+    /// Cut only the first sequence, e.g. a long document before a question
+    OnlyFirst,
+    /// Cut only the second sequence, keeping a QA question whole
+    OnlySecond,
+}
+
+impl Default for TurkishTokenizer {
+    fn default() -> Self {
+        Self::new().expect("Failed to create TurkishTokenizer")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenizer_creation() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        assert!(tokenizer.vocab_size() > 0);
+        assert_eq!(tokenizer.pad_token, "<pad>");
+        assert_eq!(tokenizer.eos_token, "<eos>");
+    }
+
+    #[test]
+    fn test_basic_encoding() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
         let text = "merhaba dünya";
         let tokens = tokenizer.encode(text);
         assert!(!tokens.is_empty());
@@ -471,7 +2142,7 @@ mod tests {
 
     #[test]
     fn test_tokenization() {
-        let tokenizer = TurkishTokenizer::new_rust().unwrap();
+        let tokenizer = TurkishTokenizer::new().unwrap();
         let text = "merhaba dünya";
         let tokens = tokenizer.tokenize(text);
         assert!(!tokens.is_empty());
@@ -480,7 +2151,7 @@ mod tests {
 
     #[test]
     fn test_camel_case() {
-        let tokenizer = TurkishTokenizer::new_rust().unwrap();
+        let tokenizer = TurkishTokenizer::new().unwrap();
         let text = "merhabaDünya";
         let tokens = tokenizer.tokenize(text);
         assert!(!tokens.is_empty());
@@ -489,7 +2160,7 @@ mod tests {
 
     #[test]
     fn test_encode_plus() {
-        let tokenizer = TurkishTokenizer::new_rust().unwrap();
+        let tokenizer = TurkishTokenizer::new().unwrap();
         let text = "merhaba dünya";
         let result = tokenizer.encode_plus(text);
         
@@ -499,26 +2170,215 @@ mod tests {
     }
 
     #[test]
-    fn test_token_utilities() {
-        let tokenizer = TurkishTokenizer::new_rust().unwrap();
-        
-        // Test token_to_id
-        assert_eq!(tokenizer.token_to_id("<pad>"), Some(tokenizer.pad_token_id));
-        assert_eq!(tokenizer.token_to_id("<eos>"), Some(tokenizer.eos_token_id));
-        assert_eq!(tokenizer.token_to_id("nonexistent_token"), None);
-        
-        // Test contains_token
-        assert!(tokenizer.contains_token("<pad>"));
-        assert!(tokenizer.contains_token("<eos>"));
-        assert!(!tokenizer.contains_token("nonexistent_token"));
+    fn test_encode_plus_padding_and_truncation() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let base = tokenizer.encode_plus("merhaba dünya");
+        assert_eq!(base.input_ids.len(), 3);
+
+        let mut padded = base.clone();
+        padded.pad(5, tokenizer.pad_token_id, &tokenizer.pad_token, PaddingSide::Right);
+        assert_eq!(padded.input_ids[3..], [tokenizer.pad_token_id; 2]);
+        assert_eq!(padded.attention_mask, vec![1, 1, 1, 0, 0]);
+        assert_eq!(padded.tokens[4], "<pad>");
+
+        let mut left = base.clone();
+        left.pad(4, tokenizer.pad_token_id, &tokenizer.pad_token, PaddingSide::Left);
+        assert_eq!(left.input_ids[0], tokenizer.pad_token_id);
+        assert_eq!(left.attention_mask, vec![0, 1, 1, 1]);
+
+        let mut truncated = base.clone();
+        truncated.truncate(2);
+        assert_eq!(truncated.input_ids, base.input_ids[..2]);
+        assert_eq!(truncated.tokens.len(), 2);
+        assert_eq!(truncated.attention_mask.len(), 2);
     }
 
     #[test]
-    fn test_turkish_morphology() {
-        let tokenizer = TurkishTokenizer::new_rust().unwrap();
-        
-        // Test complex Turkish word with multiple suffixes
-        let tokens = tokenizer.tokenize_text("kitaplarımızdan");
+    fn test_add_special_tokens() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let plain = tokenizer.encode("merhaba");
+        assert_eq!(tokenizer.encode_with("merhaba", false), plain);
+
+        let framed = tokenizer.encode_with("merhaba", true);
+        assert_eq!(framed, [plain.clone(), vec![tokenizer.eos_token_id]].concat());
+
+        tokenizer.add_bos_token = true;
+        let framed = tokenizer.encode_plus_with("merhaba", true);
+        assert_eq!(framed.input_ids[0], tokenizer.bos_token_id);
+        assert_eq!(framed.tokens.first().unwrap(), "<bos>");
+        assert_eq!(framed.tokens.last().unwrap(), "<eos>");
+        assert_eq!(framed.attention_mask.len(), framed.input_ids.len());
+    }
+
+    #[test]
+    fn test_bos_token_claims_reserved_slot() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert_eq!(tokenizer.token_to_id("<bos>"), Some(tokenizer.bos_token_id));
+        assert!(!tokenizer.contains_token("special_7"));
+        assert_eq!(tokenizer.decode(&[tokenizer.bos_token_id]), "<bos>");
+
+        let id = tokenizer.set_bos_token("<s>").unwrap();
+        assert_eq!(tokenizer.token_to_id("<s>"), Some(id));
+        assert_ne!(id, tokenizer.token_to_id("<bos>").unwrap());
+
+        // Tokens already in the vocabulary keep their ID
+        assert_eq!(tokenizer.set_bos_token("<eos>").unwrap(), tokenizer.eos_token_id);
+    }
+
+    #[test]
+    fn test_word_ids() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let result = tokenizer.encode_plus("Kitaplarımız  geldi");
+
+        // <uppercase> kitap lar ım ız " " " " gel di
+        let expected: Vec<Option<usize>> = [
+            vec![Some(0); 5],
+            vec![None, None],
+            vec![Some(1); result.input_ids.len() - 7],
+        ]
+        .concat();
+        assert_eq!(result.word_ids, expected);
+
+        let framed = tokenizer.encode_plus_with("geldi", true);
+        assert_eq!(framed.word_ids.last(), Some(&None));
+
+        let lossless = tokenizer.tokenize_lossless("a \n b");
+        let word_ids: Vec<Option<usize>> = lossless.iter().map(|t| t.word_id).collect();
+        assert_eq!(word_ids, vec![Some(0), None, None, None, Some(1)]);
+    }
+
+    #[test]
+    fn test_encode_pair() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let result = tokenizer.encode_pair("merhaba", "nasılsın");
+
+        assert_eq!(result.tokens.first().unwrap(), "<cls>");
+        assert_eq!(result.input_ids[0], tokenizer.cls_token_id);
+        let sep_positions: Vec<usize> = result
+            .input_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, &id)| id == tokenizer.sep_token_id)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(sep_positions, vec![2, result.input_ids.len() - 1]);
+
+        assert!(result.token_type_ids[..=2].iter().all(|&t| t == 0));
+        assert!(result.token_type_ids[3..].iter().all(|&t| t == 1));
+        assert_eq!(result.attention_mask.len(), result.input_ids.len());
+    }
+
+    #[test]
+    fn test_pair_truncation() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let question = "Kitabı kim yazdı?";
+        let context = "Bu kitabı geçen yıl Orhan Pamuk yazdı ve çok sevildi.";
+        let (q, c) = (tokenizer.encode(question).len(), tokenizer.encode(context).len());
+        assert_eq!(tokenizer.num_special_tokens_to_add(true), 3);
+        let segment = |encoding: &EncodingResult, type_id| {
+            encoding.token_type_ids.iter().zip(&encoding.special_tokens_mask).filter(|&(&t, &m)| t == type_id && m == 0).count()
+        };
+
+        let max_length = q + 3 + 5;
+        let only_second = tokenizer.encode_pair_truncated(question, context, max_length, TruncationStrategy::OnlySecond).unwrap();
+        assert_eq!(only_second.input_ids.len(), max_length);
+        assert_eq!((segment(&only_second, 0), segment(&only_second, 1)), (q, 5));
+        assert_eq!(*only_second.input_ids.last().unwrap(), tokenizer.sep_token_id);
+        let only_first = tokenizer.encode_pair_truncated(context, question, max_length, TruncationStrategy::OnlyFirst).unwrap();
+        assert_eq!((segment(&only_first, 0), segment(&only_first, 1)), (5, q));
+
+        // Longest first cuts the longer text down to the shorter, then both
+        let longest = tokenizer.encode_pair_truncated(question, context, max_length, TruncationStrategy::LongestFirst).unwrap();
+        let budget = max_length - 3;
+        assert_eq!((segment(&longest, 0), segment(&longest, 1)), (budget - budget / 2, budget / 2));
+        let longest = tokenizer.encode_pair_truncated(question, context, q + c, TruncationStrategy::LongestFirst).unwrap();
+        assert_eq!((segment(&longest, 0), segment(&longest, 1)), (q, c - 3));
+        let untouched = tokenizer.encode_pair_truncated(question, context, 512, TruncationStrategy::OnlySecond).unwrap();
+        assert_eq!(untouched, tokenizer.encode_pair(question, context));
+
+        assert!(tokenizer.encode_pair_truncated(question, context, q + 3, TruncationStrategy::OnlySecond).is_err());
+        assert!(tokenizer.encode_pair_truncated(question, context, 2, TruncationStrategy::LongestFirst).is_err());
+
+        let single = tokenizer.encode_plus_truncated(context, true, 6, TruncationStrategy::LongestFirst).unwrap();
+        assert_eq!(single.input_ids.len(), 6);
+        assert_eq!(single.special_tokens_mask.iter().sum::<u32>(), tokenizer.num_special_tokens_to_add(false) as u32);
+        assert_eq!(tokenizer.encode_plus_truncated(context, false, 4, TruncationStrategy::OnlyFirst).unwrap().input_ids.len(), 4);
+        assert!(tokenizer.encode_plus_truncated(context, false, 4, TruncationStrategy::OnlySecond).is_err());
+    }
+
+    #[test]
+    fn test_encode_options() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let text = "Bu kitabı geçen yıl okudum.";
+        assert_eq!(tokenizer.encode_with_options(text, None, &EncodeOptions::default()).unwrap(), tokenizer.encode_plus(text));
+        let options = EncodeOptions::new().add_special_tokens(true);
+        assert_eq!(tokenizer.encode_with_options(text, None, &options).unwrap(), tokenizer.encode_plus_with(text, true));
+        assert_eq!(tokenizer.encode_with_options(text, Some("Kim?"), &options).unwrap(), tokenizer.encode_pair(text, "Kim?"));
+
+        let options = options.max_length(6).truncation(TruncationStrategy::LongestFirst);
+        assert_eq!(
+            tokenizer.encode_with_options(text, None, &options).unwrap(),
+            tokenizer.encode_plus_truncated(text, true, 6, TruncationStrategy::LongestFirst).unwrap()
+        );
+        let options = options.max_length(40).padding(PaddingStrategy::MaxLength).padding_side(PaddingSide::Left);
+        let padded = tokenizer.encode_with_options(text, None, &options).unwrap();
+        assert_eq!(padded.input_ids.len(), 40);
+        assert_eq!(padded.input_ids[0], tokenizer.pad_token_id);
+
+        let texts = ["kısa", "biraz daha uzun bir cümle"];
+        let batch = tokenizer
+            .encode_batch_with_options(&texts, None, &EncodeOptions::new().padding(PaddingStrategy::Longest))
+            .unwrap();
+        let longest = tokenizer.encode(texts[1]).len();
+        assert!(batch.iter().all(|e| e.input_ids.len() == longest));
+        assert_eq!(batch[0].attention_mask.iter().sum::<u32>() as usize, tokenizer.encode(texts[0]).len());
+        let pairs = tokenizer.encode_batch_with_options(&texts, Some(&texts[..1]), &EncodeOptions::new());
+        assert!(pairs.is_err());
+        let unbounded = EncodeOptions::new().padding(PaddingStrategy::MaxLength);
+        assert!(tokenizer.encode_with_options(text, None, &unbounded).is_err());
+    }
+
+    #[test]
+    fn test_token_utilities() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        
+        // Test token_to_id
+        assert_eq!(tokenizer.token_to_id("<pad>"), Some(tokenizer.pad_token_id));
+        assert_eq!(tokenizer.token_to_id("<eos>"), Some(tokenizer.eos_token_id));
+        assert_eq!(tokenizer.token_to_id("nonexistent_token"), None);
+        
+        // Test id_to_token
+        assert_eq!(tokenizer.id_to_token(tokenizer.pad_token_id), Some("<pad>"));
+        assert_eq!(tokenizer.id_to_token(tokenizer.token_to_id("ler").unwrap()), Some("lar"));
+        assert_eq!(tokenizer.id_to_token(u32::MAX), None);
+        assert_eq!(
+            tokenizer.convert_ids_to_tokens(&[tokenizer.eos_token_id, u32::MAX]),
+            vec!["<eos>", "<unknown>"]
+        );
+
+        let tokens = vec!["<eos>".to_string(), "nonexistent_token".to_string()];
+        assert_eq!(
+            tokenizer.convert_tokens_to_ids(&tokens),
+            vec![tokenizer.eos_token_id, tokenizer.unk_token_id()]
+        );
+        assert!(matches!(
+            tokenizer.try_convert_tokens_to_ids(&tokens),
+            Err(TurkishTokenizerError::UnknownToken(token)) if token == "nonexistent_token"
+        ));
+        assert_eq!(tokenizer.try_convert_tokens_to_ids(&tokens[..1]).unwrap(), vec![tokenizer.eos_token_id]);
+        
+        // Test contains_token
+        assert!(tokenizer.contains_token("<pad>"));
+        assert!(tokenizer.contains_token("<eos>"));
+        assert!(!tokenizer.contains_token("nonexistent_token"));
+    }
+
+    #[test]
+    fn test_turkish_morphology() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        
+        // Test complex Turkish word with multiple suffixes
+        let tokens = tokenizer.tokenize_text("kitaplarımızdan");
         
         // Should have: kitap (root) + lar (suffix) + ım (suffix) + ız (suffix) + dan (suffix)
         assert_eq!(tokens.len(), 5);
@@ -527,13 +2387,1523 @@ mod tests {
         assert_eq!(tokens[1].token, "lar");
         assert_eq!(tokens[1].token_type, TokenType::Suffix);
     }
-}
 
-/// Python module definition
-#[pymodule]
-fn turkish_tokenizer_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
-    m.add_class::<TurkishTokenizer>()?;
-    m.add_class::<Token>()?;
-    m.add_class::<TokenType>()?;
-    Ok(())
+    #[test]
+    fn test_batch_matches_single() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let texts = ["merhaba dünya", "", "Kitaplarımızdan geliyorum"];
+
+        let batch_ids = tokenizer.encode_batch(&texts);
+        let batch_tokens = tokenizer.tokenize_batch(&texts);
+        let batch_texts = tokenizer.decode_batch(&batch_ids, true);
+        for (i, text) in texts.iter().enumerate() {
+            assert_eq!(batch_ids[i], tokenizer.encode(text));
+            assert_eq!(batch_tokens[i], tokenizer.tokenize(text));
+            assert_eq!(batch_texts[i], tokenizer.decode_with_options(&batch_ids[i], true));
+        }
+    }
+
+    #[test]
+    fn test_token_equality_and_hash() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let tokens = tokenizer.tokenize_text("kitap kitap");
+        assert_eq!(tokens, tokenizer.tokenize_text("kitap kitap"));
+        // Same text at different spans
+        assert_ne!(tokens[0], tokens[2]);
+        let unique: std::collections::HashSet<&Token> = tokens.iter().chain(&tokens).collect();
+        assert_eq!(unique.len(), tokens.len());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let tokens = tokenizer.tokenize_text("Kitaplarımızdan geliyorum");
+        let json = serde_json::to_string(&tokens).unwrap();
+        assert!(json.contains(r#""token_type":"SUFFIX""#));
+        assert_eq!(serde_json::from_str::<Vec<Token>>(&json).unwrap(), tokens);
+
+        let encoding = tokenizer.encode_pair("merhaba", "dünya");
+        let json = serde_json::to_string(&encoding).unwrap();
+        assert_eq!(serde_json::from_str::<EncodingResult>(&json).unwrap(), encoding);
+    }
+
+    #[test]
+    fn test_decode_with_custom_vocab_ids() {
+        // Reserved slots for the default special tokens, digits and punctuation
+        let slots: String = (200..300).map(|id| format!(r#", "special_{id}": {id}"#)).collect();
+        let tokenizer = TurkishTokenizer::from_json(
+            &format!(
+                r#"{{" ": 1, "\n": 2, "\t": 3, "<pad>": 4, "<eos>": 5, "<unknown>": 6, "<uppercase>": 7,
+                    "kitap": 10, "kitab": 10, "işle": 11, "işli": 11{slots}}}"#
+            ),
+            r#"{"lar": 100, "ler": 100, "ı": 101, "i": 101, "yor": 102}"#,
+            "{}",
+        )
+        .unwrap();
+        assert_eq!(tokenizer.decode(&[7, 10, 101, 1, 11, 102, 1, 10, 100, 1, 6]), "Kitabı işliyor kitaplar ▁u▁");
+    }
+
+    #[test]
+    fn test_structured_errors() {
+        let missing = TurkishTokenizer::from_json(r#"{"<uppercase>": 0, "kitap": 1}"#, "{}", "{}");
+        assert!(matches!(missing, Err(TurkishTokenizerError::MissingSpecialToken(token)) if token == "<unknown>"));
+        assert!(matches!(
+            TurkishTokenizer::from_json("[", "{}", "{}"),
+            Err(TurkishTokenizerError::VocabParse(_))
+        ));
+
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let byte_id = tokenizer.byte_offset;
+        assert!(matches!(
+            tokenizer.add_user_tokens(&[("kardiyomiyopati", Some(byte_id))]),
+            Err(TurkishTokenizerError::IdOutOfRange(id)) if id == byte_id
+        ));
+        assert!(matches!(
+            TemplateProcessing::new("$A $A", "$A $B"),
+            Err(TurkishTokenizerError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_clone_shares_vocabulary() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let mut clone = tokenizer.clone();
+        assert!(Arc::ptr_eq(&tokenizer.vocab, &clone.vocab));
+        assert!(Arc::ptr_eq(&tokenizer.root_matcher, &clone.root_matcher));
+        assert_eq!(clone.encode("kitaplar"), tokenizer.encode("kitaplar"));
+
+        // Changing the clone's vocabulary copies it, leaving the original alone
+        clone.add_user_tokens(&[("kardiyomiyopati", None)]).unwrap();
+        assert!(!Arc::ptr_eq(&tokenizer.vocab, &clone.vocab));
+        assert_eq!(clone.tokenize("kardiyomiyopati"), vec!["kardiyomiyopati"]);
+        assert_ne!(tokenizer.tokenize("kardiyomiyopati"), vec!["kardiyomiyopati"]);
+    }
+
+    #[test]
+    fn test_global_instance() {
+        let global = TurkishTokenizer::global();
+        assert!(std::ptr::eq(global, TurkishTokenizer::global()));
+        assert_eq!(global.encode("kitaplar"), TurkishTokenizer::new().unwrap().encode("kitaplar"));
+    }
+
+    #[test]
+    fn test_token_surface() {
+        let mut tokenizer = TurkishTokenizer::builder().whitespace_mode(WhitespaceMode::Collapse).build().unwrap();
+        tokenizer.add_tokens(&["<kod>"]);
+        let text = "Merhaba Dünya,  KİTAPLARIMIZ\n\tİstanbul'da<kod> ☕";
+        let chars: Vec<char> = text.chars().collect();
+        let tokens = tokenizer.tokenize_text(text);
+        for token in &tokens {
+            assert_eq!(token.surface, chars[token.start..token.end].iter().collect::<String>(), "{}", token.token);
+        }
+        let dunya = tokens.iter().find(|token| token.token == "dünya").unwrap();
+        assert_eq!(dunya.surface, "Dünya");
+        assert_eq!(tokens[0].token, tokenizer.uppercase_marker.token);
+        assert_eq!(tokens[0].surface, "");
+
+        let text = "Çok güzel ☕";
+        let chars: Vec<char> = text.chars().collect();
+        for token in tokenizer.tokenize_lossless(text) {
+            assert_eq!(token.surface, chars[token.start..token.end].iter().collect::<String>(), "{}", token.token);
+        }
+    }
+
+    #[test]
+    fn test_vocab_views() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert_eq!(tokenizer.add_tokens(&["covid-19"]), 1);
+        assert_eq!(tokenizer.get_roots()["kitap"], tokenizer.token_to_id("kitap").unwrap());
+        assert!(tokenizer.get_suffixes().contains_key("ler"));
+        assert!(!tokenizer.get_bpe_vocab().contains_key("kitap"));
+
+        let text = "Kitaplarımızdan xyzq ☕ covid-19";
+        for token in tokenizer.tokenize_text(text) {
+            assert_eq!(tokenizer.token_type_of_id(token.id), Some(token.token_type), "{}", token.token);
+        }
+        let ler = tokenizer.token_to_id("ler").unwrap();
+        assert_eq!(tokenizer.token_type_of_id(ler), Some(TokenType::Suffix));
+        assert_eq!(tokenizer.token_type_of_id(tokenizer.byte_offset + 255), Some(TokenType::Byte));
+        assert_eq!(tokenizer.token_type_of_id(u32::MAX), None);
+    }
+
+    #[test]
+    fn test_with_vocab() {
+        let base = TurkishTokenizer::with_vocab(VocabSize::Base).unwrap();
+        assert_eq!(base.get_vocab(), TurkishTokenizer::new().unwrap().get_vocab());
+        assert_eq!(VocabSize::default(), VocabSize::Base);
+        for size in VocabSize::ALL {
+            match TurkishTokenizer::with_vocab(size) {
+                Ok(tokenizer) => assert!(size.is_embedded() && tokenizer.vocab_size() > 0),
+                Err(TurkishTokenizerError::InvalidConfig(message)) => {
+                    assert!(!size.is_embedded());
+                    assert!(message.contains(size.feature()));
+                }
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi_round_trip() {
+        use std::ffi::{CStr, CString};
+
+        let text = CString::new("merhaba dünya").unwrap();
+        unsafe {
+            let tokenizer = ffi::ttk_new();
+            assert!(!tokenizer.is_null());
+
+            let mut len = 0;
+            let ids = ffi::ttk_encode(tokenizer, text.as_ptr(), &mut len);
+            assert_eq!(std::slice::from_raw_parts(ids, len), (*tokenizer).encode("merhaba dünya"));
+
+            let decoded = ffi::ttk_decode(tokenizer, ids, len);
+            assert_eq!(CStr::from_ptr(decoded).to_str().unwrap(), "merhaba dünya");
+            assert!(ffi::ttk_decode(tokenizer, std::ptr::null(), 1).is_null());
+
+            ffi::ttk_free_string(decoded);
+            ffi::ttk_free_ids(ids, len);
+            ffi::ttk_free(tokenizer);
+        }
+    }
+
+    #[cfg(feature = "uniffi")]
+    #[test]
+    fn test_uniffi_tokenizer() {
+        let tokenizer = mobile::TurkishTokenizer::new().unwrap();
+        let native = TurkishTokenizer::new().unwrap();
+        let text = "Merhaba dünya";
+
+        assert_eq!(tokenizer.encode(text), native.encode(text));
+        assert_eq!(tokenizer.count_tokens(text), native.count_tokens(text) as u64);
+        let tokens = tokenizer.tokenize_text(text);
+        let native_tokens = native.tokenize_text(text);
+        assert_eq!(tokens.len(), native_tokens.len());
+        for (token, native_token) in tokens.iter().zip(&native_tokens) {
+            assert_eq!((token.id, token.start, token.end), (native_token.id, native_token.start as u64, native_token.end as u64));
+            assert_eq!(token.word_id, native_token.word_id.map(|id| id as u64));
+        }
+        assert_eq!(tokenizer.decode(&tokenizer.encode(text)), text);
+    }
+
+    #[test]
+    fn test_decode_round_trip() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+
+        for text in ["kitaplarımızdan", "merhaba dünya", "Merhaba Dünya", "geliyorum", "İstanbul"] {
+            let ids = tokenizer.encode(text);
+            assert_eq!(tokenizer.decode(&ids), text);
+        }
+    }
+
+    #[test]
+    fn test_decode_skip_special_tokens() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let mut ids = tokenizer.encode("merhaba");
+        ids.push(tokenizer.eos_token_id);
+        ids.push(tokenizer.pad_token_id);
+
+        assert_eq!(tokenizer.decode_with_options(&ids, true), "merhaba");
+        assert_eq!(tokenizer.decode(&ids), "merhaba<eos><pad>");
+    }
+
+    #[test]
+    fn test_lossless_round_trip() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+
+        for text in [
+            "merhaba  dünya\n",
+            "\tKitaplarımızdan ",
+            "café ☕ naïve\r\n",
+            "TBMM ẞtraße",
+        ] {
+            let ids = tokenizer.encode_lossless(text);
+            assert_eq!(tokenizer.decode(&ids), text);
+        }
+    }
+
+    #[test]
+    fn test_byte_fallback_toggle() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert!(tokenizer.byte_fallback());
+        assert_eq!(tokenizer.tokenize("☕"), vec!["<0xE2>", "<0x98>", "<0x95>"]);
+        assert_eq!(tokenizer.decode(&tokenizer.encode("kahve ☕")), "kahve ☕");
+
+        tokenizer.set_byte_fallback(false);
+        assert_eq!(tokenizer.tokenize("☕"), vec!["<unknown>"]);
+    }
+
+    #[test]
+    fn test_unknown_strategies() {
+        let text = "kitap ☕☕ ev☕";
+        let unknown = |strategy| {
+            let tokenizer = TurkishTokenizer::builder().unknown_strategy(strategy).build().unwrap();
+            let tokens = tokenizer.tokenize_text(text);
+            assert_eq!(tokens.iter().map(|t| t.id).collect::<Vec<_>>(), tokenizer.encode(text));
+            assert_eq!(tokenizer.count_tokens(text), tokens.len());
+            let unk_id = tokenizer.unk_token_id();
+            tokens
+                .into_iter()
+                .filter(|t| t.id == unk_id || t.token_type == TokenType::Byte)
+                .map(|t| (t.surface, t.start, t.end))
+                .collect::<Vec<_>>()
+        };
+
+        let per_char = unknown(UnknownStrategy::Unknown);
+        assert_eq!(per_char.iter().map(|(s, ..)| s.as_str()).collect::<Vec<_>>(), vec!["☕", "☕", "☕"]);
+        let runs = unknown(UnknownStrategy::UnknownRun);
+        assert_eq!(runs, vec![("☕☕".to_string(), 6, 8), ("☕".to_string(), 11, 12)]);
+        assert_eq!(unknown(UnknownStrategy::ByteFallback).len(), 9);
+        assert!(unknown(UnknownStrategy::Skip).is_empty());
+
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        tokenizer.set_unknown_strategy(UnknownStrategy::Skip);
+        assert_eq!(tokenizer.decode(&tokenizer.encode("kahve☕")), "kahve");
+        assert_eq!(tokenizer.unknown_strategy(), UnknownStrategy::Skip);
+    }
+
+    #[test]
+    fn test_grapheme_clusters() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        let text = format!("aile {} 🇹🇷 ☕", family);
+        let mut tokenizer = TurkishTokenizer::builder().unknown_strategy(UnknownStrategy::Unknown).build().unwrap();
+        let unknown: Vec<Token> = tokenizer
+            .tokenize_text(&text)
+            .into_iter()
+            .filter(|t| t.id == tokenizer.unk_token_id())
+            .collect();
+        let surfaces: Vec<&str> = unknown.iter().map(|t| t.surface.as_str()).collect();
+        assert_eq!(surfaces, vec![family, "🇹🇷", "☕"]);
+        assert_eq!((unknown[0].start, unknown[0].end), (5, 10));
+
+        for mode in [SegmentationMode::BestPath, SegmentationMode::Unigram] {
+            tokenizer.set_segmentation(mode);
+            assert_eq!(tokenizer.tokenize(&text).iter().filter(|t| *t == "<unknown>").count(), 3);
+        }
+
+        // Byte tokens of a cluster all span it and still decode exactly
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let bytes: Vec<Token> = tokenizer
+            .tokenize_text(family)
+            .into_iter()
+            .filter(|t| t.token_type == TokenType::Byte)
+            .collect();
+        assert_eq!(bytes.len(), family.len());
+        assert!(bytes.iter().all(|t| (t.start, t.end) == (0, 5) && t.surface == family));
+        assert_eq!(tokenizer.decode(&tokenizer.encode(&text)), text);
+    }
+
+    #[test]
+    fn test_emoji_tokens() {
+        let text = "harika 😂😂 ❤ 🦩";
+        let plain = TurkishTokenizer::new().unwrap();
+        let mut tokenizer = TurkishTokenizer::builder().emoji_tokens(true).build().unwrap();
+        assert!(tokenizer.vocab_size() > plain.vocab_size());
+        let laugh = tokenizer.emoji_tokens().into_iter().find(|(token, _)| token == "😂").unwrap().1;
+        let heart = tokenizer.emoji_tokens().into_iter().find(|(token, _)| token == "❤️").unwrap().1;
+
+        // One token per known emoji, with or without the variation selector;
+        // unseen ones still fall back to bytes
+        let tokens = tokenizer.tokenize_text(text);
+        let ids: Vec<u32> = tokens.iter().map(|t| t.id).collect();
+        assert_eq!(ids.iter().filter(|&&id| id == laugh).count(), 2);
+        assert!(ids.contains(&heart));
+        assert_eq!(tokens.iter().filter(|t| t.token_type == TokenType::Byte).count(), "🦩".len());
+        let heart_token = tokens.iter().find(|t| t.id == heart).unwrap();
+        assert_eq!((heart_token.surface.as_str(), heart_token.start, heart_token.end), ("❤", 10, 11));
+        assert_eq!(tokenizer.encode(text), ids);
+        assert_eq!(tokenizer.encode("❤\u{FE0F}"), vec![heart]);
+        assert_eq!(tokenizer.decode(&tokenizer.encode("harika 😂")), "harika 😂");
+
+        for mode in [SegmentationMode::BestPath, SegmentationMode::Unigram] {
+            tokenizer.set_segmentation(mode);
+            assert_eq!(tokenizer.encode(text), ids);
+        }
+        tokenizer.set_segmentation(SegmentationMode::Greedy);
+
+        assert_eq!(tokenizer.add_emoji_tokens(&["😂", "🦩"]), 1);
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.emoji_tokens(), tokenizer.emoji_tokens());
+        assert_eq!(restored.encode(text), tokenizer.encode(text));
+    }
+
+    #[test]
+    fn test_entities() {
+        let text = "Kaynak (https://www.haber.com/spor). Yaz: ali.veli@ornek.com.tr @ahmet'e #bugün";
+        let tokenizer = TurkishTokenizer::builder()
+            .entity_mode(EntityKind::Url, EntityMode::Placeholder)
+            .entity_mode(EntityKind::Email, EntityMode::Placeholder)
+            .entity_mode(EntityKind::Mention, EntityMode::Placeholder)
+            .entity_mode(EntityKind::Hashtag, EntityMode::Bpe)
+            .build()
+            .unwrap();
+        let tokens = tokenizer.tokenize_text(text);
+        let placeholders: Vec<(&str, &str)> = tokens
+            .iter()
+            .filter(|t| t.token_type != TokenType::Byte && t.token.starts_with('<') && t.token != "<uppercase>")
+            .map(|t| (t.token.as_str(), t.surface.as_str()))
+            .collect();
+        assert_eq!(
+            placeholders,
+            vec![("<url>", "https://www.haber.com/spor"), ("<email>", "ali.veli@ornek.com.tr"), ("<mention>", "@ahmet")]
+        );
+        let mention = tokens.iter().position(|t| t.token == "<mention>").unwrap();
+        assert_eq!(tokens[mention + 1].start, tokens[mention].end);
+        assert_eq!(tokenizer.encode(text), tokens.iter().map(|t| t.id).collect::<Vec<_>>());
+
+        // Hashtags keep their case without markers and come back unchanged
+        assert!(!tokenizer.tokenize("#BugünMaçVar").contains(&"<uppercase>".to_string()));
+        assert_eq!(tokenizer.decode(&tokenizer.encode("#BugünMaçVar")), "#BugünMaçVar");
+
+        let plain = TurkishTokenizer::new().unwrap();
+        assert!(!plain.tokenize(text).iter().any(|t| t == "<url>"));
+        assert_eq!(plain.entity_modes(), EntityModes::default());
+
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.entity_modes(), tokenizer.entity_modes());
+        assert_eq!(restored.encode(text), tokenizer.encode(text));
+    }
+
+    #[test]
+    fn test_foreign_words() {
+        let patterns = TurkishTokenizer::builder().foreign_word_mode(ForeignWordMode::Patterns).build().unwrap();
+        assert!(patterns.is_foreign_word("software"));
+        assert!(patterns.is_foreign_word("Thanks"));
+        assert!(!patterns.is_foreign_word("deploy"));
+        assert!(!patterns.is_foreign_word("ishal"));
+
+        let tokenizer = TurkishTokenizer::builder().foreign_word_mode(ForeignWordMode::Dictionary).build().unwrap();
+        assert!(tokenizer.is_foreign_word("deploy"));
+        assert!(!tokenizer.is_foreign_word("ettik"));
+        assert!(!tokenizer.is_foreign_word("kitaplarımızdan"));
+        assert!(!TurkishTokenizer::new().unwrap().is_foreign_word("software"));
+
+        // Foreign words get no morphological split; suffixes after an
+        // apostrophe still do
+        let text = "Meeting'e software deploy ettik";
+        let tokens = tokenizer.tokenize_text(text);
+        assert_eq!(tokenizer.tokenize("meeting"), vec!["meet", "ing"]);
+        assert_eq!(&tokenizer.tokenize("Meeting'e")[3..], ["'", "e"]);
+        assert_eq!(tokenizer.tokenize("ettik"), TurkishTokenizer::new().unwrap().tokenize("ettik"));
+        assert_eq!(tokenizer.encode(text), tokens.iter().map(|t| t.id).collect::<Vec<_>>());
+        assert_eq!(tokenizer.decode(&tokenizer.encode("software deploy")), "software deploy");
+
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.foreign_word_mode(), ForeignWordMode::Dictionary);
+    }
+
+    #[test]
+    fn test_deasciify() {
+        let plain = TurkishTokenizer::new().unwrap();
+        assert_eq!(plain.deasciify("Gunaydin arkadaslar, nasilsiniz?"), "Günaydın arkadaşlar, nasılsınız?");
+        assert_eq!(plain.deasciify("Turkiye'nin baskenti"), "Türkiye'nin başkenti");
+        assert_eq!(plain.deasciify("simdi gelecegim"), "şimdi geleceğim");
+        for typed in ["gidiyor", "kitap okudum", "deploy", "günaydın"] {
+            assert_eq!(plain.deasciify(typed), typed);
+        }
+        assert_ne!(plain.encode("gunaydin"), plain.encode("günaydın"));
+
+        let tokenizer = TurkishTokenizer::builder().restore_diacritics(true).build().unwrap();
+        assert!(tokenizer.restore_diacritics());
+        let text = "Gunaydin arkadaslar";
+        assert_eq!(tokenizer.encode(text), plain.encode("Günaydın arkadaşlar"));
+        let tokens = tokenizer.tokenize_text(text);
+        assert_eq!(tokenizer.encode(text), tokens.iter().map(|t| t.id).collect::<Vec<_>>());
+        let root = tokens.iter().find(|t| t.token == "günaydın").unwrap();
+        assert_eq!((root.surface.as_str(), root.start, root.end), ("Gunaydin", 0, 8));
+        // A single call can deasciify without the setting
+        assert_eq!(plain.encode(&plain.deasciify(text)), tokenizer.encode(text));
+
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.encode(text), tokenizer.encode(text));
+    }
+
+    #[test]
+    fn test_ascii_fold_matching() {
+        let plain = TurkishTokenizer::new().unwrap();
+        assert!(!plain.ascii_fold_matching());
+        assert_ne!(plain.encode("cocuklar"), plain.encode("çocuklar"));
+
+        let tokenizer = TurkishTokenizer::builder().ascii_fold_matching(true).build().unwrap();
+        assert_eq!(tokenizer.encode("cocuklar"), plain.encode("çocuklar"));
+        assert_eq!(tokenizer.decode(&tokenizer.encode("cocuklar")), "çocuklar");
+        // Exact matches are unaffected
+        for text in ["çocuklar", "kitap okudum"] {
+            assert_eq!(tokenizer.encode(text), plain.encode(text));
+        }
+        // Token text keeps the typed surface
+        let tokens = tokenizer.tokenize_text("cocuklar");
+        assert_eq!((tokens[0].token.as_str(), tokens[0].start, tokens[0].end), ("cocuk", 0, 5));
+
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert!(restored.ascii_fold_matching());
+        assert_eq!(restored.encode("cocuklar"), tokenizer.encode("cocuklar"));
+    }
+
+    #[test]
+    fn test_clitics() {
+        let plain = TurkishTokenizer::new().unwrap();
+        assert!(!plain.clitics());
+        // The locative suffix takes "ta" after a hard consonant
+        assert_eq!(plain.decode(&plain.encode("kitap da var")), "kitap ta var");
+
+        let tokenizer = TurkishTokenizer::builder().clitics(true).build().unwrap();
+        let particle = tokenizer.token_to_id(QUESTION_PARTICLE_TOKEN).unwrap();
+        let conjunction = tokenizer.token_to_id(CLITIC_DE_TOKEN).unwrap();
+        assert_eq!(tokenizer.tokenize("geliyor musun?"), ["gel", "i", "yor", " ", "mu", "su", "n", "?"]);
+        assert_eq!(tokenizer.encode("okul mu")[2], particle);
+        assert_eq!(tokenizer.encode("güzel mi")[2], particle);
+        assert_eq!(tokenizer.encode("ben de")[2], conjunction);
+        assert_eq!(tokenizer.token_type_of_id(particle), Some(TokenType::Suffix));
+        for text in ["kitap da var", "ben de geldim", "gelmiyor musunuz?", "hasta mıydı", "sen de mi geldin", "okul mu"] {
+            assert_eq!(tokenizer.decode(&tokenizer.encode(text)), text);
+        }
+        // Words that only look like clitics are segmented as usual
+        for text in ["mısır", "De bakalım", "dede"] {
+            assert_eq!(tokenizer.encode(text), plain.encode(text));
+        }
+
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert!(restored.clitics());
+        assert_eq!(restored.decode(&restored.encode("kitap da var")), "kitap da var");
+    }
+
+    #[test]
+    fn test_hyphenation() {
+        let plain = TurkishTokenizer::new().unwrap();
+        let joining = TurkishTokenizer::builder().join_line_breaks(true).build().unwrap();
+        assert!(joining.join_line_breaks() && !plain.join_line_breaks());
+        assert_eq!(plain.tokenize("kitap-\nlar"), ["kitap", "-", "\n", "lar"]);
+        for text in ["kitap-\nlar", "ki-\ntaplar", "ki-\r\ntaplar", "kitap\u{ad}lar"] {
+            assert_eq!(joining.encode(text), plain.encode("kitaplar"), "{text:?}");
+        }
+        // A token matched across the break spans it
+        let tokens = joining.tokenize_text("ki-\ntaplar okundu");
+        assert_eq!((tokens[0].token.as_str(), tokens[0].start, tokens[0].end), ("kitap", 0, 7));
+        assert_eq!(tokens[0].surface, "ki-\ntap");
+        assert_eq!((tokens[1].start, tokens[1].end), (7, 10));
+        // Hyphens before capitals, digits or the end of a line stay
+        for text in ["Türk-\nAlman", "kitap-\n2", "kitap-"] {
+            assert_eq!(joining.encode(text), plain.encode(text));
+        }
+        // With newlines as separators the break no longer splits the word
+        let preserving = TurkishTokenizer::builder()
+            .join_line_breaks(true)
+            .whitespace_mode(WhitespaceMode::Preserve)
+            .build()
+            .unwrap();
+        let text = "ki-\ntaplar\nokundu";
+        assert_eq!(preserving.encode(text)[..2], plain.encode("kitaplar")[..]);
+        let streamed: Vec<u32> = preserving.tokenize_stream(text.as_bytes()).map(|t| t.unwrap().id).collect();
+        assert_eq!(streamed, preserving.encode(text));
+
+        let foreign = TurkishTokenizer::builder().foreign_word_mode(ForeignWordMode::Patterns).build().unwrap();
+        let splitting = TurkishTokenizer::builder()
+            .foreign_word_mode(ForeignWordMode::Patterns)
+            .split_compounds(true)
+            .build()
+            .unwrap();
+        assert!(splitting.split_compounds() && !foreign.split_compounds());
+        // Each part is segmented as its own word, so "show" is foreign
+        assert_eq!(foreign.tokenize("show-room")[..3], ["s", "ho", "w"]);
+        assert_eq!(splitting.tokenize("show-room")[..2], ["show", "-"]);
+        assert_eq!(splitting.decode(&splitting.encode("show-room")), "show-room");
+        assert_eq!(splitting.encode("sosyo-ekonomik"), [foreign.encode("sosyo"), foreign.encode("-"), foreign.encode("ekonomik")].concat());
+
+        let restored = TurkishTokenizer::from_bytes(&splitting.to_bytes().unwrap()).unwrap();
+        assert!(restored.split_compounds());
+        let restored = TurkishTokenizer::from_bytes(&joining.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.encode("ki-\ntaplar"), joining.encode("ki-\ntaplar"));
+    }
+
+    #[test]
+    fn test_encode_question_context() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let question = "Kitabı kim yazdı?";
+        let context = "Bu kitabı geçen yıl Orhan Pamuk yazdı ve çok sevildi. Kitap birçok dile çevrildi.";
+        let question_len = tokenizer.encode(question).len();
+        let context_len = tokenizer.encode(context).len();
+        let max_length = question_len + 3 + 10;
+        let windows = tokenizer.encode_question_context(question, context, max_length, 4).unwrap();
+        assert!(windows.len() > 1);
+        for window in &windows {
+            let encoding = &window.encoding;
+            assert!(encoding.input_ids.len() <= max_length);
+            assert_eq!(encoding.tokens[0], tokenizer.cls_token);
+            assert_eq!(window.context_range.start, question_len + 2);
+            assert_eq!(window.context_range.end + 1, encoding.input_ids.len());
+            assert!(window.context_range.clone().all(|i| encoding.token_type_ids[i] == 1));
+        }
+        // Every context token appears, windows overlapping by the stride
+        let covered: usize = windows.iter().map(|w| w.context_range.len()).sum();
+        assert_eq!(covered, context_len + 4 * (windows.len() - 1));
+        let first = &windows[0];
+        let overlap = &first.encoding.input_ids[first.context_range.end - 4..first.context_range.end];
+        let second = &windows[1];
+        assert_eq!(&second.encoding.input_ids[second.context_range.start..second.context_range.start + 4], overlap);
+
+        let start = context.find("Orhan").map(|i| context[..i].chars().count()).unwrap();
+        let end = start + "Orhan Pamuk".chars().count();
+        let found: Vec<_> = windows.iter().filter_map(|w| Some((w, w.answer_token_span(start, end)?))).collect();
+        let marker = &tokenizer.uppercase_marker.token;
+        assert!(found.iter().any(|(window, (first, _))| &window.encoding.tokens[*first] == marker));
+        for (window, (first, last)) in found {
+            // The marker is left out only by a window starting after it
+            if &window.encoding.tokens[first] == marker {
+                assert_eq!(tokenizer.decode(&window.encoding.input_ids[first..=last]), "Orhan Pamuk");
+            } else {
+                assert_eq!(first, window.context_range.start);
+            }
+            assert_eq!(window.token_span_to_chars(first, last), Some((start, end)));
+        }
+        let last = windows.last().unwrap();
+        assert_eq!(last.answer_token_span(start, end), None);
+        assert_eq!(first.answer_token_span(start, start), None);
+        assert_eq!(first.token_span_to_chars(0, 1), None);
+
+        assert!(tokenizer.encode_question_context(question, context, question_len + 3, 0).is_err());
+        assert!(tokenizer.encode_question_context(question, context, max_length, 10).is_err());
+        let single = tokenizer.encode_question_context(question, "Kısa.", 64, 8).unwrap();
+        assert_eq!(single.len(), 1);
+    }
+
+    #[test]
+    fn test_vocab_index() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let index = tokenizer.vocab_index();
+        let vocab = tokenizer.get_vocab();
+        let with_prefix = index.ids_with_prefix("kita");
+        let mut expected: Vec<u32> = vocab.iter().filter(|(token, _)| token.starts_with("kita")).map(|(_, &id)| id).collect();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(with_prefix, expected);
+        assert!(index.ids_with_prefix("qqqq").is_empty());
+
+        let continuing = index.ids_continuing("kitaplar");
+        for token in ["k", "kitap", "kitaplar"] {
+            if let Some(id) = vocab.get(token) {
+                assert!(continuing.contains(id), "{token}");
+            }
+        }
+        assert!(continuing.contains(&(tokenizer.byte_offset + u32::from(b'k'))));
+        assert!(!continuing.contains(&vocab["ev"]));
+        assert!(continuing.windows(2).all(|pair| pair[0] < pair[1]));
+        // A partial character is continued by its bytes
+        let first_byte = tokenizer.byte_offset + u32::from("ş".as_bytes()[0]);
+        assert!(index.ids_continuing("ş").contains(&first_byte));
+        assert_eq!(index.ids_continuing("").len(), index.ids_with_prefix("").len() + 256);
+
+        tokenizer.set_byte_fallback(false);
+        assert!(!tokenizer.vocab_index().ids_continuing("kitap").contains(&(tokenizer.byte_offset + u32::from(b'k'))));
+    }
+
+    #[test]
+    fn test_token_ids_for_phrase() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let sequences = tokenizer.token_ids_for_phrase(" İstanbul ");
+        let expected: Vec<Vec<u32>> = ["İstanbul", "istanbul", "İSTANBUL", " İstanbul", " istanbul", " İSTANBUL"]
+            .iter()
+            .map(|form| tokenizer.encode(form))
+            .collect();
+        assert_eq!(sequences, expected);
+        assert_eq!(sequences[3][0], tokenizer.space_marker.id);
+        assert_eq!(sequences[0][0], tokenizer.uppercase_marker.id);
+        // Every sequence decodes back to a form of the phrase
+        for ids in &sequences {
+            assert_eq!(tr_lower(tokenizer.decode(ids).trim()), "istanbul");
+        }
+        assert_eq!(tokenizer.token_ids_for_phrase("yeni kitap").len(), 6);
+        assert!(tokenizer.token_ids_for_phrase("  ").is_empty());
+    }
+
+    #[test]
+    fn test_stop_criteria() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        tokenizer.add_special_tokens(&["<|im_end|>"]).unwrap();
+        let halt = |stop_strings: &[&str], text: &str| {
+            let mut stop = tokenizer.stop_criteria(stop_strings);
+            let ids = tokenizer.encode(text);
+            let at = ids.iter().position(|&id| stop.push(id))?;
+            Some((tokenizer.decode(&ids[..=at]), stop.matched().unwrap().to_string()))
+        };
+        // Multi-token patterns
+        let stop = tokenizer.stop_criteria(&["Soru:", "<|im_end|>", ""]);
+        assert_eq!(stop.patterns(), [tokenizer.encode("Soru:"), tokenizer.encode("<|im_end|>")]);
+        assert!(stop.patterns()[0].len() > 1);
+        assert_eq!(halt(&["Soru:"], "Cevap verdim. Soru: neden?"), Some(("Cevap verdim. Soru:".to_string(), "Soru:".to_string())));
+        assert_eq!(halt(&["<|im_end|>"], "Tamam<|im_end|>devam"), Some(("Tamam<|im_end|>".to_string(), "<|im_end|>".to_string())));
+        // Stop strings ending inside a token or spanning words are found in
+        // the decoded text
+        let (text, matched) = halt(&["dum ki", "xyz"], "okudum kitabı").unwrap();
+        assert_eq!((text.as_str(), matched.as_str()), ("okudum kitap", "dum ki"));
+        let (text, _) = halt(&["abalar"], "merhabalar dünya").unwrap();
+        assert_eq!(text, "merhabalar");
+        assert_eq!(halt(&["yok"], "hiç bir şey"), None);
+
+        let mut stop = tokenizer.stop_criteria(&["."]);
+        assert!(tokenizer.encode("bitti.").into_iter().any(|id| stop.push(id)));
+        assert!(stop.push(tokenizer.encode("devam")[0]));
+        stop.reset();
+        assert_eq!(stop.matched(), None);
+        assert!(!tokenizer.encode("devam").into_iter().any(|id| stop.push(id)));
+    }
+
+    #[test]
+    fn test_decode_stream() {
+        let tokenizer = TurkishTokenizer::builder()
+            .all_caps_markers(true)
+            .clitics(true)
+            .whitespace_mode(WhitespaceMode::Preserve)
+            .build()
+            .unwrap();
+        let stream_decode = |ids: &[u32], skip_special_tokens: bool| {
+            let mut stream = tokenizer.decode_stream(skip_special_tokens);
+            let mut pieces: Vec<String> = ids.iter().filter_map(|&id| stream.push(id)).collect();
+            pieces.extend(stream.finish());
+            pieces
+        };
+        for text in [
+            "Kitabı okudum, sen de okudun mu?",
+            "Ankara'ya ☕ ile\tgittik\nNASA ve İstanbul",
+            "  kitap  da  ",
+        ] {
+            let ids = tokenizer.encode(text);
+            assert_eq!(stream_decode(&ids, false).concat(), tokenizer.decode(&ids), "{text:?}");
+        }
+
+        // Text comes out a word at a time, once the root's form is known
+        let ids = tokenizer.encode("Kitabı okudum");
+        let mut stream = tokenizer.decode_stream(false);
+        let pieces: Vec<Option<String>> = ids.iter().map(|&id| stream.push(id)).collect();
+        assert_eq!(pieces.iter().flatten().collect::<Vec<_>>(), ["Kitabı "]);
+        assert_eq!(stream.finish().as_deref(), Some("okudum"));
+
+        let ids = tokenizer.encode_plus_with("Merhaba dünya", true).input_ids;
+        assert_eq!(stream_decode(&ids, true).concat(), tokenizer.decode_with_options(&ids, true));
+        assert!(stream_decode(&ids, false).concat().ends_with(&tokenizer.eos_token));
+    }
+
+    #[test]
+    fn test_chat_template() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let messages = [
+            ChatMessage::new("system", "Kısa cevap ver."),
+            ChatMessage::new("user", "{role} nedir?"),
+        ];
+        assert!(tokenizer.apply_chat_template(&messages, true).is_err());
+
+        tokenizer.set_chat_template(ChatTemplate::chatml()).unwrap();
+        let prompt = tokenizer.render_chat(&messages, false).unwrap();
+        assert_eq!(
+            prompt,
+            "<|im_start|>system\nKısa cevap ver.<|im_end|>\n<|im_start|>user\n{role} nedir?<|im_end|>\n"
+        );
+        let start = tokenizer.convert_tokens_to_ids(&["<|im_start|>".to_string()])[0];
+        let end = tokenizer.convert_tokens_to_ids(&["<|im_end|>".to_string()])[0];
+        assert_ne!(start, tokenizer.unk_token_id());
+        let ids = tokenizer.apply_chat_template(&messages, true).unwrap();
+        assert_eq!(ids, tokenizer.encode(&(prompt + "<|im_start|>assistant\n")));
+        assert_eq!(ids.iter().filter(|&&id| id == start).count(), 3);
+        assert_eq!(ids.iter().filter(|&&id| id == end).count(), 2);
+
+        let template = ChatTemplate::new("<|{role}|>\n{content}<|end|>\n", "<|assistant|>\n", &["<|user|>", "<|end|>"]).unwrap();
+        assert_eq!(
+            template.to_jinja(),
+            "{% for message in messages %}{{ '<|' + message['role'] + '|>\\n' + message['content'] + '<|end|>\\n' }}{% endfor %}\
+             {% if add_generation_prompt %}{{ '<|assistant|>\\n' }}{% endif %}"
+        );
+        assert!(ChatTemplate::new("<|{role}|>", "", &[]).is_err());
+
+        let tokenizer = TurkishTokenizer::builder().chat_template(template.clone()).build().unwrap();
+        assert_eq!(tokenizer.tokenize("<|user|>"), ["<|user|>"]);
+        let config = tokenizer.tokenizer_config();
+        assert_eq!(config.chat_template, Some(template.to_jinja()));
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.chat_template(), Some(&template));
+    }
+
+    #[test]
+    fn test_case_mapping() {
+        assert_eq!(tr_upper("istanbul ılık"), "İSTANBUL ILIK");
+        assert_eq!(tr_lower("İSTANBUL IŞIK"), "istanbul ışık");
+        assert_eq!(CaseMapping::Unicode.upper("internet"), "INTERNET");
+        assert_eq!(CaseMapping::Unicode.capitalize("iphone"), "Iphone");
+
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert_eq!(tokenizer.case_mapping(), CaseMapping::Turkish);
+        assert_eq!(tokenizer.decode(&tokenizer.encode("IŞIK İlk")), "IŞIK İlk");
+        // Turkish rules lowercase "Internet" to "ınternet"
+        let turkish = tokenizer.encode("Internet");
+        tokenizer.set_case_mapping(CaseMapping::Unicode);
+        assert_ne!(tokenizer.encode("Internet"), turkish);
+        assert_eq!(tokenizer.decode(&tokenizer.encode("Internet")), "Internet");
+        assert_eq!(tokenizer.decode(&tokenizer.encode("İlk")), "Ilk");
+
+        let unicode = TurkishTokenizer::builder().case_mapping(CaseMapping::Unicode).build().unwrap();
+        assert_eq!(unicode.encode("Internet"), tokenizer.encode("Internet"));
+        let restored = TurkishTokenizer::from_bytes(&unicode.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.case_mapping(), CaseMapping::Unicode);
+        assert_eq!(restored.decode(&restored.encode("Internet")), "Internet");
+
+        // A cased model sees the text as written
+        tokenizer.set_lowercase(false);
+        assert!(!tokenizer.lowercase());
+        assert_ne!(tokenizer.encode("Kitap"), unicode.encode("Kitap"));
+    }
+
+    #[test]
+    fn test_control_chars() {
+        let plain = TurkishTokenizer::new().unwrap();
+        let stripping = TurkishTokenizer::builder().control_chars(ControlCharMode::Strip).build().unwrap();
+        assert_eq!(plain.control_chars(), ControlCharMode::Keep);
+        for text in ["mer\u{200b}haba", "\u{feff}merhaba", "\u{200e}merhaba\u{200f}", "mer\u{7}haba"] {
+            assert_ne!(plain.encode(text), plain.encode("merhaba"), "{text:?}");
+            assert_eq!(stripping.encode(text), plain.encode("merhaba"), "{text:?}");
+        }
+        // Offsets point into the original text
+        let tokens = stripping.tokenize_text("\u{feff}ev\u{200b}ler güzel");
+        assert_eq!((tokens[0].token.as_str(), tokens[0].start, tokens[0].end), ("ev", 1, 3));
+        assert_eq!((tokens[1].start, tokens[1].end), (4, 7));
+        assert_eq!(tokens[1].surface, "ler");
+        assert_eq!((tokens[2].start, tokens[2].end), (7, 8));
+        // A word of nothing but invisible characters is dropped
+        assert_eq!(stripping.encode("ev \u{200b} güzel"), plain.encode("ev  güzel"));
+        // Zero-width joiners inside emoji sequences stay
+        let family = "\u{1f468}\u{200d}\u{1f469}";
+        assert_eq!(stripping.encode(family), plain.encode(family));
+
+        let mut normalizing = TurkishTokenizer::builder().control_chars(ControlCharMode::Normalize).build().unwrap();
+        assert_eq!(normalizing.encode("ev\u{a0}güzel"), plain.encode("ev güzel"));
+        assert_eq!(normalizing.encode("ev\u{b}güzel\u{200b}"), plain.encode("ev güzel"));
+        // Otherwise a non-breaking space is two byte tokens inside the word
+        assert_eq!(plain.tokenize("ev\u{a0}güzel").len(), plain.tokenize("ev güzel").len() + 1);
+        normalizing.set_control_chars(ControlCharMode::Keep);
+        assert_eq!(normalizing.encode("ev\u{a0}güzel"), plain.encode("ev\u{a0}güzel"));
+
+        let restored = TurkishTokenizer::from_bytes(&stripping.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.control_chars(), ControlCharMode::Strip);
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        let plain = TurkishTokenizer::new().unwrap();
+        assert_eq!(plain.tokenize("T.C."), ["<uppercase>", "t", ".", "<uppercase>", "c", "."]);
+
+        let mut tokenizer = TurkishTokenizer::builder().abbreviations(true).build().unwrap();
+        let size = plain.vocab_size();
+        assert_eq!(tokenizer.vocab_size(), size + COMMON_ABBREVIATIONS.len());
+        assert_eq!(tokenizer.tokenize("T.C."), ["T.C."]);
+        assert_eq!(tokenizer.tokenize("Prof. Dr. Ahmet")[..3], ["<uppercase>", "prof.", " "]);
+        assert_eq!(tokenizer.tokenize("(vb.)"), ["(", "vb.", ")"]);
+        let tokens = tokenizer.tokenize_text("T.C.'nin");
+        assert_eq!((tokens[0].token.as_str(), tokens[0].start, tokens[0].end), ("T.C.", 0, 4));
+        assert_eq!(tokens[1].token, "'");
+        // Only whole abbreviations match
+        for text in ["drama", "Dr.Ahmet", "t.c."] {
+            assert_eq!(tokenizer.encode(text), plain.encode(text), "{text:?}");
+        }
+        for text in ["Prof. Dr. Ahmet Kaya", "elma, armut vb. meyveler", "Vs. diye"] {
+            assert_eq!(tokenizer.decode(&tokenizer.encode(text)), text);
+        }
+
+        // User entries also keep the sentence going
+        let text = "Görüşmeye Ord. Prof. Kaya katıldı. Sonra gitti.";
+        assert_eq!(sentences::split_sentences(text).len(), 3);
+        assert_eq!(tokenizer.add_abbreviations(&["ord.", "T.C."]), 1);
+        assert_eq!(tokenizer.tokenize_sentences(text).len(), 2);
+
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.abbreviations(), tokenizer.abbreviations());
+        assert_eq!(restored.encode(text), tokenizer.encode(text));
+    }
+
+    #[test]
+    fn test_token_offsets() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let text = "Merhaba güzel dünya ☕";
+        let tokens = tokenizer.tokenize_text(text);
+        let chars: Vec<char> = text.chars().collect();
+
+        for token in tokens.iter().filter(|t| t.token_type != TokenType::Byte && t.id != 0) {
+            let surface: String = chars[token.start..token.end].iter().collect();
+            assert_eq!(surface.to_lowercase(), token.token);
+        }
+
+        // The uppercase marker is a zero-width span before its segment
+        assert_eq!((tokens[0].start, tokens[0].end), (0, 0));
+        // Every byte of the emoji maps back to the same character
+        let last = tokens.last().unwrap();
+        assert_eq!((last.start, last.end), (20, 21));
+    }
+
+    #[test]
+    fn test_builder_options() {
+        let tokenizer = TurkishTokenizer::builder()
+            .split_camel_case(false)
+            .build()
+            .unwrap();
+        assert_eq!(tokenizer.tokenize("Merhaba")[0], "<uppercase>");
+        assert!(!tokenizer.tokenize("merhabaDünya").contains(&"<uppercase>".to_string()));
+
+        let tokenizer = TurkishTokenizer::builder()
+            .emit_case_markers(false)
+            .build()
+            .unwrap();
+        assert_eq!(tokenizer.tokenize("Merhaba"), vec!["merhaba"]);
+
+        let tokenizer = TurkishTokenizer::builder().lowercase(false).build().unwrap();
+        assert_ne!(tokenizer.tokenize("Merhaba")[1], "merhaba");
+
+        let tokenizer = TurkishTokenizer::builder()
+            .unknown_strategy(UnknownStrategy::Unknown)
+            .build()
+            .unwrap();
+        assert_eq!(tokenizer.tokenize("☕"), vec!["<unknown>"]);
+    }
+
+    #[test]
+    fn test_builder_special_tokens() {
+        let tokenizer = TurkishTokenizer::builder()
+            .pad_token("<eos>")
+            .eos_token("</s>")
+            .cls_token("[CLS]")
+            .build()
+            .unwrap();
+
+        assert_eq!(tokenizer.pad_token_id, tokenizer.token_to_id("<eos>").unwrap());
+        assert_eq!(tokenizer.token_to_id("</s>"), Some(tokenizer.eos_token_id));
+        assert_eq!(tokenizer.encode_pair("a", "b").tokens[0], "[CLS]");
+        // Claimed tokens are matched as roots; released slot names are not
+        assert_eq!(tokenizer.encode("</s>"), vec![tokenizer.eos_token_id]);
+        assert!(!tokenizer.encode("special_7").contains(&7));
+    }
+
+    #[test]
+    fn test_encode_matches_tokenize_text() {
+        let texts = [
+            "Kitaplarımızdan geliyorum",
+            "merhabaDünyaİSTANBUL  çift  boşluk ",
+            "☕ kahve\tve\nsatır",
+            "",
+        ];
+        let tokenizers = [
+            TurkishTokenizer::new().unwrap(),
+            TurkishTokenizer::builder()
+                .split_camel_case(false)
+                .lowercase(false)
+                .unknown_strategy(UnknownStrategy::Unknown)
+                .build()
+                .unwrap(),
+        ];
+        for tokenizer in &tokenizers {
+            for text in texts {
+                let ids: Vec<u32> = tokenizer.tokenize_text(text).iter().map(|t| t.id).collect();
+                assert_eq!(tokenizer.encode(text), ids, "{:?}", text);
+                assert_eq!(tokenizer.count_tokens(text), ids.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokenize_iter() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let text = "Kitaplarımızdan  geliyorum bugün";
+
+        let first: Vec<(u32, usize, Option<usize>)> =
+            tokenizer.tokenize_iter(text).take(4).map(|t| (t.id, t.start, t.word_id)).collect();
+        let all: Vec<(u32, usize, Option<usize>)> =
+            tokenizer.tokenize_text(text).iter().map(|t| (t.id, t.start, t.word_id)).collect();
+        assert_eq!(first, all[..4]);
+
+        let spaces: Vec<usize> = tokenizer.tokenize_iter(text).filter(|t| t.token == " ").map(|t| t.start).collect();
+        assert_eq!(spaces, vec![15, 16, 26]);
+    }
+
+    #[test]
+    fn test_encode_chunks() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let text = "Kitaplarımızdan geliyorum ve evlerimizde oturuyoruz";
+        let full = tokenizer.encode_plus(text);
+
+        let chunks = tokenizer.encode_chunks(text, 6, 2).unwrap();
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.input_ids.len() <= 6));
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].input_ids[4..], pair[1].input_ids[..2]);
+            assert_eq!(pair[0].offset_mapping[4..], pair[1].offset_mapping[..2]);
+        }
+        assert_eq!(chunks.last().unwrap().input_ids.last(), full.input_ids.last());
+        // Offsets point into the whole text
+        assert_eq!(chunks[1].offset_mapping, full.offset_mapping[4..10]);
+
+        assert_eq!(tokenizer.encode_chunks("", 4, 0).unwrap().len(), 1);
+        assert!(matches!(tokenizer.encode_chunks(text, 4, 4), Err(TurkishTokenizerError::InvalidConfig(_))));
+        assert!(matches!(tokenizer.encode_chunks(text, 0, 0), Err(TurkishTokenizerError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_sentences() {
+        let text = "Dr. Ayşe Hanım 3. sınıfa gitti. T.C. vatandaşıdır, vb. şeyler yazdı! \"Geldin mi?\" diye sordu. A. Yılmaz geldi\n\nYeni paragraf";
+        assert_eq!(
+            sentences::split_sentences(text),
+            vec![
+                "Dr. Ayşe Hanım 3. sınıfa gitti.",
+                "T.C. vatandaşıdır, vb. şeyler yazdı!",
+                "\"Geldin mi?\" diye sordu.",
+                "A. Yılmaz geldi",
+                "Yeni paragraf",
+            ]
+        );
+
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let text = "Merhaba. Nasılsın?";
+        let per_sentence = tokenizer.tokenize_sentences(text);
+        assert_eq!(per_sentence.len(), 2);
+        assert_eq!(per_sentence[1][0].token, "<uppercase>");
+        assert_eq!(per_sentence[1][0].start, 9);
+        assert_eq!(per_sentence[1][1].word_id, Some(0));
+    }
+
+    #[test]
+    fn test_split_punctuation() {
+        let default = TurkishTokenizer::new().unwrap();
+        for mark in PUNCTUATION {
+            assert!(default.contains_token(&mark.to_string()), "{:?}", mark);
+        }
+
+        let tokenizer = TurkishTokenizer::builder().split_punctuation(true).build().unwrap();
+        let text = "“Merhaba,” dedi... (evet) İstanbul'da!";
+        assert_eq!(
+            tokenizer.tokenize(text),
+            vec![
+                "“", "<uppercase>", "merhaba", ",", "”", " ", "dedi", ".", ".", ".", " ", "(", "evet", ")", " ",
+                "<uppercase>", "istanbul", "'", "da", "!",
+            ]
+        );
+        assert_eq!(tokenizer.decode(&tokenizer.encode(text)), text);
+    }
+
+    #[test]
+    fn test_number_modes() {
+        let text = "1923 yılında 1.250,75 lira";
+        let bpe = TurkishTokenizer::new().unwrap();
+        assert_eq!(bpe.number_mode(), NumberMode::Bpe);
+
+        let digits = TurkishTokenizer::builder().number_mode(NumberMode::Digits).build().unwrap();
+        let tokens = digits.tokenize(text);
+        assert_eq!(tokens[..4], ["1", "9", "2", "3"]);
+        assert_eq!(digits.decode(&digits.encode(text)), text);
+
+        let placeholder = TurkishTokenizer::builder().number_mode(NumberMode::Placeholder).build().unwrap();
+        let tokens = placeholder.tokenize_text(text);
+        let numbers: Vec<(usize, usize)> =
+            tokens.iter().filter(|t| t.token == NUMBER_TOKEN).map(|t| (t.start, t.end)).collect();
+        assert_eq!(numbers, vec![(0, 4), (13, 21)]);
+        assert_eq!(placeholder.decode(&placeholder.encode(text)), "<number> yılında <number> lira");
+    }
+
+    #[test]
+    fn test_marker_ids_independent_of_order() {
+        let enable = |steps: &[&str]| {
+            let mut tokenizer = TurkishTokenizer::new().unwrap();
+            for &step in steps {
+                match step {
+                    "number" => tokenizer.set_number_mode(NumberMode::Placeholder).unwrap(),
+                    "caps" => tokenizer.set_all_caps_markers(true).unwrap(),
+                    "clitics" => tokenizer.set_clitics(true).unwrap(),
+                    "url" => tokenizer.set_entity_mode(EntityKind::Url, EntityMode::Placeholder).unwrap(),
+                    "hashtag" => tokenizer.set_entity_mode(EntityKind::Hashtag, EntityMode::Placeholder).unwrap(),
+                    token => {
+                        tokenizer.add_special_tokens(&[token]).unwrap();
+                    }
+                }
+            }
+            tokenizer
+        };
+        let forward = enable(&["number", "caps", "clitics", "url", "hashtag", "<ozel>"]);
+        let backward = enable(&["<ozel>", "hashtag", "url", "clitics", "caps", "number"]);
+        assert_eq!(forward.get_vocab(), backward.get_vocab());
+        // A marker enabled alone gets the same ID as with the others
+        let number = enable(&["number"]);
+        assert_eq!(number.token_to_id(NUMBER_TOKEN), forward.token_to_id(NUMBER_TOKEN));
+        let caps = enable(&["<ozel>", "caps"]);
+        assert_eq!(caps.token_to_id(ALL_CAPS_TOKEN), forward.token_to_id(ALL_CAPS_TOKEN));
+        assert_eq!(caps.token_to_id("<ozel>"), forward.token_to_id("<ozel>"));
+        let ids: std::collections::HashSet<u32> = MARKER_TOKENS.iter().filter_map(|marker| forward.token_to_id(marker)).collect();
+        assert_eq!(ids.len(), 6);
+        assert!(!ids.contains(&forward.token_to_id("<ozel>").unwrap()));
+    }
+
+    #[test]
+    fn test_all_caps_markers() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert!(!tokenizer.all_caps_markers());
+        let per_letter = tokenizer.tokenize("NATO");
+        assert_eq!(per_letter.iter().filter(|t| *t == "<uppercase>").count(), 4);
+
+        tokenizer.set_all_caps_markers(true).unwrap();
+        assert_eq!(tokenizer.tokenize("NATO")[0], ALL_CAPS_TOKEN);
+        assert!(!tokenizer.tokenize("NATO").contains(&"<uppercase>".to_string()));
+
+        let text = "NATO'da HTTPSunucu ve İSTANBUL MP3 A";
+        let tokens = tokenizer.tokenize(text);
+        assert_eq!(tokens.iter().filter(|t| *t == ALL_CAPS_TOKEN).count(), 4);
+        assert_eq!(tokenizer.decode(&tokenizer.encode(text)), text);
+    }
+
+    #[test]
+    fn test_whitespace_modes() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert_eq!(tokenizer.whitespace_mode(), WhitespaceMode::Space);
+        let text = "merhaba\tdünya \n\nnasılsın";
+        let tokens = tokenizer.tokenize_text(text);
+        assert!(tokens.iter().filter(|t| t.token == "\n").all(|t| t.word_id.is_some()));
+
+        tokenizer.set_whitespace_mode(WhitespaceMode::Preserve);
+        let tokens = tokenizer.tokenize_text(text);
+        let whitespace: Vec<(&str, usize, usize, Option<usize>)> = tokens
+            .iter()
+            .filter(|t| t.token.trim().is_empty())
+            .map(|t| (t.token.as_str(), t.start, t.end, t.word_id))
+            .collect();
+        assert_eq!(
+            whitespace,
+            vec![("\t", 7, 8, None), (" ", 13, 14, None), ("\n", 14, 15, None), ("\n", 15, 16, None)]
+        );
+        assert_eq!(tokens.last().unwrap().word_id, Some(2));
+        assert_eq!(tokenizer.decode(&tokenizer.encode(text)), text);
+        assert_eq!(tokenizer.count_tokens(text), tokens.len());
+
+        tokenizer.set_whitespace_mode(WhitespaceMode::Collapse);
+        let tokens = tokenizer.tokenize_text(text);
+        assert_eq!(tokens.iter().filter(|t| t.token.trim().is_empty()).count(), 2);
+        assert_eq!(tokenizer.decode(&tokenizer.encode(text)), "merhaba dünya\nnasılsın");
+        let newline = tokens.iter().find(|t| t.token == "\n").unwrap();
+        assert_eq!((newline.start, newline.end), (13, 16));
+    }
+
+    #[test]
+    fn test_strict_morphology() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert!(!tokenizer.strict_morphology());
+        assert_eq!(tokenizer.tokenize("kitapler"), vec!["kitap", "ler"]);
+
+        tokenizer.set_strict_morphology(true);
+        assert_ne!(tokenizer.tokenize("kitapler"), vec!["kitap", "ler"]);
+        assert_ne!(tokenizer.tokenize("okulde"), vec!["okul", "de"]);
+        for word in ["kitaplarımızdan", "evlerinizden", "gözlükçüler", "geliyorum"] {
+            tokenizer.set_strict_morphology(false);
+            let lenient = tokenizer.tokenize(word);
+            tokenizer.set_strict_morphology(true);
+            assert_eq!(tokenizer.tokenize(word), lenient, "{}", word);
+        }
+
+        assert!(morphology::harmonizes(&['g', 'ö', 'z'], &['ü']));
+        assert!(!morphology::harmonizes(&['g', 'ö', 'z'], &['i']));
+        assert!(morphology::harmonizes(&['o', 'k', 'u', 'r'], &['k', 'e', 'n']));
+    }
+
+    #[test]
+    fn test_analyze() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let analysis = tokenizer.analyze("kitaplarımızdan");
+        assert_eq!(analysis.root, "kitap");
+        assert_eq!(analysis.root_id, Some(tokenizer.encode("kitap")[0]));
+        let chain: Vec<(&str, SuffixCategory)> =
+            analysis.suffixes.iter().map(|s| (s.surface.as_str(), s.category)).collect();
+        assert_eq!(
+            chain,
+            vec![
+                ("lar", SuffixCategory::Plural),
+                ("ım", SuffixCategory::Possessive),
+                ("ız", SuffixCategory::Possessive),
+                ("dan", SuffixCategory::Case),
+            ]
+        );
+        assert_eq!(analysis.pos_guess, PartOfSpeech::Noun);
+        assert_eq!(tokenizer.analyze("güzelleşti").pos_guess, PartOfSpeech::Verb);
+        assert_eq!(tokenizer.analyze("akıllı").pos_guess, PartOfSpeech::Adjective);
+
+        let text = "Kitaplarda okumaktan";
+        let analyses = tokenizer.analyze_text(text);
+        assert_eq!(analyses.len(), 2);
+        assert_eq!((analyses[0].word.as_str(), analyses[0].root.as_str()), ("Kitaplarda", "kitap"));
+        assert_eq!(analyses[1].word, "okumaktan");
+        let last = analyses[1].suffixes.last().unwrap();
+        assert_eq!((last.surface.as_str(), last.start, last.end), ("tan", 17, 20));
+    }
+
+    #[test]
+    fn test_add_user_tokens() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let size = tokenizer.vocab_size();
+        assert!(tokenizer.tokenize("trendyolda").len() > 2);
+
+        let ids = tokenizer
+            .add_user_tokens(&[("trendyol", None), ("kardiyomiyopati", None), ("kitap", None)])
+            .unwrap();
+        let byte_end = tokenizer.byte_offset + 256;
+        assert_eq!(ids, vec![byte_end, byte_end + 1, tokenizer.token_to_id("kitap").unwrap()]);
+        assert_eq!(tokenizer.vocab_size(), size + 2);
+        assert_eq!(tokenizer.get_vocab().get("trendyol"), Some(&byte_end));
+
+        assert_eq!(tokenizer.tokenize("Trendyol'dan"), vec!["<uppercase>", "trendyol", "'", "dan"]);
+        assert_eq!(tokenizer.encode("kardiyomiyopati"), vec![byte_end + 1]);
+        let text = "Trendyolda kardiyomiyopati";
+        assert_eq!(tokenizer.decode(&tokenizer.encode(text)), text);
+
+        // An explicit ID adds another surface form of an existing token
+        let kitap = tokenizer.token_to_id("kitap").unwrap();
+        assert_eq!(tokenizer.add_user_tokens(&[("kitapcık", Some(kitap))]).unwrap(), vec![kitap]);
+        assert_eq!(tokenizer.encode("kitapcık"), vec![kitap]);
+        assert!(tokenizer.add_user_tokens(&[("trendyol", Some(kitap))]).is_err());
+        assert!(tokenizer.add_user_tokens(&[("bayt", Some(tokenizer.byte_offset))]).is_err());
+    }
+
+    #[test]
+    fn test_add_tokens() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let size = tokenizer.vocab_size();
+        assert_eq!(tokenizer.add_tokens(&["covid-19", "kitap", "covid-19"]), 1);
+        assert_eq!(tokenizer.vocab_size(), size + 1);
+        assert_eq!(tokenizer.add_special_tokens(&["<|im_start|>", "<|im_end|>", "<pad>"]).unwrap(), 2);
+        assert_eq!(tokenizer.vocab_size(), size + 1);
+        assert_eq!(tokenizer.additional_special_tokens(), vec!["<pad>", "<|im_start|>", "<|im_end|>"]);
+
+        let covid = tokenizer.byte_offset + 256;
+        let im_start = tokenizer.token_to_id("<|im_start|>").unwrap();
+        let im_end = tokenizer.token_to_id("<|im_end|>").unwrap();
+        assert_eq!(tokenizer.get_added_vocab().get("covid-19"), Some(&covid));
+
+        // Added tokens win over normal matching, even inside words
+        let text = "<|im_start|>covid-19 aşısı<|im_end|>";
+        let tokens = tokenizer.tokenize_text(text);
+        assert_eq!(tokens[0].id, im_start);
+        assert_eq!(tokens[0].word_id, None);
+        assert_eq!((tokens[1].id, tokens[1].word_id), (covid, Some(0)));
+        assert_eq!(tokens.last().unwrap().id, im_end);
+        assert_eq!((tokens.last().unwrap().start, tokens.last().unwrap().end), (26, 36));
+        assert_eq!(tokenizer.encode(text), tokens.iter().map(|t| t.id).collect::<Vec<_>>());
+
+        let ids = tokenizer.encode(text);
+        assert_eq!(tokenizer.decode(&ids), text);
+        assert_eq!(tokenizer.decode_with_options(&ids, true), "covid-19 aşısı");
+    }
+
+    #[test]
+    fn test_mask_and_cls_sep() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        assert_eq!(tokenizer.mask_token, "<mask>");
+        assert_eq!(tokenizer.token_to_id("<mask>"), Some(tokenizer.mask_token_id));
+        assert_eq!(tokenizer.tokenize("<mask>"), vec!["<mask>"]);
+
+        let mut tokenizer = TurkishTokenizer::builder()
+            .mask_token("[MASK]")
+            .post_processor(PostProcessor::ClsSep)
+            .build()
+            .unwrap();
+        assert_eq!(tokenizer.token_to_id("[MASK]"), Some(tokenizer.mask_token_id));
+
+        let plain = tokenizer.encode_plus("kitap okudum");
+        let framed = tokenizer.encode_plus_with("kitap okudum", true);
+        assert_eq!(framed.input_ids.len(), plain.input_ids.len() + 2);
+        assert_eq!(framed.input_ids[0], tokenizer.cls_token_id);
+        assert_eq!(framed.input_ids[framed.input_ids.len() - 1], tokenizer.sep_token_id);
+        assert_eq!(&framed.input_ids[1..framed.input_ids.len() - 1], plain.input_ids.as_slice());
+        assert_eq!(framed.word_ids[0], None);
+        assert!(framed.token_type_ids.iter().all(|&t| t == 0));
+
+        tokenizer.set_post_processor(PostProcessor::BosEos).unwrap();
+        let framed = tokenizer.encode_plus_with("kitap okudum", true);
+        assert_eq!(framed.input_ids[framed.input_ids.len() - 1], tokenizer.eos_token_id);
+    }
+
+    #[test]
+    fn test_template_post_processor() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let plain = tokenizer.encode_plus("kitap okudum");
+        let n = plain.input_ids.len();
+        let default_pair = tokenizer.encode_pair("kitap", "okudum");
+
+        let template = TemplateProcessing::new("$A <eos>", "<cls> $A <sep> $B <sep>").unwrap();
+        tokenizer.set_post_processor(PostProcessor::Template(template)).unwrap();
+        let single = tokenizer.encode_plus_with("kitap okudum", true);
+        assert_eq!(&single.input_ids[..n], plain.input_ids.as_slice());
+        assert_eq!(single.input_ids[n], tokenizer.eos_token_id);
+        assert_eq!(single.special_tokens_mask[n - 1..], [0, 1]);
+        let pair = tokenizer.encode_pair("kitap", "okudum");
+        assert_eq!(pair.input_ids, default_pair.input_ids);
+        assert_eq!(pair.token_type_ids, default_pair.token_type_ids);
+        assert_eq!(default_pair.special_tokens_mask, vec![1, 0, 1, 0, 0, 1]);
+
+        // Explicit type IDs and tokens claimed for the template
+        let template = TemplateProcessing::new("[BAŞ] $A", "[BAŞ] $A:0 [ARA]:0 $B:2 [ARA]:2").unwrap();
+        tokenizer.set_post_processor(PostProcessor::Template(template)).unwrap();
+        let start = tokenizer.token_to_id("[BAŞ]").unwrap();
+        let pair = tokenizer.encode_pair("kitap", "okudum");
+        assert_eq!(pair.input_ids[0], start);
+        assert_eq!(pair.tokens[2], "[ARA]");
+        assert_eq!(pair.token_type_ids, vec![0, 0, 0, 2, 2, 2]);
+        assert_eq!(pair.word_ids, vec![None, Some(0), None, Some(0), Some(0), None]);
+
+        assert!(TemplateProcessing::new("$A $B", "$A $B").is_err());
+        assert!(TemplateProcessing::new("$A", "$A <sep>").is_err());
+        assert!(TemplateProcessing::new("$C", "$A $B").is_err());
+    }
+
+    #[test]
+    fn test_special_tokens_mask() {
+        let tokenizer = TurkishTokenizer::builder().post_processor(PostProcessor::ClsSep).build().unwrap();
+        let mut encoding = tokenizer.encode_plus_with("kitap okudum", true);
+        let n = encoding.input_ids.len();
+        encoding.pad(n + 2, tokenizer.pad_token_id, &tokenizer.pad_token, PaddingSide::Right);
+
+        let mut expected = vec![0; n + 2];
+        expected[0] = 1;
+        expected[n - 1..].fill(1);
+        assert_eq!(encoding.special_tokens_mask, expected);
+        assert_eq!(tokenizer.get_special_tokens_mask(&encoding.input_ids), expected);
+
+        let chunks = tokenizer.encode_chunks("kitap okudum", 2, 1).unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.special_tokens_mask == vec![0; chunk.input_ids.len()]));
+    }
+
+    #[test]
+    fn test_stem() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        assert_eq!(tokenizer.stem("kitaplarımızdan").as_deref(), Some("kitap"));
+        assert_eq!(tokenizer.stem("Kitabı").as_deref(), Some("kitap"));
+        assert_eq!(tokenizer.stem("ağacı").as_deref(), Some("ağaç"));
+        assert_eq!(tokenizer.stem("☕"), None);
+        assert_eq!(tokenizer.stem_text("Çocuklar kitabı ☕ okudu"), vec!["çocuk", "kitap", "oku"]);
+    }
+
+    #[test]
+    fn test_morphotactics() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert!(!tokenizer.morphotactics());
+        assert_eq!(tokenizer.tokenize("dandan"), vec!["dan", "dan"]);
+        let words = ["kitaplarımızdan", "evdekiler", "geliyorlardı", "güzelleşti", "okumaktan"];
+        let lenient: Vec<Vec<String>> = words.iter().map(|w| tokenizer.tokenize(w)).collect();
+
+        tokenizer.set_morphotactics(true);
+        assert_ne!(tokenizer.tokenize("dandan")[0], "dan");
+        for (word, expected) in words.iter().zip(&lenient) {
+            assert_eq!(&tokenizer.tokenize(word), expected, "{}", word);
+        }
+
+        use morphology::{next_slot, Slot};
+        assert_eq!(next_slot(Slot::Start, 20000), None);
+        assert_eq!(next_slot(Slot::Stem, 20000), Some(Slot::Plural));
+        assert_eq!(next_slot(Slot::Case, 20015), None);
+        assert_eq!(next_slot(Slot::Case, 20059), Some(Slot::Stem));
+    }
+
+    #[test]
+    fn test_consonant_alternation() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let root_id = tokenizer.encode("kepenk")[0];
+        assert_ne!(tokenizer.encode("kepenği")[0], root_id);
+
+        tokenizer.set_consonant_alternation(true);
+        let tokens = tokenizer.tokenize_text("kepenği");
+        assert_eq!((tokens[0].token.as_str(), tokens[0].id), ("kepenğ", root_id));
+        assert_eq!(tokenizer.encode("kitabı")[0], tokenizer.encode("kitap")[0]);
+        assert_eq!(tokenizer.tokenize("kitaplar"), vec!["kitap", "lar"]);
+    }
+
+    #[test]
+    fn test_best_path_segmentation() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let text = "kitaplarımızdan evlerinizden gözlükçüler İstanbul'da çalışıyorum ☕";
+        let greedy = tokenizer.encode(text);
+        assert_eq!(tokenizer.segmentation(), SegmentationMode::Greedy);
+
+        tokenizer.set_segmentation(SegmentationMode::BestPath);
+        let best = tokenizer.encode(text);
+        assert!(best.len() <= greedy.len());
+        assert_eq!(tokenizer.count_tokens(text), best.len());
+        let tokens = tokenizer.tokenize_text(text);
+        assert_eq!(tokens.iter().map(|t| t.id).collect::<Vec<_>>(), best);
+        assert_eq!(tokens.last().unwrap().token_type, TokenType::Byte);
+        assert_eq!(tokenizer.tokenize("kitaplar"), vec!["kitap", "lar"]);
+    }
+
+    #[test]
+    fn test_unigram_segmentation() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let corpus = ["kitaplar masada duruyor", "evlerimizden geldik", "kitaplarımızı okuduk"];
+        let mut scores = tokenizer.train_unigram_scores(corpus.iter().copied(), 5);
+        let total: f64 = scores.values().map(|&score| f64::from(score).exp()).sum();
+        assert!((total - 1.0).abs() < 1e-3);
+        let kitap = tokenizer.token_to_id("kitap").unwrap();
+        assert!(scores[&kitap] > scores.values().copied().fold(f32::INFINITY, f32::min));
+
+        tokenizer.set_scores(scores.clone());
+        tokenizer.set_segmentation(SegmentationMode::Unigram);
+        assert_eq!(tokenizer.tokenize("kitaplar"), vec!["kitap", "lar"]);
+        assert_eq!(tokenizer.decode(&tokenizer.encode("evlerimizden geldik")), "evlerimizden geldik");
+
+        // A root scored far below everything else loses to a longer path
+        scores.insert(kitap, -100.0);
+        tokenizer.set_scores(scores);
+        let tokens = tokenizer.tokenize("kitaplar");
+        assert_ne!(tokens[0], "kitap");
+        assert_eq!(tokens.concat(), "kitaplar");
+
+        let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.scores(), tokenizer.scores());
+        assert_eq!(restored.tokenize("kitaplar"), tokens);
+    }
+
+    #[test]
+    fn test_encode_with_sampling() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let text = "kitaplarımızdan evlerinizden geliyorum";
+        assert_eq!(tokenizer.encode_with_sampling(text, 0.0, 7), tokenizer.encode(text));
+        assert_eq!(
+            tokenizer.encode_with_sampling(text, 0.3, 42),
+            tokenizer.encode_with_sampling(text, 0.3, 42)
+        );
+
+        let samples: std::collections::HashSet<Vec<u32>> = (0..20).map(|seed| tokenizer.encode_with_sampling(text, 0.3, seed)).collect();
+        assert!(samples.len() > 1);
+        assert!(samples.iter().flatten().all(|&id| tokenizer.id_to_token(id).is_some()));
+        let split = tokenizer.encode_with_sampling(text, 1.0, 0);
+        assert!(split.len() > tokenizer.encode(text).len());
+    }
+
+    #[test]
+    fn test_word_cache() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        assert_eq!(tokenizer.word_cache_capacity(), DEFAULT_WORD_CACHE_CAPACITY);
+        let uncached = TurkishTokenizer::builder().word_cache_capacity(0).build().unwrap();
+        assert_eq!(uncached.word_cache_capacity(), 0);
+
+        // Cached words are shifted to each occurrence's offset
+        let text = "kahve ☕ kahve ☕";
+        let spans = |tokenizer: &TurkishTokenizer| -> Vec<(u32, usize, usize)> {
+            tokenizer.tokenize_text(text).iter().map(|t| (t.id, t.start, t.end)).collect()
+        };
+        assert_eq!(spans(&tokenizer), spans(&uncached));
+        assert_eq!(tokenizer.encode(text), uncached.encode(text));
+
+        // Changing how unknown characters are emitted invalidates the cache
+        tokenizer.set_byte_fallback(false);
+        assert!(tokenizer.encode(text).contains(&tokenizer.unknown_marker.id));
+    }
+
+    #[test]
+    fn test_export_hf_tokenizer_json() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let exported = tokenizer.export_hf_tokenizer_json();
+
+        assert_eq!(exported["model"]["type"], "WordPiece");
+        assert_eq!(exported["model"]["vocab"]["kitap"], tokenizer.token_to_id("kitap").unwrap());
+        assert_eq!(
+            exported["model"]["vocab"].as_object().unwrap().len(),
+            tokenizer.vocab_size()
+        );
+        let added = exported["added_tokens"].as_array().unwrap();
+        assert!(added.iter().any(|t| t["content"] == "<eos>" && t["id"] == tokenizer.eos_token_id));
+    }
+
+    #[test]
+    fn test_lossless_byte_fallback() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let tokens = tokenizer.tokenize_lossless("☕");
+
+        assert_eq!(tokens.len(), 3);
+        assert!(tokens.iter().all(|t| t.token_type == TokenType::Byte));
+        assert_eq!(tokens[0].token, "<0xE2>");
+        assert_eq!(tokenizer.token_to_id("<0xE2>"), Some(tokens[0].id));
+    }
+
+    #[test]
+    fn test_visualize() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let html = tokenizer.visualize("Kitaplar & ev");
+
+        assert_eq!(html.matches("<span").count(), tokenizer.tokenize_text("Kitaplar & ev").len());
+        let marker = format!("title=\"ID {}, special\">&lt;uppercase&gt;</span>", tokenizer.uppercase_marker.id);
+        assert!(html.contains(&marker));
+        let kitap = tokenizer.token_to_id("kitap").unwrap();
+        assert!(html.contains(&format!("title=\"ID {}, root\">kitap</span>", kitap)));
+        assert!(html.contains(">lar</span>") && html.contains("tt-suffix"));
+        assert!(html.contains(">&amp;</span>"));
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        let report = tokenizer.coverage(["Kitaplar 😀", "ev"]);
+
+        assert_eq!((report.texts, report.words), (2, 3));
+        assert_eq!(report.tokens, tokenizer.encode("Kitaplar 😀").len() as u64 + 1);
+        assert_eq!((report.byte_tokens, report.unknown_tokens), (4, 4));
+        assert_eq!(report.top_oov_char_classes(), [("emoji", 1)]);
+        assert_eq!(
+            report.root_tokens + report.suffix_tokens + report.bpe_tokens + report.byte_tokens,
+            report.tokens
+        );
+        assert!(report.tokens_per_word() > 1.0);
+
+        tokenizer.set_byte_fallback(false);
+        let report = tokenizer.coverage(["😀😀 ☕"]);
+        assert_eq!(report.unknown_tokens, 3);
+        assert_eq!(report.oov_char_classes["emoji"], 3);
+        assert!((report.unknown_rate() - 3.0 / report.tokens as f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_count_token_frequencies() {
+        let tokenizer = TurkishTokenizer::new().unwrap();
+        let texts = ["kitap kitaplar", "ev", "kitap"];
+        let counts = tokenizer.count_token_frequencies(texts.iter().copied());
+
+        let kitap = tokenizer.token_to_id("kitap").unwrap();
+        assert_eq!(counts[&kitap], 3);
+        assert_eq!(counts[&tokenizer.token_to_id("ev").unwrap()], 1);
+        let total: u64 = counts.values().sum();
+        assert_eq!(total, texts.iter().map(|text| tokenizer.encode(text).len() as u64).sum::<u64>());
+        assert!(!counts.contains_key(&tokenizer.eos_token_id));
+    }
+
+    #[test]
+    fn test_train_bpe() {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        tokenizer.add_user_tokens(&[("kovid", None)]).unwrap();
+        let corpus: Vec<String> = (0..50)
+            .map(|i| format!("Kitaplarımı okudum, qwxz{} zqwx ve qwxzqwx", i % 7))
+            .collect();
+        let texts = || corpus.iter().map(String::as_str);
+        let first_bpe_id = tokenizer.suffixes.values().max().unwrap() + 1;
+
+        // Runs out of frequent pairs well before the target
+        let trained = tokenizer.train_bpe(texts(), first_bpe_id as usize + 1000).unwrap();
+        let bpe_len = trained.bpe_tokens.len() as u32;
+        assert!(bpe_len < 1000);
+        assert_eq!(trained.byte_offset, first_bpe_id + bpe_len);
+        assert!(trained.bpe_tokens.values().all(|&id| (first_bpe_id..trained.byte_offset).contains(&id)));
+        assert_eq!(trained.token_to_id("kitap"), tokenizer.token_to_id("kitap"));
+        assert_eq!(trained.token_to_id("kovid"), Some(trained.byte_offset + 256));
+        // Frequent leftovers become single tokens; punctuation keeps one
+        assert_eq!(trained.tokenize("qwxzqwx"), ["qwxzqwx"]);
+        assert!(trained.bpe_tokens.contains_key("qwxz") && trained.bpe_tokens.contains_key(","));
+        for text in texts() {
+            assert_eq!(trained.decode(&trained.encode(text)), tokenizer.decode(&tokenizer.encode(text)));
+        }
+
+        // The most frequent merge comes first once the alphabet is in
+        let alphabet = tokenizer.bpe_tokens.keys().filter(|token| token.chars().count() == 1).count();
+        let target = first_bpe_id as usize + alphabet + 1;
+        let trained = tokenizer.train_bpe(texts(), target).unwrap();
+        assert_eq!(trained.byte_offset as usize, target);
+        assert_eq!(trained.token_to_id("qw"), Some(target as u32 - 1));
+
+        assert!(matches!(
+            tokenizer.train_bpe(texts(), first_bpe_id as usize),
+            Err(TurkishTokenizerError::InvalidConfig(_))
+        ));
+    }
 }
@@ -0,0 +1,44 @@
+use crate::TurkishTokenizer;
+
+impl TurkishTokenizer {
+    /// Every ID sequence `phrase` can be generated as, for logit bias and
+    /// banned-word lists: the phrase as given, lowercased, capitalized and
+    /// uppercased, each on its own and after a space. Case is carried by
+    /// `<uppercase>` and `<all_caps>` markers, so "Ankara" and "ankara" are
+    /// different sequences ending in the same root, and a word after a
+    /// space starts with the space token. Surrounding whitespace is
+    /// ignored and duplicates are dropped, keeping the order above; a blank
+    /// phrase gives none.
+    ///
+    /// ```
+    /// use turkish_tokenizer::TurkishTokenizer;
+    ///
+    /// let tokenizer = TurkishTokenizer::new().unwrap();
+    /// let sequences = tokenizer.token_ids_for_phrase("kitap");
+    /// assert!(sequences.contains(&tokenizer.encode("Kitap")));
+    /// assert!(sequences.contains(&tokenizer.encode(" kitap")));
+    /// ```
+    pub fn token_ids_for_phrase(&self, phrase: &str) -> Vec<Vec<u32>> {
+        let phrase = phrase.trim();
+        if phrase.is_empty() {
+            return Vec::new();
+        }
+        let lower = self.case_mapping.lower(phrase);
+        let cased = [
+            phrase.to_string(),
+            lower.clone(),
+            self.case_mapping.capitalize(&lower),
+            self.case_mapping.upper(phrase),
+        ];
+        let mut sequences: Vec<Vec<u32>> = Vec::new();
+        for prefix in ["", " "] {
+            for form in &cased {
+                let ids = self.encode(&format!("{}{}", prefix, form));
+                if !ids.is_empty() && !sequences.contains(&ids) {
+                    sequences.push(ids);
+                }
+            }
+        }
+        sequences
+    }
+}
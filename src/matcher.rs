@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+
+/// Longest-prefix matching over one vocabulary table
+pub(crate) trait PrefixMatcher: Sized {
+    fn from_table(table: &HashMap<String, u32>) -> Self;
+
+    /// ID and char length of the longest entry that is a prefix of `chars`
+    fn longest_prefix(&self, chars: &[char]) -> Option<(u32, usize)>;
+
+    /// Every entry that is a prefix of `chars`, longest first
+    fn prefixes<'a>(&'a self, chars: &'a [char]) -> impl Iterator<Item = (u32, usize)> + 'a {
+        let mut limit = chars.len();
+        std::iter::from_fn(move || {
+            let (id, len) = self.longest_prefix(&chars[..limit]).filter(|&(_, len)| len > 0)?;
+            limit = len - 1;
+            Some((id, len))
+        })
+    }
+
+    /// Reflect `table` after its entry `removed` was replaced by `added`
+    fn replace(&mut self, table: &HashMap<String, u32>, removed: &str, added: &str, id: u32);
+
+    /// Reflect `table` after the entry `added` was inserted
+    fn add(&mut self, table: &HashMap<String, u32>, added: &str, id: u32);
+}
+
+/// Matcher backend chosen by the `fst` feature
+#[cfg(not(feature = "fst"))]
+pub(crate) type VocabMatcher = crate::trie::PrefixTrie;
+#[cfg(feature = "fst")]
+pub(crate) type VocabMatcher = crate::fst_matcher::FstMatcher;
@@ -0,0 +1,72 @@
+//! Swift and Kotlin bindings, built with the `uniffi` feature from the
+//! interface in `src/turkish_tokenizer.udl`.
+//!
+//! UniFFI has no `usize`, so tokens are mirrored with `u64` offsets.
+
+// The generated scaffolding leaves a blank line after a doc comment
+#![allow(clippy::empty_line_after_doc_comments)]
+
+pub use crate::{TokenType, TurkishTokenizerError};
+
+uniffi::include_scaffolding!("turkish_tokenizer");
+
+pub struct Token {
+    pub token: String,
+    pub id: u32,
+    pub token_type: TokenType,
+    pub start: u64,
+    pub end: u64,
+    pub word_id: Option<u64>,
+}
+
+impl From<crate::Token> for Token {
+    fn from(token: crate::Token) -> Self {
+        Token {
+            token: token.token,
+            id: token.id,
+            token_type: token.token_type,
+            start: token.start as u64,
+            end: token.end as u64,
+            word_id: token.word_id.map(|id| id as u64),
+        }
+    }
+}
+
+pub struct TurkishTokenizer {
+    inner: crate::TurkishTokenizer,
+}
+
+impl TurkishTokenizer {
+    /// Tokenizer with the embedded vocabulary
+    pub fn new() -> Result<Self, TurkishTokenizerError> {
+        Ok(Self { inner: crate::TurkishTokenizer::new()? })
+    }
+
+    pub fn from_json(roots_json: &str, suffixes_json: &str, bpe_tokens_json: &str) -> Result<Self, TurkishTokenizerError> {
+        Ok(Self { inner: crate::TurkishTokenizer::from_json(roots_json, suffixes_json, bpe_tokens_json)? })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TurkishTokenizerError> {
+        Ok(Self { inner: crate::TurkishTokenizer::from_bytes(bytes)? })
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        self.inner.encode(text)
+    }
+
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.inner.tokenize(text)
+    }
+
+    pub fn tokenize_text(&self, text: &str) -> Vec<Token> {
+        self.inner.tokenize_text(text).into_iter().map(Token::from).collect()
+    }
+
+    pub fn decode(&self, ids: &[u32]) -> String {
+        self.inner.decode(ids)
+    }
+
+    pub fn count_tokens(&self, text: &str) -> u64 {
+        self.inner.count_tokens(text) as u64
+    }
+}
@@ -0,0 +1,129 @@
+//! Turkish morphophonology checks used by `strict_morphology` and
+//! `consonant_alternation`.
+//!
+//! A suffix's first vowel must agree with the stem's last vowel in
+//! frontness (`ev-ler`, `kitap-lar`) and, if it is a high vowel, also in
+//! rounding (`göz-ü`, `kız-ı`). Suffixes whose vowel never alternates, such
+//! as `-ken` and `-yor`, are exempt. Loanwords that take front suffixes
+//! after a back vowel (`saat-ler`) are not recognised.
+//!
+//! A root-final `p`, `ç`, `t` or `k` is voiced to `b`, `c`, `d` or `ğ` before
+//! a vowel (`kitap` → `kitab-ı`, `ağaç` → `ağac-a`), and `nk` becomes `ng`
+//! (`renk` → `reng-i`).
+
+const FRONT_VOWELS: &[char] = &['e', 'i', 'î', 'ö', 'ü'];
+const BACK_VOWELS: &[char] = &['a', 'â', 'ı', 'o', 'u', 'û'];
+const ROUNDED_VOWELS: &[char] = &['o', 'ö', 'u', 'ü', 'û'];
+const HIGH_VOWELS: &[char] = &['ı', 'i', 'î', 'u', 'ü', 'û'];
+
+/// Suffixes whose first vowel does not follow vowel harmony
+const INVARIANT_SUFFIXES: &[&str] = &["ken", "yken", "ki", "leyin", "yor"];
+
+pub(crate) fn is_vowel(c: char) -> bool {
+    FRONT_VOWELS.contains(&c) || BACK_VOWELS.contains(&c)
+}
+
+/// The dictionary form of the root-final consonant `c` given whether a
+/// vowel follows it: voiced consonants before a vowel are devoiced, and
+/// voiceless ones elsewhere are voiced, for roots listed only that way
+pub(crate) fn alternate_consonant(prev: Option<char>, c: char, vowel_follows: bool) -> Option<char> {
+    match (c, vowel_follows) {
+        ('b', true) => Some('p'),
+        ('c', true) => Some('ç'),
+        ('d', true) => Some('t'),
+        ('ğ', true) => Some('k'),
+        ('g', true) if prev == Some('n') => Some('k'),
+        ('p', false) => Some('b'),
+        ('ç', false) => Some('c'),
+        ('t', false) => Some('d'),
+        ('k', false) => Some('ğ'),
+        _ => None,
+    }
+}
+
+/// Whether `suffix` may follow `stem` under vowel harmony. Lowercase input
+/// is expected; a stem or suffix without vowels always harmonizes.
+pub(crate) fn harmonizes(stem: &[char], suffix: &[char]) -> bool {
+    let last = stem.iter().rev().copied().find(|&c| is_vowel(c));
+    let first = suffix.iter().copied().find(|&c| is_vowel(c));
+    let (Some(last), Some(first)) = (last, first) else {
+        return true;
+    };
+    if INVARIANT_SUFFIXES.iter().any(|s| s.chars().eq(suffix.iter().copied())) {
+        return true;
+    }
+
+    FRONT_VOWELS.contains(&last) == FRONT_VOWELS.contains(&first)
+        && (!HIGH_VOWELS.contains(&first) || ROUNDED_VOWELS.contains(&last) == ROUNDED_VOWELS.contains(&first))
+}
+
+/// How far a word has progressed through its suffixes under
+/// `morphotactics`. Suffixes attach in this order: derivational suffixes,
+/// then verbal ones (voice, negation, tense), plural, possessive, case and
+/// finally copula or person endings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Slot {
+    /// Start of a segment, before any root
+    Start,
+    Stem,
+    Derivation,
+    Verbal,
+    Plural,
+    Possessive,
+    Case,
+    Copula,
+}
+
+impl Slot {
+    pub(crate) const ALL: [Slot; 8] = [
+        Slot::Start,
+        Slot::Stem,
+        Slot::Derivation,
+        Slot::Verbal,
+        Slot::Plural,
+        Slot::Possessive,
+        Slot::Case,
+        Slot::Copula,
+    ];
+}
+
+/// The relative suffix `-ki`, which turns a case-marked word into a new stem
+const KI_ID: u32 = 20059;
+
+/// Slots the suffix `id` can fill. Buffer letters and single vowels, which
+/// fit almost anywhere after a stem, have none.
+pub(crate) fn suffix_slots(id: u32) -> &'static [Slot] {
+    use Slot::*;
+    match id {
+        20000 => &[Plural, Copula],
+        20001 => &[Verbal, Case],
+        20002 | 20003 | 20011 | 20012 | 20031 | 20032 | 20041 | 20042 | 20043 | 20046 | 20071 => &[Verbal],
+        20005 | 20023 | 20047 => &[Derivation, Case],
+        20007 => &[Derivation, Copula],
+        20013 | 20014 | 20039 => &[Possessive, Copula],
+        20015 => &[Possessive],
+        20016 | 20026 | 20044 | 20045 | 20070 => &[Verbal, Copula],
+        20017 | 20022 | 20024 | 20025 => &[Case],
+        20021 => &[Possessive, Case],
+        20028 | 20061 | 20062 | 20063 => &[Copula],
+        20033..=20038 | 20040 | 20060 | 20064 => &[],
+        20072.. => &[],
+        _ => &[Derivation],
+    }
+}
+
+/// The slot after attaching suffix `id` at `slot`: the earliest one it can
+/// fill that does not precede `slot`, or `None` if it cannot follow
+pub(crate) fn next_slot(slot: Slot, id: u32) -> Option<Slot> {
+    if slot == Slot::Start {
+        return None;
+    }
+    if id == KI_ID {
+        return (slot <= Slot::Case).then_some(Slot::Stem);
+    }
+    let slots = suffix_slots(id);
+    if slots.is_empty() {
+        return Some(slot);
+    }
+    slots.iter().copied().find(|&s| s >= slot)
+}
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::vocab_tables::VocabTables;
+use crate::{ABBREVIATIONS_FILE, ADDED_TOKENS_FILE, CaseMapping, ChatTemplate, ControlCharMode, EMOJI_TOKENS_FILE, EntityModes, ForeignWordMode, NumberMode, PostProcessor, SegmentationMode, TurkishTokenizer, TurkishTokenizerError, UnknownStrategy, WhitespaceMode, BPE_FILE, HF_TOKENIZER_FILE, ROOTS_FILE, SCORES_FILE, SUFFIXES_FILE, USER_TOKENS_FILE};
+
+pub const TOKENIZER_CONFIG_FILE: &str = "tokenizer_config.json";
+pub const SPECIAL_TOKENS_MAP_FILE: &str = "special_tokens_map.json";
+
+/// `tokenizer_class` written to `tokenizer_config.json`: the Python
+/// wrapper `AutoTokenizer` instantiates once it is registered
+pub const TOKENIZER_CLASS: &str = "HFTurkishTokenizer";
+
+/// Special tokens as stored in `special_tokens_map.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecialTokensMap {
+    pub bos_token: String,
+    pub eos_token: String,
+    pub unk_token: String,
+    pub pad_token: String,
+    pub sep_token: String,
+    pub cls_token: String,
+    #[serde(default = "default_mask_token")]
+    pub mask_token: String,
+    /// Tokens added with `add_special_tokens`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_special_tokens: Vec<String>,
+}
+
+/// Tokenizer settings as stored in `tokenizer_config.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenizerConfig {
+    /// Python class Hugging Face instantiates for the directory
+    #[serde(default = "default_tokenizer_class")]
+    pub tokenizer_class: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_max_length: Option<usize>,
+    #[serde(flatten)]
+    pub special_tokens: SpecialTokensMap,
+    pub add_bos_token: bool,
+    pub add_eos_token: bool,
+    pub split_camel_case: bool,
+    #[serde(default)]
+    pub split_punctuation: bool,
+    #[serde(default)]
+    pub join_line_breaks: bool,
+    #[serde(default)]
+    pub split_compounds: bool,
+    pub lowercase: bool,
+    #[serde(default)]
+    pub case_mapping: CaseMapping,
+    #[serde(default)]
+    pub strict_morphology: bool,
+    #[serde(default)]
+    pub morphotactics: bool,
+    #[serde(default)]
+    pub consonant_alternation: bool,
+    #[serde(default)]
+    pub segmentation: SegmentationMode,
+    pub emit_case_markers: bool,
+    #[serde(default)]
+    pub all_caps_markers: bool,
+    #[serde(default)]
+    pub clitics: bool,
+    pub unknown_strategy: UnknownStrategy,
+    #[serde(default)]
+    pub number_mode: NumberMode,
+    #[serde(default)]
+    pub whitespace_mode: WhitespaceMode,
+    #[serde(default)]
+    pub control_chars: ControlCharMode,
+    #[serde(default)]
+    pub entity_modes: EntityModes,
+    #[serde(default)]
+    pub foreign_word_mode: ForeignWordMode,
+    #[serde(default)]
+    pub restore_diacritics: bool,
+    #[serde(default)]
+    pub ascii_fold_matching: bool,
+    #[serde(default)]
+    pub post_processor: PostProcessor,
+    /// The chat template as Jinja, for Hugging Face; not read back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_template: Option<String>,
+    /// The chat template `apply_chat_template` uses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_format: Option<ChatTemplate>,
+}
+
+/// Everything `to_bytes` writes
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TokenizerSnapshot {
+    pub tables: VocabTables,
+    pub user_tokens: Vec<(String, u32)>,
+    /// `tokenizer_config.json` contents, kept as JSON because bincode
+    /// cannot encode its flattened and optional fields
+    pub config: String,
+    pub added_tokens: Vec<(String, u32)>,
+    pub word_cache_capacity: usize,
+    pub scores: Option<Vec<(u32, f32)>>,
+    pub emoji_tokens: Vec<(String, u32)>,
+    pub abbreviations: Vec<(String, u32)>,
+}
+
+fn default_mask_token() -> String {
+    "<mask>".to_string()
+}
+
+fn default_tokenizer_class() -> String {
+    TOKENIZER_CLASS.to_string()
+}
+
+impl TurkishTokenizer {
+    /// Special tokens in the `special_tokens_map.json` layout
+    pub fn special_tokens_map(&self) -> SpecialTokensMap {
+        SpecialTokensMap {
+            bos_token: self.bos_token.clone(),
+            eos_token: self.eos_token.clone(),
+            unk_token: self.unknown_marker.token.clone(),
+            pad_token: self.pad_token.clone(),
+            sep_token: self.sep_token.clone(),
+            cls_token: self.cls_token.clone(),
+            mask_token: self.mask_token.clone(),
+            additional_special_tokens: self.additional_special_tokens(),
+        }
+    }
+
+    /// Current settings in the `tokenizer_config.json` layout
+    pub fn tokenizer_config(&self) -> TokenizerConfig {
+        TokenizerConfig {
+            tokenizer_class: TOKENIZER_CLASS.to_string(),
+            model_max_length: self.model_max_length,
+            special_tokens: self.special_tokens_map(),
+            add_bos_token: self.add_bos_token,
+            add_eos_token: self.add_eos_token,
+            split_camel_case: self.split_camel_case,
+            split_punctuation: self.split_punctuation,
+            join_line_breaks: self.join_line_breaks,
+            split_compounds: self.split_compounds,
+            lowercase: self.lowercase,
+            case_mapping: self.case_mapping,
+            strict_morphology: self.strict_morphology,
+            morphotactics: self.morphotactics,
+            consonant_alternation: self.consonant_alternation,
+            segmentation: self.segmentation,
+            emit_case_markers: self.emit_case_markers,
+            all_caps_markers: self.all_caps_markers(),
+            clitics: self.clitics(),
+            unknown_strategy: self.unknown_strategy,
+            number_mode: self.number_mode,
+            whitespace_mode: self.whitespace_mode,
+            control_chars: self.control_chars,
+            entity_modes: self.entity_modes,
+            foreign_word_mode: self.foreign_word_mode,
+            restore_diacritics: self.restore_diacritics,
+            ascii_fold_matching: self.ascii_fold_matching,
+            post_processor: self.post_processor.clone(),
+            chat_template: self.chat_template().map(ChatTemplate::to_jinja),
+            chat_format: self.chat_template().cloned(),
+        }
+    }
+
+    /// Write `tokenizer_config.json` to `dir`, creating it if needed. Next
+    /// to the settings it holds the special tokens, `model_max_length` and
+    /// `tokenizer_class` in Hugging Face's layout, so `HFTurkishTokenizer`
+    /// and `AutoTokenizer` load the directory from Python.
+    pub fn write_tokenizer_config(&self, dir: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        fs::write(
+            dir.join(TOKENIZER_CONFIG_FILE),
+            serde_json::to_string_pretty(&self.tokenizer_config())?,
+        )?;
+        Ok(())
+    }
+
+    /// Write the vocabulary tables, `tokenizer_config.json`,
+    /// `special_tokens_map.json` and an approximate Hugging Face
+    /// `tokenizer.json` to `dir`, creating it if needed. User tokens go to
+    /// `user_tokens.json` and added tokens to `added_tokens.json`, so the
+    /// byte-fallback IDs stay where they are. Token scores, if set, go to
+    /// `scores.json`, the emoji table to `emoji_tokens.json` and the
+    /// abbreviation table to `abbreviations.json`.
+    pub fn save_pretrained(&self, dir: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        fs::write(dir.join(ROOTS_FILE), self.table_json(&self.base_roots())?)?;
+        fs::write(dir.join(SUFFIXES_FILE), self.table_json(&self.suffixes)?)?;
+        fs::write(dir.join(BPE_FILE), self.table_json(&self.bpe_tokens)?)?;
+        self.write_tokenizer_config(dir)?;
+        fs::write(
+            dir.join(SPECIAL_TOKENS_MAP_FILE),
+            serde_json::to_string_pretty(&self.special_tokens_map())?,
+        )?;
+        self.save_hf_tokenizer_json(dir.join(HF_TOKENIZER_FILE))?;
+        if !self.user_tokens.is_empty() {
+            self.save_user_tokens(dir.join(USER_TOKENS_FILE))?;
+        }
+        if self.added_tokens.iter().any(|added| !added.special) {
+            self.save_added_tokens(dir.join(ADDED_TOKENS_FILE))?;
+        }
+        if self.scores.is_some() {
+            self.save_scores(dir.join(SCORES_FILE))?;
+        }
+        if !self.emoji_tokens.is_empty() {
+            self.save_emoji_tokens(dir.join(EMOJI_TOKENS_FILE))?;
+        }
+        if !self.abbreviations.is_empty() {
+            self.save_abbreviations(dir.join(ABBREVIATIONS_FILE))?;
+        }
+        Ok(())
+    }
+
+    /// Load a tokenizer saved with `save_pretrained`. Directories holding
+    /// only the vocabulary tables load with default settings.
+    pub fn from_pretrained(dir: impl AsRef<Path>) -> Result<Self, TurkishTokenizerError> {
+        let dir = dir.as_ref();
+        let mut tokenizer = Self::from_dir(dir)?;
+        let user_tokens_path = dir.join(USER_TOKENS_FILE);
+        if user_tokens_path.exists() {
+            tokenizer.load_user_tokens(user_tokens_path)?;
+        }
+
+        let config_path = dir.join(TOKENIZER_CONFIG_FILE);
+        if config_path.exists() {
+            let config: TokenizerConfig = serde_json::from_str(&fs::read_to_string(config_path)?)?;
+            tokenizer.apply_config(config)?;
+        }
+        let added_tokens_path = dir.join(ADDED_TOKENS_FILE);
+        if added_tokens_path.exists() {
+            tokenizer.load_added_tokens(added_tokens_path)?;
+        }
+        let emoji_tokens_path = dir.join(EMOJI_TOKENS_FILE);
+        if emoji_tokens_path.exists() {
+            tokenizer.load_emoji_tokens(emoji_tokens_path)?;
+        }
+        let abbreviations_path = dir.join(ABBREVIATIONS_FILE);
+        if abbreviations_path.exists() {
+            tokenizer.load_abbreviations(abbreviations_path)?;
+        }
+        let scores_path = dir.join(SCORES_FILE);
+        if scores_path.exists() {
+            tokenizer.load_scores(scores_path)?;
+        }
+        Ok(tokenizer)
+    }
+
+    fn apply_config(&mut self, config: TokenizerConfig) -> Result<(), TurkishTokenizerError> {
+        let special = config.special_tokens;
+        let special_tokens = [
+            ("pad_token", &special.pad_token),
+            ("eos_token", &special.eos_token),
+            ("bos_token", &special.bos_token),
+            ("sep_token", &special.sep_token),
+            ("cls_token", &special.cls_token),
+            ("mask_token", &special.mask_token),
+        ];
+        for (role, token) in special_tokens {
+            self.assign_special_token(role, token)?;
+        }
+        let additional: Vec<&str> = special.additional_special_tokens.iter().map(String::as_str).collect();
+        self.add_special_tokens(&additional)?;
+
+        self.model_max_length = config.model_max_length;
+        self.add_bos_token = config.add_bos_token;
+        self.add_eos_token = config.add_eos_token;
+        self.split_camel_case = config.split_camel_case;
+        self.split_punctuation = config.split_punctuation;
+        self.join_line_breaks = config.join_line_breaks;
+        self.split_compounds = config.split_compounds;
+        self.lowercase = config.lowercase;
+        self.set_case_mapping(config.case_mapping);
+        self.strict_morphology = config.strict_morphology;
+        self.morphotactics = config.morphotactics;
+        self.consonant_alternation = config.consonant_alternation;
+        self.segmentation = config.segmentation;
+        self.emit_case_markers = config.emit_case_markers;
+        self.unknown_strategy = config.unknown_strategy;
+        self.whitespace_mode = config.whitespace_mode;
+        self.control_chars = config.control_chars;
+        self.foreign_word_mode = config.foreign_word_mode;
+        self.restore_diacritics = config.restore_diacritics;
+        self.ascii_fold_matching = config.ascii_fold_matching;
+        self.set_number_mode(config.number_mode)?;
+        self.set_all_caps_markers(config.all_caps_markers)?;
+        self.set_clitics(config.clitics)?;
+        self.set_entity_modes(config.entity_modes)?;
+        self.set_post_processor(config.post_processor)?;
+        if let Some(template) = config.chat_format {
+            self.set_chat_template(template)?;
+        }
+        self.word_cache.clear();
+        Ok(())
+    }
+
+    /// Serialize a vocabulary table ordered by ID
+    pub(crate) fn table_json(&self, table: &HashMap<String, u32>) -> Result<String, serde_json::Error> {
+        let entries: serde_json::Map<String, serde_json::Value> =
+            self.table_entries(table).into_iter().map(|(form, id)| (form, id.into())).collect();
+        serde_json::to_string_pretty(&entries)
+    }
+
+    /// Entries of a vocabulary table ordered by ID. Forms sharing an ID
+    /// keep the decoder's order, which its suffix selection rules depend on.
+    fn table_entries(&self, table: &HashMap<String, u32>) -> Vec<(String, u32)> {
+        let mut ids: Vec<u32> = table.values().copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut entries = Vec::with_capacity(table.len());
+        for id in ids {
+            for form in self.decoder.forms(id).unwrap_or_default() {
+                if table.get(form) == Some(&id) {
+                    entries.push((form.clone(), id));
+                }
+            }
+        }
+        entries
+    }
+
+    /// Roots without the entries added with `add_user_tokens`
+    pub(crate) fn base_roots(&self) -> HashMap<String, u32> {
+        let mut roots = HashMap::clone(&self.roots);
+        for (token, _) in &self.user_tokens {
+            roots.remove(token);
+        }
+        roots
+    }
+
+    /// Serialize the whole tokenizer, vocabulary and settings included, to
+    /// a compact binary snapshot for `from_bytes`. Used to pickle the Python
+    /// class for worker processes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TurkishTokenizerError> {
+        Ok(bincode::serialize(&self.snapshot()?)?)
+    }
+
+    /// Rebuild a tokenizer from a `to_bytes` snapshot
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TurkishTokenizerError> {
+        Self::from_snapshot(bincode::deserialize(bytes)?)
+    }
+
+    pub(crate) fn snapshot(&self) -> Result<TokenizerSnapshot, TurkishTokenizerError> {
+        Ok(TokenizerSnapshot {
+            tables: VocabTables {
+                roots: self.table_entries(&self.base_roots()),
+                suffixes: self.table_entries(&self.suffixes),
+                bpe_tokens: self.table_entries(&self.bpe_tokens),
+            },
+            user_tokens: self.user_tokens.clone(),
+            config: serde_json::to_string(&self.tokenizer_config())?,
+            added_tokens: self.added_token_entries(),
+            word_cache_capacity: self.word_cache_capacity(),
+            scores: self.scores().map(|scores| scores.iter().map(|(&id, &score)| (id, score)).collect()),
+            emoji_tokens: self.emoji_tokens(),
+            abbreviations: self.abbreviations(),
+        })
+    }
+
+    pub(crate) fn from_snapshot(snapshot: TokenizerSnapshot) -> Result<Self, TurkishTokenizerError> {
+        let mut tokenizer = Self::from_vocab_parts(snapshot.tables)?;
+        let user_tokens: Vec<(&str, Option<u32>)> = snapshot
+            .user_tokens
+            .iter()
+            .map(|(token, id)| (token.as_str(), Some(*id)))
+            .collect();
+        tokenizer.add_user_tokens(&user_tokens)?;
+        tokenizer.apply_config(serde_json::from_str(&snapshot.config)?)?;
+        tokenizer.restore_added_tokens(snapshot.added_tokens)?;
+        tokenizer.restore_emoji_tokens(snapshot.emoji_tokens);
+        tokenizer.restore_abbreviations(snapshot.abbreviations);
+        tokenizer.set_word_cache_capacity(snapshot.word_cache_capacity);
+        if let Some(scores) = snapshot.scores {
+            tokenizer.set_scores(scores.into_iter().collect());
+        }
+        Ok(tokenizer)
+    }
+}
@@ -0,0 +1,111 @@
+//! Vocabulary pruning driven by token frequency counts.
+//!
+//! Rare BPE entries are dropped and the IDs after them shift down to close
+//! the gaps, so the byte fallback, user and added tokens stay contiguous.
+//! Roots, suffixes and special tokens are never dropped. Single-character
+//! BPE tokens are kept too, so no character loses its token and
+//! punctuation keeps the ID its special handling relies on.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::{TurkishTokenizer, TurkishTokenizerError};
+
+/// Old to new token IDs after `prune_vocab`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IdRemapping {
+    old_to_new: Vec<Option<u32>>,
+    new_to_old: Vec<u32>,
+}
+
+impl IdRemapping {
+    /// New ID of `old_id`, or `None` if it was pruned
+    pub fn get(&self, old_id: u32) -> Option<u32> {
+        self.old_to_new.get(old_id as usize).copied().flatten()
+    }
+
+    /// New ID of every old ID, indexed by old ID
+    pub fn old_to_new(&self) -> &[Option<u32>] {
+        &self.old_to_new
+    }
+
+    /// Old ID of every new ID, indexed by new ID. Taking the rows of an
+    /// embedding matrix in this order gives the pruned matrix.
+    pub fn new_to_old(&self) -> &[u32] {
+        &self.new_to_old
+    }
+
+    /// Old IDs that were pruned
+    pub fn removed(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.old_to_new.len() as u32).filter(|&id| self.get(id).is_none())
+    }
+
+    /// Map IDs encoded with the old vocabulary to the new one, or `None` if
+    /// any of them was pruned
+    pub fn remap(&self, ids: &[u32]) -> Option<Vec<u32>> {
+        ids.iter().map(|&id| self.get(id)).collect()
+    }
+}
+
+impl TurkishTokenizer {
+    /// Copy of this tokenizer without the BPE entries occurring fewer than
+    /// `min_count` times in `counts`, as returned by
+    /// `count_token_frequencies`, with IDs compacted. The remapping says
+    /// where each old ID went.
+    pub fn prune_vocab(
+        &self,
+        counts: &HashMap<u32, u64>,
+        min_count: u64,
+    ) -> Result<(TurkishTokenizer, IdRemapping), TurkishTokenizerError> {
+        let shared: HashSet<u32> = self
+            .roots
+            .values()
+            .chain(self.suffixes.values())
+            .chain(self.emoji_tokens.values())
+            .chain(self.abbreviations.values())
+            .copied()
+            .collect();
+        let mut removed: Vec<u32> = self
+            .bpe_tokens
+            .iter()
+            .filter(|(token, &id)| {
+                token.chars().nth(1).is_some()
+                    && counts.get(&id).copied().unwrap_or(0) < min_count
+                    && !shared.contains(&id)
+            })
+            .map(|(_, &id)| id)
+            .collect();
+        removed.sort_unstable();
+        removed.dedup();
+        let remap = |id: u32| id - removed.partition_point(|&removed_id| removed_id < id) as u32;
+
+        let mut snapshot = self.snapshot()?;
+        snapshot.tables.bpe_tokens.retain(|(_, id)| removed.binary_search(id).is_err());
+        let tables = &mut snapshot.tables;
+        let entries = [&mut tables.roots, &mut tables.suffixes, &mut tables.bpe_tokens]
+            .into_iter()
+            .chain([
+                &mut snapshot.user_tokens,
+                &mut snapshot.added_tokens,
+                &mut snapshot.emoji_tokens,
+                &mut snapshot.abbreviations,
+            ]);
+        for (_, id) in entries.flatten() {
+            *id = remap(*id);
+        }
+        if let Some(scores) = &mut snapshot.scores {
+            scores.retain(|(id, _)| removed.binary_search(id).is_err());
+            for (id, _) in scores {
+                *id = remap(*id);
+            }
+        }
+        let pruned = Self::from_snapshot(snapshot)?;
+
+        let len = self.vocab.values().max().map_or(0, |&max_id| max_id + 1);
+        let old_to_new: Vec<Option<u32>> = (0..len)
+            .map(|id| removed.binary_search(&id).is_err().then(|| remap(id)))
+            .collect();
+        let new_to_old = (0..len).filter(|id| removed.binary_search(id).is_err()).collect();
+        Ok((pruned, IdRemapping { old_to_new, new_to_old }))
+    }
+}
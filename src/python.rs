@@ -0,0 +1,920 @@
+//! Python bindings, built with the `python` feature.
+
+use numpy::{PyArray1, PyArray2, PyArray3};
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+use std::collections::HashMap;
+
+use crate::{
+    ChatMessage, ChatTemplate, EncodeOptions, EncodingResult, MorphAnalysis, PaddingSide, PaddingStrategy, PartOfSpeech,
+    PostProcessor, SuffixCategory, SuffixInfo, TemplateProcessing, Token, TokenType, TruncationStrategy, TurkishTokenizer,
+    TurkishTokenizerError, VocabIndex,
+};
+
+impl From<TurkishTokenizerError> for PyErr {
+    fn from(error: TurkishTokenizerError) -> Self {
+        match error {
+            TurkishTokenizerError::Io(_) => PyOSError::new_err(error.to_string()),
+            TurkishTokenizerError::UnknownToken(_) => PyKeyError::new_err(error.to_string()),
+            TurkishTokenizerError::IdOutOfRange(_) => PyIndexError::new_err(error.to_string()),
+            _ => PyValueError::new_err(error.to_string()),
+        }
+    }
+}
+
+#[pymethods]
+impl Token {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Token(token={:?}, id={}, token_type=TokenType.{:?}, start={}, end={}, word_id={})",
+            self.token,
+            self.id,
+            self.token_type,
+            self.start,
+            self.end,
+            self.word_id.map_or("None".to_string(), |id| id.to_string()),
+        )
+    }
+
+    pub fn __str__(&self) -> &str {
+        &self.token
+    }
+}
+
+#[pymethods]
+impl VocabIndex {
+    /// Sorted IDs of the vocabulary entries starting with `prefix`
+    #[pyo3(name = "ids_with_prefix")]
+    pub fn py_ids_with_prefix(&self, py: Python<'_>, prefix: &str) -> Vec<u32> {
+        py.detach(|| self.ids_with_prefix(prefix))
+    }
+
+    /// Sorted IDs of the tokens that can come next when the output must
+    /// continue with `text`
+    #[pyo3(name = "ids_continuing")]
+    pub fn py_ids_continuing(&self, py: Python<'_>, text: &str) -> Vec<u32> {
+        py.detach(|| self.ids_continuing(text))
+    }
+}
+
+#[pymethods]
+impl TurkishTokenizer {
+    #[new]
+    pub fn py_new() -> PyResult<Self> {
+        Ok(Self::new()?)
+    }
+
+    /// Load a tokenizer saved with `save_pretrained`, or a directory holding
+    /// `kokler.json`, `ekler.json` and `bpe_tokenler.json`
+    #[staticmethod]
+    #[pyo3(name = "from_pretrained")]
+    pub fn py_from_pretrained(dir: &str) -> PyResult<Self> {
+        Ok(Self::from_pretrained(dir)?)
+    }
+
+    /// Serialize the tokenizer to a binary snapshot
+    #[pyo3(name = "to_bytes")]
+    pub fn py_to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        let bytes = self.to_bytes()?;
+        Ok(pyo3::types::PyBytes::new(py, &bytes))
+    }
+
+    /// Rebuild a tokenizer from a `to_bytes` snapshot
+    #[staticmethod]
+    #[pyo3(name = "from_bytes")]
+    pub fn py_from_bytes(data: &[u8]) -> PyResult<Self> {
+        Ok(Self::from_bytes(data)?)
+    }
+
+    /// Pickle as a `to_bytes` snapshot, so worker processes get the same
+    /// vocabulary and settings without reloading any files
+    pub fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, pyo3::types::PyBytes>,))> {
+        let from_bytes = slf.get_type().getattr("from_bytes")?;
+        Ok((from_bytes, (slf.borrow().py_to_bytes(slf.py())?,)))
+    }
+
+    /// Get a Hugging Face `tokenizers`-compatible `tokenizer.json` as a string
+    #[pyo3(name = "export_hf_tokenizer_json")]
+    pub fn py_export_hf_tokenizer_json(&self) -> String {
+        self.export_hf_tokenizer_json().to_string()
+    }
+
+    /// Save the vocabulary and configuration to a directory
+    #[pyo3(name = "save_pretrained")]
+    pub fn py_save_pretrained(&self, dir: &str) -> PyResult<()> {
+        Ok(self.save_pretrained(dir)?)
+    }
+
+    /// Write only `tokenizer_config.json`, in the layout Hugging Face's
+    /// `from_pretrained` reads
+    #[pyo3(name = "write_tokenizer_config")]
+    pub fn py_write_tokenizer_config(&self, dir: &str) -> PyResult<()> {
+        Ok(self.write_tokenizer_config(dir)?)
+    }
+
+    /// Get the vocabulary as a Python dictionary
+    #[pyo3(name = "get_vocab")]
+    pub fn py_get_vocab(&self) -> HashMap<String, u32> {
+        HashMap::clone(&self.vocab)
+    }
+
+    /// Root table, special tokens and user tokens included
+    #[pyo3(name = "get_roots")]
+    pub fn py_get_roots(&self) -> HashMap<String, u32> {
+        HashMap::clone(&self.roots)
+    }
+
+    #[pyo3(name = "get_suffixes")]
+    pub fn py_get_suffixes(&self) -> HashMap<String, u32> {
+        HashMap::clone(&self.suffixes)
+    }
+
+    #[pyo3(name = "get_bpe_vocab")]
+    pub fn py_get_bpe_vocab(&self) -> HashMap<String, u32> {
+        HashMap::clone(&self.bpe_tokens)
+    }
+
+    /// Which table an ID comes from, or None if it is not in the vocabulary
+    #[pyo3(name = "token_type_of_id")]
+    pub fn py_token_type_of_id(&self, id: u32) -> Option<TokenType> {
+        self.token_type_of_id(id)
+    }
+
+    /// Get vocabulary size
+    #[pyo3(name = "vocab_size")]
+    pub fn py_vocab_size(&self) -> usize {
+        self.vocab_size()
+    }
+
+    /// Index the vocabulary for prefix queries during constrained decoding
+    #[pyo3(name = "vocab_index")]
+    pub fn py_vocab_index(&self) -> VocabIndex {
+        self.vocab_index()
+    }
+
+    /// Encode text to token IDs
+    #[pyo3(name = "encode", signature = (text, add_special_tokens = false))]
+    pub fn py_encode(&self, py: Python<'_>, text: &str, add_special_tokens: bool) -> Vec<u32> {
+        py.detach(|| self.encode_with(text, add_special_tokens))
+    }
+
+    /// Number of tokens `encode` would produce, without building them
+    #[pyo3(name = "count_tokens")]
+    pub fn py_count_tokens(&self, py: Python<'_>, text: &str) -> usize {
+        py.detach(|| self.count_tokens(text))
+    }
+
+    /// Every ID sequence a phrase can be generated as, for logit bias and
+    /// banned-word lists
+    #[pyo3(name = "token_ids_for_phrase")]
+    pub fn py_token_ids_for_phrase(&self, phrase: &str) -> Vec<Vec<u32>> {
+        self.token_ids_for_phrase(phrase)
+    }
+
+    /// Encode with BPE-dropout-style sampling; the same seed gives the same IDs
+    #[pyo3(name = "encode_with_sampling", signature = (text, alpha = 0.1, seed = 0))]
+    pub fn py_encode_with_sampling(&self, py: Python<'_>, text: &str, alpha: f64, seed: u64) -> Vec<u32> {
+        py.detach(|| self.encode_with_sampling(text, alpha, seed))
+    }
+
+    /// Tokenize text to string tokens
+    #[pyo3(name = "tokenize")]
+    pub fn py_tokenize(&self, py: Python<'_>, text: &str) -> Vec<String> {
+        py.detach(|| self.tokenize(text))
+    }
+
+    /// Source word index of each token, `None` for spaces and special tokens
+    #[pyo3(name = "word_ids")]
+    pub fn py_word_ids(&self, py: Python<'_>, text: &str) -> Vec<Option<usize>> {
+        py.detach(|| self.tokenize_text(text).into_iter().map(|t| t.word_id).collect())
+    }
+
+    /// Encode a text pair as `<cls> A <sep> B <sep>` with segment IDs
+    #[pyo3(name = "encode_pair")]
+    pub fn py_encode_pair(&self, py: Python<'_>, text_a: &str, text_b: &str) -> HashMap<String, Vec<u32>> {
+        let encoding = py.detach(|| self.encode_pair(text_a, text_b));
+        let mut result = HashMap::new();
+        result.insert("input_ids".to_string(), encoding.input_ids);
+        result.insert("token_type_ids".to_string(), encoding.token_type_ids);
+        result.insert("attention_mask".to_string(), encoding.attention_mask);
+        result
+    }
+
+    /// Encode many texts in parallel, releasing the GIL
+    #[pyo3(name = "encode_batch")]
+    pub fn py_encode_batch(&self, py: Python<'_>, texts: Vec<String>) -> Vec<Vec<u32>> {
+        py.detach(|| {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            self.encode_batch(&texts)
+        })
+    }
+
+    /// Tokenize many texts in parallel, releasing the GIL
+    #[pyo3(name = "tokenize_batch")]
+    pub fn py_tokenize_batch(&self, py: Python<'_>, texts: Vec<String>) -> Vec<Vec<String>> {
+        py.detach(|| {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            self.tokenize_batch(&texts)
+        })
+    }
+
+    /// Encode text so that decoding reproduces it exactly
+    #[pyo3(name = "encode_lossless")]
+    pub fn py_encode_lossless(&self, py: Python<'_>, text: &str) -> Vec<u32> {
+        py.detach(|| self.encode_lossless(text))
+    }
+
+    /// Tokenize text so that decoding reproduces it exactly
+    #[pyo3(name = "tokenize_lossless")]
+    pub fn py_tokenize_lossless(&self, py: Python<'_>, text: &str) -> Vec<Token> {
+        py.detach(|| self.tokenize_lossless(text))
+    }
+
+    /// Get detailed token information
+    #[pyo3(name = "tokenize_text")]
+    pub fn py_tokenize_text(&self, py: Python<'_>, text: &str) -> Vec<Token> {
+        py.detach(|| self.tokenize_text(text))
+    }
+
+    /// Split a word into its stem and suffix chain
+    #[pyo3(name = "analyze")]
+    pub fn py_analyze(&self, py: Python<'_>, word: &str) -> MorphAnalysis {
+        py.detach(|| self.analyze(word))
+    }
+
+    /// Analyze every space-delimited word of a text
+    #[pyo3(name = "analyze_text")]
+    pub fn py_analyze_text(&self, py: Python<'_>, text: &str) -> Vec<MorphAnalysis> {
+        py.detach(|| self.analyze_text(text))
+    }
+
+    /// Register extra root entries, as `(token, id)` pairs with `id` None
+    /// for the next free ID. Returns the ID of every entry.
+    #[pyo3(name = "add_user_tokens")]
+    pub fn py_add_user_tokens(&mut self, tokens: Vec<(String, Option<u32>)>) -> PyResult<Vec<u32>> {
+        let tokens: Vec<(&str, Option<u32>)> = tokens.iter().map(|(token, id)| (token.as_str(), *id)).collect();
+        Ok(self.add_user_tokens(&tokens)?)
+    }
+
+    /// Add tokens matched whole anywhere in the text. Returns how many
+    /// were new to the vocabulary.
+    #[pyo3(name = "add_tokens")]
+    pub fn py_add_tokens(&mut self, tokens: Vec<String>) -> usize {
+        let tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+        self.add_tokens(&tokens)
+    }
+
+    /// Give each emoji or symbol a single token; `None` adds the common
+    /// set. Returns how many were new to the vocabulary.
+    #[pyo3(name = "add_emoji_tokens", signature = (tokens=None))]
+    pub fn py_add_emoji_tokens(&mut self, tokens: Option<Vec<String>>) -> usize {
+        match tokens {
+            Some(tokens) => {
+                let tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+                self.add_emoji_tokens(&tokens)
+            }
+            None => self.add_emoji_tokens(crate::COMMON_EMOJI),
+        }
+    }
+
+    /// Give each abbreviation a single token; `None` adds the common set.
+    /// Returns how many were new to the vocabulary.
+    #[pyo3(name = "add_abbreviations", signature = (abbreviations=None))]
+    pub fn py_add_abbreviations(&mut self, abbreviations: Option<Vec<String>>) -> usize {
+        match abbreviations {
+            Some(abbreviations) => {
+                let abbreviations: Vec<&str> = abbreviations.iter().map(String::as_str).collect();
+                self.add_abbreviations(&abbreviations)
+            }
+            None => self.add_abbreviations(crate::COMMON_ABBREVIATIONS),
+        }
+    }
+
+    /// Add special tokens from a dict with `bos_token`, `eos_token`,
+    /// `pad_token`, `sep_token`, `cls_token`, `mask_token` and `additional_special_tokens`
+    /// keys, assigning the named roles. Returns how many tokens were new to
+    /// the vocabulary.
+    #[pyo3(name = "add_special_tokens")]
+    pub fn py_add_special_tokens(&mut self, special_tokens_dict: HashMap<String, Bound<'_, PyAny>>) -> PyResult<usize> {
+        let mut added = 0;
+        for (key, value) in special_tokens_dict {
+            if key == "additional_special_tokens" {
+                let tokens: Vec<String> = value.extract()?;
+                let tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+                added += self.add_special_tokens(&tokens)?;
+                continue;
+            }
+            let token: String = value.extract()?;
+            let new = usize::from(!self.vocab.contains_key(&token));
+            self.assign_special_token(&key, &token)?;
+            self.add_special_tokens(&[&token])?;
+            added += new;
+        }
+        Ok(added)
+    }
+
+    /// Tokens added with `add_tokens` or `add_special_tokens` and their IDs
+    #[pyo3(name = "get_added_vocab")]
+    pub fn py_get_added_vocab(&self) -> HashMap<String, u32> {
+        self.get_added_vocab()
+    }
+
+    /// Dictionary form of a word's root, or `None` if no root matched
+    #[pyo3(name = "stem")]
+    pub fn py_stem(&self, word: &str) -> Option<String> {
+        self.stem(word)
+    }
+
+    /// Roots of every word of a text that has one
+    #[pyo3(name = "stem_text")]
+    pub fn py_stem_text(&self, py: Python<'_>, text: &str) -> Vec<String> {
+        py.detach(|| self.stem_text(text))
+    }
+
+    /// Text with ı, ğ, ü, ş, ö and ç restored where they were typed as
+    /// ASCII letters
+    #[pyo3(name = "deasciify")]
+    pub fn py_deasciify(&self, py: Python<'_>, text: &str) -> String {
+        py.detach(|| self.deasciify(text))
+    }
+
+    /// 1 for each ID that is a special token, 0 otherwise
+    #[pyo3(name = "get_special_tokens_mask")]
+    pub fn py_get_special_tokens_mask(&self, token_ids: Vec<u32>) -> Vec<u32> {
+        self.get_special_tokens_mask(&token_ids)
+    }
+
+    /// Decode token IDs back to text
+    #[pyo3(name = "decode", signature = (ids, skip_special_tokens = true))]
+    pub fn py_decode(&self, py: Python<'_>, ids: Vec<u32>, skip_special_tokens: bool) -> String {
+        py.detach(|| self.decode_with_options(&ids, skip_special_tokens))
+    }
+
+    /// Decode many ID sequences in parallel, releasing the GIL
+    #[pyo3(name = "batch_decode", signature = (sequences, skip_special_tokens = true))]
+    pub fn py_batch_decode(&self, py: Python<'_>, sequences: Vec<Vec<u32>>, skip_special_tokens: bool) -> Vec<String> {
+        py.detach(|| self.decode_batch(&sequences, skip_special_tokens))
+    }
+
+    /// Convert tokens to IDs, giving the `<unknown>` ID to tokens outside the vocabulary
+    #[pyo3(name = "convert_tokens_to_ids")]
+    pub fn py_convert_tokens_to_ids(&self, tokens: Vec<String>) -> Vec<u32> {
+        self.convert_tokens_to_ids(&tokens)
+    }
+
+    /// Convert IDs to token strings
+    #[pyo3(name = "convert_ids_to_tokens")]
+    pub fn py_convert_ids_to_tokens(&self, ids: Vec<u32>) -> Vec<String> {
+        self.convert_ids_to_tokens(&ids)
+    }
+
+    /// Get the token string for a specific ID
+    #[pyo3(name = "id_to_token")]
+    pub fn py_id_to_token(&self, id: u32) -> Option<&str> {
+        self.id_to_token(id)
+    }
+
+    /// Get token ID for a specific token
+    #[pyo3(name = "token_to_id")]
+    pub fn py_token_to_id(&self, token: &str) -> Option<u32> {
+        self.token_to_id(token)
+    }
+
+    /// Check if token exists in vocabulary
+    #[pyo3(name = "contains_token")]
+    pub fn py_contains_token(&self, token: &str) -> bool {
+        self.contains_token(token)
+    }
+
+    /// Get pad token
+    #[getter(pad_token)]
+    pub fn py_pad_token(&self) -> &str {
+        &self.pad_token
+    }
+
+    /// Get EOS token
+    #[getter(eos_token)]
+    pub fn py_eos_token(&self) -> &str {
+        &self.eos_token
+    }
+
+    /// Get BOS token
+    #[getter(bos_token)]
+    pub fn py_bos_token(&self) -> &str {
+        &self.bos_token
+    }
+
+    /// Get BOS token ID
+    #[getter(bos_token_id)]
+    pub fn py_bos_token_id(&self) -> u32 {
+        self.bos_token_id
+    }
+
+    /// Get separator token
+    #[getter(sep_token)]
+    pub fn py_sep_token(&self) -> &str {
+        &self.sep_token
+    }
+
+    /// Get separator token ID
+    #[getter(sep_token_id)]
+    pub fn py_sep_token_id(&self) -> u32 {
+        self.sep_token_id
+    }
+
+    /// Get CLS token
+    #[getter(cls_token)]
+    pub fn py_cls_token(&self) -> &str {
+        &self.cls_token
+    }
+
+    /// Get CLS token ID
+    #[getter(cls_token_id)]
+    pub fn py_cls_token_id(&self) -> u32 {
+        self.cls_token_id
+    }
+
+    /// Mask token string
+    #[getter(mask_token)]
+    pub fn py_mask_token(&self) -> &str {
+        &self.mask_token
+    }
+
+    /// Mask token ID
+    #[getter(mask_token_id)]
+    pub fn py_mask_token_id(&self) -> u32 {
+        self.mask_token_id
+    }
+
+    /// Use a different BOS token, claiming a reserved slot if needed
+    #[pyo3(name = "set_bos_token")]
+    pub fn py_set_bos_token(&mut self, token: &str) -> PyResult<u32> {
+        Ok(self.set_bos_token(token)?)
+    }
+
+    /// Whether BOS is prepended when special tokens are added
+    #[getter(add_bos_token)]
+    pub fn py_add_bos_token(&self) -> bool {
+        self.add_bos_token
+    }
+
+    #[setter(add_bos_token)]
+    pub fn py_set_add_bos_token(&mut self, enabled: bool) {
+        self.add_bos_token = enabled;
+    }
+
+    /// Whether EOS is appended when special tokens are added
+    #[getter(add_eos_token)]
+    pub fn py_add_eos_token(&self) -> bool {
+        self.add_eos_token
+    }
+
+    #[setter(add_eos_token)]
+    pub fn py_set_add_eos_token(&mut self, enabled: bool) {
+        self.add_eos_token = enabled;
+    }
+
+    /// Longest input the model accepts, or `None`
+    #[getter(model_max_length)]
+    pub fn py_model_max_length(&self) -> Option<usize> {
+        self.model_max_length
+    }
+
+    #[setter(model_max_length)]
+    pub fn py_set_model_max_length(&mut self, length: Option<usize>) {
+        self.model_max_length = length;
+    }
+
+    /// Get pad token ID
+    #[getter(pad_token_id)]
+    pub fn py_pad_token_id(&self) -> u32 {
+        self.pad_token_id
+    }
+
+    /// Get EOS token ID
+    #[getter(eos_token_id)]
+    pub fn py_eos_token_id(&self) -> u32 {
+        self.eos_token_id
+    }
+
+    /// Get the token unmatched text maps to when byte fallback is off
+    #[getter(unk_token)]
+    pub fn py_unk_token(&self) -> &str {
+        self.unk_token()
+    }
+
+    /// Get unknown token ID
+    #[getter(unk_token_id)]
+    pub fn py_unk_token_id(&self) -> u32 {
+        self.unk_token_id()
+    }
+
+    /// Vocabulary size, like `len(tokenizer)` in Hugging Face
+    pub fn __len__(&self) -> usize {
+        self.vocab_size()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "TurkishTokenizer(vocab_size={}, special_tokens={{'bos_token': '{}', 'eos_token': '{}', 'unk_token': '{}', \
+             'pad_token': '{}', 'sep_token': '{}', 'cls_token': '{}', 'mask_token': '{}'}})",
+            self.vocab_size(),
+            self.bos_token,
+            self.eos_token,
+            self.unknown_marker.token,
+            self.pad_token,
+            self.sep_token,
+            self.cls_token,
+            self.mask_token,
+        )
+    }
+
+    /// Whether unmatched characters become byte tokens instead of `<unknown>`
+    #[getter(byte_fallback)]
+    pub fn py_byte_fallback(&self) -> bool {
+        self.byte_fallback()
+    }
+
+    #[setter(byte_fallback)]
+    pub fn py_set_byte_fallback(&mut self, enabled: bool) {
+        self.set_byte_fallback(enabled);
+    }
+
+    /// Number of distinct words whose tokenization is cached (0 disables)
+    #[getter(word_cache_capacity)]
+    pub fn py_word_cache_capacity(&self) -> usize {
+        self.word_cache_capacity()
+    }
+
+    #[setter(word_cache_capacity)]
+    pub fn py_set_word_cache_capacity(&mut self, capacity: usize) {
+        self.set_word_cache_capacity(capacity);
+    }
+
+    /// Special tokens added around texts: `"bos_eos"`, `"cls_sep"`, or
+    /// `"template"` after `set_template`
+    #[getter(post_processor)]
+    pub fn py_post_processor(&self) -> &str {
+        self.post_processor.name()
+    }
+
+    #[setter(post_processor)]
+    pub fn py_set_post_processor(&mut self, value: &str) -> PyResult<()> {
+        Ok(self.set_post_processor(PostProcessor::parse(value)?)?)
+    }
+
+    /// Use custom special-token templates such as `"<cls> $A <sep>"` and
+    /// `"<cls> $A <sep> $B:1 <sep>:1"`
+    #[pyo3(name = "set_template")]
+    pub fn py_set_template(&mut self, single: &str, pair: &str) -> PyResult<()> {
+        let template = TemplateProcessing::new(single, pair)?;
+        Ok(self.set_post_processor(PostProcessor::Template(template))?)
+    }
+
+    /// Format conversations with `message`, containing `{content}` and
+    /// optionally `{role}`, opening replies with `generation_prompt`;
+    /// `special_tokens` are added to the vocabulary. Without arguments,
+    /// use ChatML.
+    #[pyo3(name = "set_chat_template", signature = (message = None, generation_prompt = "", special_tokens = Vec::new()))]
+    pub fn py_set_chat_template(
+        &mut self,
+        message: Option<&str>,
+        generation_prompt: &str,
+        special_tokens: Vec<String>,
+    ) -> PyResult<()> {
+        let template = match message {
+            None => ChatTemplate::chatml(),
+            Some(message) => {
+                let special_tokens: Vec<&str> = special_tokens.iter().map(String::as_str).collect();
+                ChatTemplate::new(message, generation_prompt, &special_tokens)?
+            }
+        };
+        Ok(self.set_chat_template(template)?)
+    }
+
+    /// The chat template as Jinja, as in Hugging Face, or `None`
+    #[getter(chat_template)]
+    pub fn py_chat_template(&self) -> Option<String> {
+        self.chat_template().map(ChatTemplate::to_jinja)
+    }
+
+    /// Format `conversation`, a list of `{"role": ..., "content": ...}`
+    /// dicts, with the chat template: token IDs, or the prompt text with
+    /// `tokenize=False`
+    #[pyo3(name = "apply_chat_template", signature = (conversation, add_generation_prompt = false, tokenize = true))]
+    pub fn py_apply_chat_template(
+        &self,
+        py: Python<'_>,
+        conversation: Vec<HashMap<String, String>>,
+        add_generation_prompt: bool,
+        tokenize: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let mut messages = Vec::with_capacity(conversation.len());
+        for mut message in conversation {
+            let mut field = |name: &str| {
+                message
+                    .remove(name)
+                    .ok_or_else(|| PyErr::new::<PyKeyError, _>(format!("Chat message without '{}'", name)))
+            };
+            messages.push(ChatMessage {
+                role: field("role")?,
+                content: field("content")?,
+            });
+        }
+        let prompt = self.render_chat(&messages, add_generation_prompt)?;
+        Ok(if tokenize {
+            py.detach(|| self.encode(&prompt)).into_py_any(py)?
+        } else {
+            prompt.into_py_any(py)?
+        })
+    }
+
+    /// Encode a question with overlapping windows of its context, as for
+    /// Hugging Face's QA preprocessing: a list per field with a row per
+    /// window, `offset_mapping` being None outside the context. With the
+    /// answer's context char span, `start_positions` and `end_positions`
+    /// give its token indices in each window, or 0 where it is not inside.
+    #[pyo3(name = "encode_question_context", signature = (question, context, max_length = 384, stride = 128, answer_start = None, answer_end = None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn py_encode_question_context(
+        &self,
+        py: Python<'_>,
+        question: &str,
+        context: &str,
+        max_length: usize,
+        stride: usize,
+        answer_start: Option<usize>,
+        answer_end: Option<usize>,
+    ) -> PyResult<HashMap<String, Py<PyAny>>> {
+        let windows = py.detach(|| self.encode_question_context(question, context, max_length, stride))?;
+        let mut result = HashMap::new();
+        let field = |f: fn(&EncodingResult) -> &Vec<u32>| windows.iter().map(|w| f(&w.encoding).clone()).collect::<Vec<_>>();
+        result.insert("input_ids".to_string(), field(|e| &e.input_ids).into_py_any(py)?);
+        result.insert("attention_mask".to_string(), field(|e| &e.attention_mask).into_py_any(py)?);
+        result.insert("token_type_ids".to_string(), field(|e| &e.token_type_ids).into_py_any(py)?);
+        let offsets: Vec<Vec<Option<(usize, usize)>>> = windows
+            .iter()
+            .map(|w| {
+                let offsets = w.encoding.offset_mapping.iter().enumerate();
+                offsets.map(|(i, &offset)| w.context_range.contains(&i).then_some(offset)).collect()
+            })
+            .collect();
+        result.insert("offset_mapping".to_string(), offsets.into_py_any(py)?);
+        if let (Some(start), Some(end)) = (answer_start, answer_end) {
+            let spans: Vec<(usize, usize)> =
+                windows.iter().map(|w| w.answer_token_span(start, end).unwrap_or((0, 0))).collect();
+            let starts: Vec<usize> = spans.iter().map(|span| span.0).collect();
+            let ends: Vec<usize> = spans.iter().map(|span| span.1).collect();
+            result.insert("start_positions".to_string(), starts.into_py_any(py)?);
+            result.insert("end_positions".to_string(), ends.into_py_any(py)?);
+        }
+        Ok(result)
+    }
+
+    /// Python-style call method for compatibility. `text` (and
+    /// `text_pair`) may be a string or a list of strings; lists are
+    /// encoded in parallel without the GIL and return a list per field.
+    /// `return_tensors="np"` returns 2-D int64 numpy arrays instead, with a
+    /// batch dimension of 1 for a single text. `return_attention_mask=False`
+    /// leaves out `attention_mask`; `return_length=True` adds `length`, the
+    /// number of IDs in each row. `max_length` defaults to
+    /// `model_max_length`. `truncation` is a bool or a strategy name
+    /// ("longest_first", "only_first", "only_second"); special tokens are
+    /// kept and pairs are cut as the strategy says.
+    #[pyo3(signature = (text, text_pair = None, max_length = None, padding = None, truncation = None, padding_side = "right", add_special_tokens = false, return_attention_mask = None, return_special_tokens_mask = false, return_offsets_mapping = false, return_length = false, return_tensors = None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn __call__(
+        &self,
+        py: Python<'_>,
+        text: &Bound<'_, PyAny>,
+        text_pair: Option<&Bound<'_, PyAny>>,
+        max_length: Option<usize>,
+        padding: Option<&Bound<'_, PyAny>>,
+        truncation: Option<&Bound<'_, PyAny>>,
+        padding_side: &str,
+        add_special_tokens: bool,
+        return_attention_mask: Option<bool>,
+        return_special_tokens_mask: bool,
+        return_offsets_mapping: bool,
+        return_length: bool,
+        return_tensors: Option<&str>,
+    ) -> PyResult<HashMap<String, Py<PyAny>>> {
+        let tensors = match return_tensors {
+            None => false,
+            Some("np") => {
+                // The numpy crate panics if numpy is missing; raise ImportError instead
+                py.import("numpy")?;
+                true
+            }
+            Some(other) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unsupported return_tensors: {} (only \"np\" is supported)",
+                    other
+                )))
+            }
+        };
+        let padding = match padding {
+            None => PaddingStrategy::DoNotPad,
+            Some(value) => match value.extract::<bool>() {
+                Ok(true) => PaddingStrategy::Longest,
+                Ok(false) => PaddingStrategy::DoNotPad,
+                Err(_) => PaddingStrategy::parse(&value.extract::<String>()?)?,
+            },
+        };
+        let truncation = match truncation {
+            None => None,
+            Some(value) => match value.extract::<bool>() {
+                Ok(true) => Some(TruncationStrategy::LongestFirst),
+                Ok(false) => None,
+                Err(_) => TruncationStrategy::parse(&value.extract::<String>()?)?,
+            },
+        };
+        let padding_side = PaddingSide::parse(padding_side)?;
+
+        let batched = !text.is_instance_of::<pyo3::types::PyString>();
+        let texts: Vec<String> = if batched { text.extract()? } else { vec![text.extract()?] };
+        let pairs: Option<Vec<String>> = match text_pair {
+            None => None,
+            Some(pair) if batched => Some(pair.extract()?),
+            Some(pair) => Some(vec![pair.extract()?]),
+        };
+        if pairs.as_ref().is_some_and(|pairs| pairs.len() != texts.len()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "text and text_pair must have the same length",
+            ));
+        }
+
+        let mut options = EncodeOptions::new()
+            .add_special_tokens(add_special_tokens)
+            .padding(padding)
+            .padding_side(padding_side);
+        if let Some(max_length) = max_length {
+            options = options.max_length(max_length);
+        }
+        if let Some(strategy) = truncation {
+            options = options.truncation(strategy);
+        }
+        let encodings = py.detach(|| {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            let pairs: Option<Vec<&str>> = pairs.as_ref().map(|pairs| pairs.iter().map(String::as_str).collect());
+            self.encode_batch_with_options(&texts, pairs.as_deref(), &options)
+        })?;
+
+        let width = encodings.first().map_or(0, |e| e.input_ids.len());
+        if tensors && encodings.iter().any(|e| e.input_ids.len() != width) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Sequences have different lengths; use padding=True or padding='max_length' with return_tensors",
+            ));
+        }
+
+        let mut fields: Vec<(&str, Vec<&[u32]>)> =
+            vec![("input_ids", encodings.iter().map(|e| e.input_ids.as_slice()).collect())];
+        if return_attention_mask.unwrap_or(true) {
+            fields.push(("attention_mask", encodings.iter().map(|e| e.attention_mask.as_slice()).collect()));
+        }
+        if pairs.is_some() {
+            fields.push(("token_type_ids", encodings.iter().map(|e| e.token_type_ids.as_slice()).collect()));
+        }
+        if return_special_tokens_mask {
+            fields.push(("special_tokens_mask", encodings.iter().map(|e| e.special_tokens_mask.as_slice()).collect()));
+        }
+
+        // A list per text, or the only list for a single text
+        let mut result = HashMap::new();
+        for (name, rows) in fields {
+            let value = if tensors {
+                let rows: Vec<Vec<i64>> = rows.iter().map(|row| row.iter().map(|&v| i64::from(v)).collect()).collect();
+                PyArray2::from_vec2(py, &rows)?.into_any().unbind()
+            } else if batched {
+                rows.into_py_any(py)?
+            } else {
+                rows[0].into_py_any(py)?
+            };
+            result.insert(name.to_string(), value);
+        }
+        if return_offsets_mapping {
+            let rows: Vec<&[(usize, usize)]> = encodings.iter().map(|e| e.offset_mapping.as_slice()).collect();
+            let value = if tensors {
+                let rows: Vec<Vec<Vec<i64>>> = rows
+                    .iter()
+                    .map(|row| row.iter().map(|&(start, end)| vec![start as i64, end as i64]).collect())
+                    .collect();
+                PyArray3::from_vec3(py, &rows)?.into_any().unbind()
+            } else if batched {
+                rows.into_py_any(py)?
+            } else {
+                rows[0].into_py_any(py)?
+            };
+            result.insert("offset_mapping".to_string(), value);
+        }
+        if return_length {
+            let lengths: Vec<usize> = encodings.iter().map(|e| e.input_ids.len()).collect();
+            let value = if tensors {
+                PyArray1::from_iter(py, lengths.iter().map(|&length| length as i64)).into_any().unbind()
+            } else if batched {
+                lengths.into_py_any(py)?
+            } else {
+                lengths[0].into_py_any(py)?
+            };
+            result.insert("length".to_string(), value);
+        }
+        Ok(result)
+    }
+}
+
+impl PaddingStrategy {
+    fn parse(value: &str) -> PyResult<Self> {
+        match value {
+            "do_not_pad" => Ok(PaddingStrategy::DoNotPad),
+            "longest" => Ok(PaddingStrategy::Longest),
+            "max_length" => Ok(PaddingStrategy::MaxLength),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown padding strategy: {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl PostProcessor {
+    fn name(&self) -> &'static str {
+        match self {
+            PostProcessor::BosEos => "bos_eos",
+            PostProcessor::ClsSep => "cls_sep",
+            PostProcessor::Template(_) => "template",
+        }
+    }
+
+    fn parse(value: &str) -> PyResult<Self> {
+        match value {
+            "bos_eos" => Ok(PostProcessor::BosEos),
+            "cls_sep" => Ok(PostProcessor::ClsSep),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown post-processor: {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl TruncationStrategy {
+    /// The strategy named by Hugging Face's `truncation` argument, or None
+    /// for "do_not_truncate"
+    fn parse(value: &str) -> PyResult<Option<Self>> {
+        match value {
+            "longest_first" => Ok(Some(TruncationStrategy::LongestFirst)),
+            "only_first" => Ok(Some(TruncationStrategy::OnlyFirst)),
+            "only_second" => Ok(Some(TruncationStrategy::OnlySecond)),
+            "do_not_truncate" => Ok(None),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown truncation strategy: {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl PaddingSide {
+    fn parse(value: &str) -> PyResult<Self> {
+        match value {
+            "left" => Ok(PaddingSide::Left),
+            "right" => Ok(PaddingSide::Right),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown padding side: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Encode many texts with the default tokenizer on `num_threads` threads
+/// (all cores when None), releasing the GIL for the whole call
+#[pyfunction]
+#[pyo3(signature = (texts, num_threads = None))]
+fn tokenize_corpus(py: Python<'_>, texts: Vec<String>, num_threads: Option<usize>) -> PyResult<Vec<Vec<u32>>> {
+    let tokenizer = TurkishTokenizer::global();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads.unwrap_or(0))
+        .build()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    Ok(py.detach(|| {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        pool.install(|| tokenizer.encode_batch(&texts))
+    }))
+}
+
+/// Python module definition
+#[pymodule]
+fn turkish_tokenizer_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<TurkishTokenizer>()?;
+    m.add_class::<Token>()?;
+    m.add_class::<TokenType>()?;
+    m.add_class::<MorphAnalysis>()?;
+    m.add_class::<SuffixInfo>()?;
+    m.add_class::<SuffixCategory>()?;
+    m.add_class::<PartOfSpeech>()?;
+    m.add_class::<VocabIndex>()?;
+    m.add_function(wrap_pyfunction!(tokenize_corpus, m)?)?;
+    Ok(())
+}
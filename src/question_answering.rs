@@ -0,0 +1,124 @@
+//! Question/context encoding for extractive question answering.
+//!
+//! A context longer than the model's input is split into overlapping
+//! windows, each encoded with the whole question as a pair, as Hugging
+//! Face's QA pipelines do with `truncation="only_second"` and
+//! `return_overflowing_tokens`. The answer is labelled by the token indices
+//! its characters fall on in each window.
+
+use std::ops::Range;
+
+use crate::template::TemplatePiece;
+use crate::{EncodingResult, TurkishTokenizer, TurkishTokenizerError};
+
+/// One window of a question and context pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QaEncoding {
+    /// The question and this part of the context with the post-processor's
+    /// pair template. Context offsets are chars of the whole context.
+    pub encoding: EncodingResult,
+    /// Indices of the context tokens in `encoding`
+    pub context_range: Range<usize>,
+}
+
+impl QaEncoding {
+    /// First and last token index (inclusive) of the context chars
+    /// `start..end`, or `None` if the span is empty or not wholly inside
+    /// this window; Hugging Face then labels the answer with the `<cls>`
+    /// index. A case marker before the first letter belongs to the span.
+    pub fn answer_token_span(&self, start: usize, end: usize) -> Option<(usize, usize)> {
+        let offsets = &self.encoding.offset_mapping[self.context_range.clone()];
+        let (first, last) = (offsets.first()?, offsets.last()?);
+        if start >= end || first.0 > start || last.1 < end {
+            return None;
+        }
+        let token_start = offsets
+            .iter()
+            .position(|&(token_start, token_end)| token_end > start || (token_start, token_end) == (start, start))?;
+        let token_end = offsets.iter().rposition(|&(token_start, _)| token_start < end)?;
+        Some((self.context_range.start + token_start, self.context_range.start + token_end))
+    }
+
+    /// Context char span of the tokens `start..=end`, for turning predicted
+    /// token indices back into answer text. `None` unless both are context
+    /// tokens in order.
+    pub fn token_span_to_chars(&self, start: usize, end: usize) -> Option<(usize, usize)> {
+        if start > end || !self.context_range.contains(&start) || !self.context_range.contains(&end) {
+            return None;
+        }
+        Some((self.encoding.offset_mapping[start].0, self.encoding.offset_mapping[end].1))
+    }
+}
+
+impl TurkishTokenizer {
+    /// Encode `question` with windows of `context` so each pair fits in
+    /// `max_length` tokens, consecutive windows sharing `stride` context
+    /// tokens. The question is never truncated. Fails if `max_length`
+    /// leaves no room for the context after the question and special
+    /// tokens, or `stride` is not smaller than that room.
+    ///
+    /// ```
+    /// use turkish_tokenizer::TurkishTokenizer;
+    ///
+    /// let tokenizer = TurkishTokenizer::new().unwrap();
+    /// let context = "Türkiye'nin başkenti Ankara'dır. En kalabalık şehri İstanbul'dur.";
+    /// let windows = tokenizer.encode_question_context("Başkent neresi?", context, 16, 4).unwrap();
+    /// let start = context.chars().position(|c| c == 'A').unwrap();
+    /// let (window, (first, last)) = windows
+    ///     .iter()
+    ///     .find_map(|window| Some((window, window.answer_token_span(start, start + 6)?)))
+    ///     .unwrap();
+    /// assert_eq!(tokenizer.decode(&window.encoding.input_ids[first..=last]), "Ankara");
+    /// ```
+    pub fn encode_question_context(
+        &self,
+        question: &str,
+        context: &str,
+        max_length: usize,
+        stride: usize,
+    ) -> Result<Vec<QaEncoding>, TurkishTokenizerError> {
+        let question = self.encode_plus(question);
+        let special_count = self.num_special_tokens_to_add(true);
+        // Tokens before $B
+        let context_start = self
+            .template_pieces(true)
+            .iter()
+            .take_while(|piece| !matches!(piece, TemplatePiece::Sequence { first: false, .. }))
+            .map(|piece| match piece {
+                TemplatePiece::Special { .. } => 1,
+                TemplatePiece::Sequence { .. } => question.input_ids.len(),
+            })
+            .sum();
+        let room = max_length.saturating_sub(question.input_ids.len() + special_count);
+        if room == 0 {
+            return Err(TurkishTokenizerError::InvalidConfig(format!(
+                "max_length {} leaves no room for the context after {} question and special tokens",
+                max_length,
+                question.input_ids.len() + special_count
+            )));
+        }
+        if stride >= room {
+            return Err(TurkishTokenizerError::InvalidConfig(format!(
+                "stride {} must be smaller than the {} context tokens each window holds",
+                stride, room
+            )));
+        }
+
+        let full = self.encode_plus(context);
+        let len = full.input_ids.len();
+        let mut windows = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + room).min(len);
+            let part = full.window(start..end);
+            windows.push(QaEncoding {
+                encoding: self.post_process(&question, Some(&part)),
+                context_range: context_start..context_start + part.input_ids.len(),
+            });
+            if end == len {
+                return Ok(windows);
+            }
+            start = end - stride;
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Stochastic segmentation in the style of BPE-dropout.
+//!
+//! Every multi-character vocabulary match is skipped with probability
+//! `alpha`, so the next shorter candidate (or the next table) is used
+//! instead. Single characters are never dropped, so words break into
+//! smaller pieces rather than byte tokens. The same seed always yields the
+//! same segmentation.
+
+use crate::{TextPiece, TurkishTokenizer};
+
+/// Seeded source of dropout decisions (SplitMix64)
+pub(crate) struct DropoutSampler {
+    state: u64,
+    alpha: f64,
+}
+
+impl DropoutSampler {
+    fn new(alpha: f64, seed: u64) -> Self {
+        DropoutSampler { state: seed, alpha }
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Whether a match of `len` chars survives dropout; always true without a
+/// sampler
+pub(crate) fn keeps_match(sampler: &mut Option<&mut DropoutSampler>, len: usize) -> bool {
+    match sampler {
+        Some(sampler) if len > 1 => sampler.next_f64() >= sampler.alpha,
+        _ => true,
+    }
+}
+
+impl TurkishTokenizer {
+    /// Encode with randomly perturbed segmentation for subword
+    /// regularization: each multi-character match is dropped with
+    /// probability `alpha` (`0.0` gives `encode`). Calls with the same
+    /// `seed` produce the same IDs.
+    pub fn encode_with_sampling(&self, text: &str, alpha: f64, seed: u64) -> Vec<u32> {
+        let mut sampler = DropoutSampler::new(alpha, seed);
+        let mut ids = Vec::with_capacity(text.len() / 2);
+        let mut word = Vec::new();
+        let mut lowered = Vec::new();
+        for piece in self.split_text(text) {
+            match piece {
+                TextPiece::Whitespace { marker, .. } => ids.push(marker.id),
+                TextPiece::Added { added, .. } => ids.push(added.token.id),
+                TextPiece::Word(part, _) => {
+                    word.clear();
+                    word.extend(part.chars());
+                    self.segment_word(&word, self.unknown_strategy, Some(&mut sampler), &mut lowered, &mut |piece| {
+                        ids.push(self.piece_id(&piece))
+                    });
+                }
+            }
+        }
+        ids
+    }
+}
@@ -0,0 +1,134 @@
+//! Invisible and control characters in scraped text.
+//!
+//! Web text carries zero-width spaces, byte order marks, bidi marks and
+//! stray control characters that are not in the vocabulary, so they come
+//! out as byte tokens and split the words around them. Stripping removes
+//! them from words before segmentation ("mer\u{200b}haba" is segmented as
+//! "merhaba"); normalizing also makes control characters and non-breaking
+//! or other Unicode spaces separate words like a space. Token offsets
+//! still point into the original text, as with line-break joining.
+
+use serde::{Deserialize, Serialize};
+
+use crate::hyphenation::{line_break_len, SOFT_HYPHEN};
+use crate::{Token, TurkishTokenizer};
+
+/// What happens to invisible and control characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlCharMode {
+    /// They are tokenized like any other character
+    #[default]
+    Keep,
+    /// Zero-width characters, byte order marks, bidi marks and control
+    /// characters other than tabs and newlines are dropped from words
+    Strip,
+    /// As `Strip`, and control characters and Unicode spaces other than
+    /// tabs and newlines separate words, emitted as a space
+    Normalize,
+}
+
+const ZERO_WIDTH_JOINER: char = '\u{200d}';
+
+/// Whether `c` is a zero-width, byte order or bidi mark
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        SOFT_HYPHEN
+            | '\u{61c}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{feff}'
+    )
+}
+
+/// Whether `c` is a control character other than a tab or line break
+fn is_stray_control(c: char) -> bool {
+    c.is_control() && !matches!(c, '\t' | '\n' | '\r')
+}
+
+/// Move `tokens` of a cleaned word, which starts at char offset `start`,
+/// back onto `word` using the `origins` of `cleaned_word`
+pub(crate) fn restore_offsets(tokens: &mut [Token], start: usize, word: &[char], origins: &[usize]) {
+    for token in tokens {
+        let (from, to) = (token.start - start, token.end - start);
+        let end = if to > from { origins[to - 1] + 1 } else { origins[from] };
+        token.start = start + origins[from];
+        token.end = start + end;
+        if !token.surface.is_empty() {
+            token.surface = word[origins[from]..end].iter().collect();
+        }
+    }
+}
+
+impl TurkishTokenizer {
+    pub fn control_chars(&self) -> ControlCharMode {
+        self.control_chars
+    }
+
+    /// Change what happens to zero-width, bidi and control characters
+    pub fn set_control_chars(&mut self, mode: ControlCharMode) {
+        self.control_chars = mode;
+        self.word_cache.clear();
+    }
+
+    /// Whether `c` separates words as a space under `ControlCharMode::Normalize`
+    pub(crate) fn is_mapped_space(&self, c: char) -> bool {
+        self.control_chars == ControlCharMode::Normalize
+            && (c.is_whitespace() || is_stray_control(c))
+            && !matches!(c, ' ' | '\t' | '\n' | '\r')
+    }
+
+    /// Whether `word[i]` is dropped from the word. A zero-width joiner
+    /// between symbols is kept, as it belongs to an emoji sequence.
+    fn strips(&self, word: &[char], i: usize) -> bool {
+        let c = word[i];
+        if self.control_chars == ControlCharMode::Keep || !(is_invisible(c) || is_stray_control(c)) {
+            return false;
+        }
+        let symbol = |c: Option<&char>| {
+            c.is_some_and(|&c| !c.is_alphanumeric() && !c.is_ascii() && !c.is_whitespace() && !is_invisible(c))
+        };
+        c != ZERO_WIDTH_JOINER || !(i > 0 && symbol(word.get(i - 1)) && symbol(word.get(i + 1)))
+    }
+
+    /// Whether nothing of `word` is left to tokenize once it is cleaned
+    pub(crate) fn is_blank(&self, word: &str) -> bool {
+        let strip = self.control_chars != ControlCharMode::Keep;
+        word.chars().all(|c| c.is_whitespace() || strip && (is_invisible(c) || is_stray_control(c)))
+    }
+
+    /// `word` without its stripped characters and joined line breaks, and
+    /// for each remaining char its index in `word`, followed by
+    /// `word.len()`; `None` if nothing is removed
+    pub(crate) fn cleaned_word(&self, word: &[char]) -> Option<(Vec<char>, Vec<usize>)> {
+        let strip = self.control_chars != ControlCharMode::Keep;
+        let removable = |c: char| {
+            strip && (is_invisible(c) || is_stray_control(c)) || self.join_line_breaks && matches!(c, '-' | SOFT_HYPHEN)
+        };
+        if !word.iter().any(|&c| removable(c)) {
+            return None;
+        }
+        let mut cleaned = Vec::with_capacity(word.len());
+        let mut origins = Vec::with_capacity(word.len() + 1);
+        let mut i = 0;
+        while i < word.len() {
+            if let Some(len) = self.join_line_breaks.then(|| line_break_len(word, i)).flatten() {
+                i += len;
+                continue;
+            }
+            if !self.strips(word, i) {
+                cleaned.push(word[i]);
+                origins.push(i);
+            }
+            i += 1;
+        }
+        if cleaned.len() == word.len() {
+            return None;
+        }
+        origins.push(word.len());
+        Some((cleaned, origins))
+    }
+}
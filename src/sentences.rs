@@ -0,0 +1,135 @@
+//! Rule-based Turkish sentence boundary detection.
+//!
+//! A sentence ends at `.`, `!`, `?` or `…` (possibly repeated and followed
+//! by closing quotes or brackets) when whitespace or the end of the text
+//! follows and the next word does not start with a lowercase letter. A
+//! period does not end a sentence after a known abbreviation ("Dr.",
+//! "vb."), a dotted abbreviation ("T.C."), a single-letter initial ("A.")
+//! or a number, which Turkish writes as an ordinal ("3. sınıf"). A blank
+//! line always ends a sentence.
+
+use crate::{Token, TurkishTokenizer, COMMON_ABBREVIATIONS};
+
+const TERMINATORS: &[char] = &['.', '!', '?', '…'];
+const CLOSING: &[char] = &['"', '\'', '”', '’', '»', ')', ']'];
+pub(crate) const OPENING: &[char] = &['"', '\'', '“', '‘', '«', '(', '['];
+
+/// Byte spans of the sentences in `text`, without surrounding whitespace
+pub fn sentence_spans(text: &str) -> Vec<(usize, usize)> {
+    spans_with(text, &|_| false)
+}
+
+/// `sentence_spans`, also treating a period as non-final after a word for
+/// which `is_abbreviation` holds, with the period
+fn spans_with(text: &str, is_abbreviation: &dyn Fn(&str) -> bool) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte_at = |i: usize| chars.get(i).map_or(text.len(), |&(b, _)| b);
+
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        if c == '\n' && is_blank_line_after(&chars, i) {
+            push_trimmed(text, start, byte_at(i), &mut spans);
+            start = byte_at(i);
+            i += 1;
+            continue;
+        }
+        if !TERMINATORS.contains(&c) {
+            i += 1;
+            continue;
+        }
+
+        let mut end = i + 1;
+        while end < chars.len() && (TERMINATORS.contains(&chars[end].1) || CLOSING.contains(&chars[end].1)) {
+            end += 1;
+        }
+        let followed_by_space = end == chars.len() || chars[end].1.is_whitespace();
+        if followed_by_space && !next_word_is_lowercase(&chars, end) && !(c == '.' && ends_with_non_final_period(&chars, i, is_abbreviation)) {
+            push_trimmed(text, start, byte_at(end), &mut spans);
+            start = byte_at(end);
+        }
+        i = end;
+    }
+    push_trimmed(text, start, text.len(), &mut spans);
+    spans
+}
+
+/// The sentences in `text`, without surrounding whitespace
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    sentence_spans(text).into_iter().map(|(start, end)| &text[start..end]).collect()
+}
+
+fn push_trimmed(text: &str, start: usize, end: usize, spans: &mut Vec<(usize, usize)>) {
+    let sentence = &text[start..end];
+    let trimmed = sentence.trim();
+    if !trimmed.is_empty() {
+        let offset = start + (sentence.len() - sentence.trim_start().len());
+        spans.push((offset, offset + trimmed.len()));
+    }
+}
+
+/// Whether the newline at `i` is followed by another after only whitespace
+fn is_blank_line_after(chars: &[(usize, char)], i: usize) -> bool {
+    chars[i + 1..]
+        .iter()
+        .map(|&(_, c)| c)
+        .take_while(|c| c.is_whitespace())
+        .any(|c| c == '\n')
+}
+
+fn next_word_is_lowercase(chars: &[(usize, char)], from: usize) -> bool {
+    chars[from..]
+        .iter()
+        .map(|&(_, c)| c)
+        .find(|c| !c.is_whitespace() && !OPENING.contains(c))
+        .is_some_and(|c| c.is_lowercase())
+}
+
+/// Whether the period at `i` closes an abbreviation, initial or ordinal
+fn ends_with_non_final_period(chars: &[(usize, char)], i: usize, is_abbreviation: &dyn Fn(&str) -> bool) -> bool {
+    let word_start = chars[..i]
+        .iter()
+        .rposition(|&(_, c)| c.is_whitespace())
+        .map_or(0, |p| p + 1);
+    let word: String = chars[word_start..i]
+        .iter()
+        .map(|&(_, c)| c)
+        .skip_while(|c| OPENING.contains(c))
+        .collect();
+    if word.is_empty() {
+        return false;
+    }
+
+    let lower = word.replace('İ', "i").replace('I', "ı").to_lowercase();
+    COMMON_ABBREVIATIONS.iter().any(|abbreviation| abbreviation.strip_suffix('.') == Some(lower.as_str()))
+        || is_abbreviation(&format!("{word}."))
+        || word.contains('.')
+        || word.chars().count() == 1 && word.chars().all(char::is_alphabetic)
+        || word.chars().all(|c| c.is_ascii_digit())
+}
+
+impl TurkishTokenizer {
+    /// Tokenize each sentence of `text` separately. Offsets are relative to
+    /// the whole text; word IDs restart in every sentence. A period after
+    /// an entry of the abbreviation table does not end a sentence.
+    pub fn tokenize_sentences(&self, text: &str) -> Vec<Vec<Token>> {
+        let mut char_offset = 0;
+        let mut byte_offset = 0;
+        spans_with(text, &|word| self.is_abbreviation(word))
+            .into_iter()
+            .map(|(start, end)| {
+                char_offset += text[byte_offset..start].chars().count();
+                byte_offset = start;
+                self.tokenize_iter(&text[start..end])
+                    .map(|t| Token {
+                        start: char_offset + t.start,
+                        end: char_offset + t.end,
+                        ..t
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
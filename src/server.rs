@@ -0,0 +1,327 @@
+//! HTTP tokenization service, built with the `serve` feature and run by the
+//! `serve` binary.
+//!
+//! Every endpoint takes and returns JSON. A field holding one input gets
+//! one result back; a list of inputs is handled as a batch and gets a list:
+//!
+//! - `POST /encode` `{"text": "..." | [...], "add_special_tokens": false}`
+//!   returns `{"ids": [...] | [[...], ...]}`
+//! - `POST /tokenize` `{"text": "..." | [...]}` returns
+//!   `{"tokens": [...] | [[...], ...]}` with offsets and token types
+//! - `POST /decode` `{"ids": [...] | [[...], ...], "skip_special_tokens": false}`
+//!   returns `{"text": "..." | [...]}`
+//! - `GET /healthz` returns `ok`
+//! - `GET /metrics` returns request counts, latency and batch size
+//!   histograms, and token and unknown-token (`<unknown>` or byte
+//!   fallback) counts per endpoint in the Prometheus text format; tokens
+//!   per second is the rate of `turkish_tokenizer_tokens_total`
+//!
+//! All requests share one tokenizer. Tokenization runs on tokio's blocking
+//! pool, and batches are spread over rayon's threads.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::task::JoinError;
+
+use crate::{Token, TokenType, TurkishTokenizer};
+
+/// A single input or output, or a batch of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Batchable<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T: Sync> Batchable<T> {
+    /// Apply `f` to the input, or to every input of a batch in parallel
+    pub fn map<U: Send>(&self, f: impl Fn(&T) -> U + Send + Sync) -> Batchable<U> {
+        match self {
+            Batchable::One(item) => Batchable::One(f(item)),
+            Batchable::Many(items) => Batchable::Many(items.par_iter().map(f).collect()),
+        }
+    }
+}
+
+impl<T> Batchable<T> {
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            Batchable::One(item) => std::slice::from_ref(item),
+            Batchable::Many(items) => items,
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            Batchable::One(item) => vec![item],
+            Batchable::Many(items) => items,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EncodeRequest {
+    pub text: Batchable<String>,
+    #[serde(default)]
+    pub add_special_tokens: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodeResponse {
+    pub ids: Batchable<Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenizeRequest {
+    pub text: Batchable<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenizeResponse {
+    pub tokens: Batchable<Vec<Token>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DecodeRequest {
+    pub ids: Batchable<Vec<u32>>,
+    #[serde(default)]
+    pub skip_special_tokens: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodeResponse {
+    pub text: Batchable<String>,
+}
+
+pub fn encode(tokenizer: &TurkishTokenizer, request: &EncodeRequest) -> EncodeResponse {
+    EncodeResponse {
+        ids: request.text.map(|text| tokenizer.encode_with(text, request.add_special_tokens)),
+    }
+}
+
+pub fn tokenize(tokenizer: &TurkishTokenizer, request: &TokenizeRequest) -> TokenizeResponse {
+    TokenizeResponse {
+        tokens: request.text.map(|text| tokenizer.tokenize_text(text)),
+    }
+}
+
+pub fn decode(tokenizer: &TurkishTokenizer, request: &DecodeRequest) -> DecodeResponse {
+    DecodeResponse {
+        text: request.ids.map(|ids| tokenizer.decode_with_options(ids, request.skip_special_tokens)),
+    }
+}
+
+/// Upper bounds of the request latency histogram, in seconds
+const LATENCY_BUCKETS: [f64; 12] = [0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+/// Upper bounds of the batch size histogram, in inputs per request
+const BATCH_SIZE_BUCKETS: [f64; 9] = [1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0];
+
+/// Requests recorded by every HTTP and gRPC service in the process, by
+/// endpoint
+static METRICS: Mutex<BTreeMap<&'static str, EndpointMetrics>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug)]
+struct Histogram<const N: usize> {
+    /// Observations at or below each bucket's bound
+    buckets: [u64; N],
+    sum: f64,
+    count: u64,
+}
+
+impl<const N: usize> Histogram<N> {
+    fn new() -> Self {
+        Histogram {
+            buckets: [0; N],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, bounds: &[f64; N], value: f64) {
+        for (bucket, &bound) in self.buckets.iter_mut().zip(bounds) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, endpoint: &str, bounds: &[f64; N]) {
+        for (bucket, bound) in self.buckets.iter().zip(bounds) {
+            out.push_str(&format!("{}_bucket{{endpoint=\"{}\",le=\"{}\"}} {}\n", name, endpoint, bound, bucket));
+        }
+        out.push_str(&format!("{}_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}\n", name, endpoint, self.count));
+        out.push_str(&format!("{}_sum{{endpoint=\"{}\"}} {}\n", name, endpoint, self.sum));
+        out.push_str(&format!("{}_count{{endpoint=\"{}\"}} {}\n", name, endpoint, self.count));
+    }
+}
+
+#[derive(Debug)]
+struct EndpointMetrics {
+    requests: u64,
+    latency: Histogram<12>,
+    batch_size: Histogram<9>,
+    tokens: u64,
+    unknown_tokens: u64,
+}
+
+/// Record a request to `endpoint` that started at `started` with
+/// `batch_size` inputs, and the token IDs it encoded or decoded. `<unknown>`
+/// and byte-fallback tokens both count as unknown, since text outside the
+/// vocabulary becomes bytes unless byte fallback is off.
+pub(crate) fn record_request<'a>(
+    endpoint: &'static str,
+    tokenizer: &TurkishTokenizer,
+    started: Instant,
+    batch_size: usize,
+    ids: impl IntoIterator<Item = &'a u32>,
+) {
+    let latency = started.elapsed().as_secs_f64();
+    let (mut tokens, mut unknown_tokens) = (0, 0);
+    for &id in ids {
+        tokens += 1;
+        let unknown = id == tokenizer.unk_token_id() || tokenizer.token_type_of_id(id) == Some(TokenType::Byte);
+        unknown_tokens += u64::from(unknown);
+    }
+    // A panic while holding the lock cannot leave the counts inconsistent
+    let mut metrics = METRICS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let metrics = metrics.entry(endpoint).or_insert_with(|| EndpointMetrics {
+        requests: 0,
+        latency: Histogram::new(),
+        batch_size: Histogram::new(),
+        tokens: 0,
+        unknown_tokens: 0,
+    });
+    metrics.requests += 1;
+    metrics.latency.observe(&LATENCY_BUCKETS, latency);
+    metrics.batch_size.observe(&BATCH_SIZE_BUCKETS, batch_size as f64);
+    metrics.tokens += tokens;
+    metrics.unknown_tokens += unknown_tokens;
+}
+
+/// The requests recorded so far in the Prometheus text format
+pub fn render_metrics() -> String {
+    let metrics = METRICS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut out = String::new();
+    render_counter(&mut out, &metrics, "turkish_tokenizer_requests_total", "Requests handled", |m| m.requests);
+    render_counter(&mut out, &metrics, "turkish_tokenizer_tokens_total", "Token IDs encoded or decoded", |m| m.tokens);
+    render_counter(
+        &mut out,
+        &metrics,
+        "turkish_tokenizer_unknown_tokens_total",
+        "Unknown and byte-fallback tokens encoded or decoded",
+        |m| m.unknown_tokens,
+    );
+
+    let name = "turkish_tokenizer_request_duration_seconds";
+    out.push_str(&format!("# HELP {} Time spent tokenizing a request\n# TYPE {} histogram\n", name, name));
+    for (endpoint, endpoint_metrics) in metrics.iter() {
+        endpoint_metrics.latency.render(&mut out, name, endpoint, &LATENCY_BUCKETS);
+    }
+    let name = "turkish_tokenizer_batch_size";
+    out.push_str(&format!("# HELP {} Inputs per request\n# TYPE {} histogram\n", name, name));
+    for (endpoint, endpoint_metrics) in metrics.iter() {
+        endpoint_metrics.batch_size.render(&mut out, name, endpoint, &BATCH_SIZE_BUCKETS);
+    }
+    out
+}
+
+fn render_counter(
+    out: &mut String,
+    metrics: &BTreeMap<&'static str, EndpointMetrics>,
+    name: &str,
+    help: &str,
+    value: fn(&EndpointMetrics) -> u64,
+) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} counter\n", name, help, name));
+    for (endpoint, endpoint_metrics) in metrics {
+        out.push_str(&format!("{}{{endpoint=\"{}\"}} {}\n", name, endpoint, value(endpoint_metrics)));
+    }
+}
+
+/// Run `f` on the blocking pool so long batches do not stall the runtime
+pub(crate) async fn run_blocking<R: Send + 'static>(
+    tokenizer: Arc<TurkishTokenizer>,
+    f: impl FnOnce(&TurkishTokenizer) -> R + Send + 'static,
+) -> Result<R, JoinError> {
+    tokio::task::spawn_blocking(move || f(&tokenizer)).await
+}
+
+fn json<R>(result: Result<R, JoinError>) -> Result<Json<R>, StatusCode> {
+    result.map(Json).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn encode_handler(
+    State(tokenizer): State<Arc<TurkishTokenizer>>,
+    Json(request): Json<EncodeRequest>,
+) -> Result<Json<EncodeResponse>, StatusCode> {
+    json(
+        run_blocking(tokenizer, move |tokenizer| {
+            let started = Instant::now();
+            let response = encode(tokenizer, &request);
+            let ids = response.ids.as_slice();
+            record_request("/encode", tokenizer, started, ids.len(), ids.iter().flatten());
+            response
+        })
+        .await,
+    )
+}
+
+async fn tokenize_handler(
+    State(tokenizer): State<Arc<TurkishTokenizer>>,
+    Json(request): Json<TokenizeRequest>,
+) -> Result<Json<TokenizeResponse>, StatusCode> {
+    json(
+        run_blocking(tokenizer, move |tokenizer| {
+            let started = Instant::now();
+            let response = tokenize(tokenizer, &request);
+            let tokens = response.tokens.as_slice();
+            let ids = tokens.iter().flatten().map(|token| &token.id);
+            record_request("/tokenize", tokenizer, started, tokens.len(), ids);
+            response
+        })
+        .await,
+    )
+}
+
+async fn decode_handler(
+    State(tokenizer): State<Arc<TurkishTokenizer>>,
+    Json(request): Json<DecodeRequest>,
+) -> Result<Json<DecodeResponse>, StatusCode> {
+    json(
+        run_blocking(tokenizer, move |tokenizer| {
+            let started = Instant::now();
+            let response = decode(tokenizer, &request);
+            let ids = request.ids.as_slice();
+            record_request("/decode", tokenizer, started, ids.len(), ids.iter().flatten());
+            response
+        })
+        .await,
+    )
+}
+
+/// Routes for the endpoints above, sharing `tokenizer`
+pub fn router(tokenizer: Arc<TurkishTokenizer>) -> Router {
+    Router::new()
+        .route("/encode", post(encode_handler))
+        .route("/tokenize", post(tokenize_handler))
+        .route("/decode", post(decode_handler))
+        .route("/healthz", get(|| async { "ok" }))
+        .route("/metrics", get(|| async { render_metrics() }))
+        .with_state(tokenizer)
+}
+
+/// Serve the endpoints on `listener` until the process stops
+pub async fn serve(listener: TcpListener, tokenizer: Arc<TurkishTokenizer>) -> std::io::Result<()> {
+    axum::serve(listener, router(tokenizer)).await
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+use crate::TurkishTokenizer;
+
+/// IDs decoded around a stop string beyond its own tokens, so suffixes
+/// harmonize with their stem and markers reach the token they apply to
+const CONTEXT_TOKENS: usize = 8;
+
+/// Reports when generated IDs complete a stop string. Created by
+/// [`TurkishTokenizer::stop_criteria`].
+///
+/// Each stop string is encoded to an ID pattern, which matches when the
+/// generated IDs end with it. A model can also spell a stop string with
+/// other tokens, or end it inside a token ("?\n\n" for "\n\n"), so the last
+/// IDs are decoded as well and the text searched for a match ending in the
+/// newest token.
+///
+/// ```
+/// use turkish_tokenizer::TurkishTokenizer;
+///
+/// let tokenizer = TurkishTokenizer::new().unwrap();
+/// let mut stop = tokenizer.stop_criteria(&["Kullanıcı:"]);
+/// let ids = tokenizer.encode("Tamam. Kullanıcı: devam");
+/// let halted_at = ids.iter().position(|&id| stop.push(id)).unwrap();
+/// assert_eq!(tokenizer.decode(&ids[..=halted_at]), "Tamam. Kullanıcı:");
+/// assert_eq!(stop.matched(), Some("Kullanıcı:"));
+/// ```
+pub struct StopCriteria<'a> {
+    tokenizer: &'a TurkishTokenizer,
+    stop_strings: Vec<String>,
+    patterns: Vec<Vec<u32>>,
+    /// The last generated IDs, as many as text matching decodes
+    recent: VecDeque<u32>,
+    window: usize,
+    /// Index of the stop string found
+    matched: Option<usize>,
+}
+
+impl<'a> StopCriteria<'a> {
+    pub(crate) fn new(tokenizer: &'a TurkishTokenizer, stop_strings: &[&str]) -> Self {
+        let stop_strings: Vec<String> =
+            stop_strings.iter().filter(|stop| !stop.is_empty()).map(|stop| stop.to_string()).collect();
+        let patterns: Vec<Vec<u32>> = stop_strings.iter().map(|stop| tokenizer.encode(stop)).collect();
+        let longest = patterns.iter().map(Vec::len).max().unwrap_or(0);
+        let window = longest + CONTEXT_TOKENS;
+        StopCriteria {
+            tokenizer,
+            stop_strings,
+            patterns,
+            recent: VecDeque::with_capacity(window + 1),
+            window,
+            matched: None,
+        }
+    }
+
+    /// ID pattern of each stop string, in the order given
+    pub fn patterns(&self) -> &[Vec<u32>] {
+        &self.patterns
+    }
+
+    /// Add the next generated ID. Returns whether generation should stop,
+    /// which stays true once a stop string is found.
+    pub fn push(&mut self, id: u32) -> bool {
+        if self.matched.is_some() {
+            return true;
+        }
+        self.recent.push_back(id);
+        if self.recent.len() > self.window {
+            self.recent.pop_front();
+        }
+        self.matched = self.pattern_match().or_else(|| self.text_match());
+        self.matched.is_some()
+    }
+
+    /// The stop string found, if any
+    pub fn matched(&self) -> Option<&str> {
+        self.matched.map(|index| self.stop_strings[index].as_str())
+    }
+
+    /// Forget the generated IDs, for the next sequence
+    pub fn reset(&mut self) {
+        self.recent.clear();
+        self.matched = None;
+    }
+
+    fn pattern_match(&self) -> Option<usize> {
+        self.patterns.iter().position(|pattern| {
+            !pattern.is_empty()
+                && pattern.len() <= self.recent.len()
+                && self.recent.iter().rev().zip(pattern.iter().rev()).all(|(a, b)| a == b)
+        })
+    }
+
+    fn text_match(&self) -> Option<usize> {
+        let ids: Vec<u32> = self.recent.iter().copied().collect();
+        let parts = self.tokenizer.decoder.decode_parts(&ids);
+        let (newest, before) = parts.split_last()?;
+        if newest.is_empty() {
+            return None;
+        }
+        let start = before.iter().map(String::len).sum::<usize>();
+        let text = parts.concat();
+        self.stop_strings
+            .iter()
+            .position(|stop| text.match_indices(stop.as_str()).any(|(i, _)| i + stop.len() > start))
+    }
+}
+
+impl TurkishTokenizer {
+    /// Track generated IDs for any of `stop_strings`; empty ones are
+    /// ignored
+    pub fn stop_criteria(&self, stop_strings: &[&str]) -> StopCriteria<'_> {
+        StopCriteria::new(self, stop_strings)
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::io::{self, Read};
+
+use crate::{Token, TurkishTokenizer};
+
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Lazily tokenizes text read from a [`Read`], yielding the same tokens as
+/// `tokenize_text` on the whole input. Created by
+/// [`TurkishTokenizer::tokenize_stream`].
+///
+/// Input is read in chunks and cut where a word follows whitespace, so
+/// neither words nor whitespace runs are split across chunk edges; only one
+/// chunk plus the trailing partial word is held in memory.
+pub struct TokenStream<'a, R> {
+    tokenizer: &'a TurkishTokenizer,
+    reader: R,
+    chunk: Box<[u8]>,
+    buf: Vec<u8>,
+    pending: VecDeque<Token>,
+    /// Char offset and word index of the start of `buf`
+    offset: usize,
+    word_id: usize,
+    done: bool,
+}
+
+impl<'a, R: Read> TokenStream<'a, R> {
+    pub(crate) fn new(tokenizer: &'a TurkishTokenizer, reader: R) -> Self {
+        TokenStream {
+            tokenizer,
+            reader,
+            chunk: vec![0; READ_CHUNK_SIZE].into_boxed_slice(),
+            buf: Vec::new(),
+            pending: VecDeque::new(),
+            offset: 0,
+            word_id: 0,
+            done: false,
+        }
+    }
+
+    /// Read until at least one complete word is buffered or the input ends,
+    /// and queue its tokens
+    fn fill(&mut self) -> io::Result<()> {
+        loop {
+            let read = match self.reader.read(&mut self.chunk) {
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if read == 0 {
+                let rest = std::mem::take(&mut self.buf);
+                self.queue(&rest)?;
+                self.done = true;
+                return Ok(());
+            }
+
+            // Separator bytes are ASCII, so they never occur inside a
+            // multi-byte UTF-8 sequence
+            let old_len = self.buf.len();
+            self.buf.extend_from_slice(&self.chunk[..read]);
+            let is_separator = |b: u8| self.tokenizer.is_separator_byte(b);
+            // Nor after a newline that may continue a word hyphenated
+            // across lines
+            let joins = |i: usize| {
+                let before = &self.buf[..i - 1];
+                let before = before.strip_suffix(b"\r").unwrap_or(before);
+                self.tokenizer.join_line_breaks() && (before.ends_with(b"-") || before.ends_with("\u{ad}".as_bytes()))
+            };
+            let cut = (old_len.max(1)..self.buf.len())
+                .rev()
+                .find(|&i| is_separator(self.buf[i - 1]) && !is_separator(self.buf[i]) && !joins(i));
+            if let Some(cut) = cut {
+                let rest = self.buf.split_off(cut);
+                let complete = std::mem::replace(&mut self.buf, rest);
+                self.queue(&complete)?;
+                return Ok(());
+            }
+        }
+    }
+
+    /// Queue the tokens of `bytes`
+    fn queue(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let text = std::str::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut words = 0;
+        for token in self.tokenizer.tokenize_text(text) {
+            if let Some(word_id) = token.word_id {
+                words = word_id + 1;
+            }
+            self.pending.push_back(Token {
+                start: self.offset + token.start,
+                end: self.offset + token.end,
+                word_id: token.word_id.map(|word_id| self.word_id + word_id),
+                ..token
+            });
+        }
+
+        self.offset += text.chars().count();
+        self.word_id += words;
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for TokenStream<'_, R> {
+    type Item = io::Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            if let Err(e) = self.fill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
@@ -0,0 +1,210 @@
+//! Special-token templates applied around encoded sequences, declared in
+//! the syntax of Hugging Face's `TemplateProcessing`.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use crate::{EncodingResult, PostProcessor, TurkishTokenizer, TurkishTokenizerError};
+
+/// Part of a template: one of the encoded texts or a special token, with
+/// the `token_type_ids` value its tokens get
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TemplatePiece {
+    /// `$A` (`first`) or `$B`
+    Sequence { first: bool, type_id: u32 },
+    Special { token: String, type_id: u32 },
+}
+
+/// Special-token layout for single texts and pairs, e.g. `"<cls> $A <sep>"`
+/// and `"<cls> $A <sep> $B <sep>"`.
+///
+/// `$A` and `$B` stand for the encoded texts; anything else is a special
+/// token. A `:n` suffix sets a piece's token type ID (`"$B:1"`); without
+/// one, pieces before `$B` get 0 and the rest 1.
+///
+/// ```
+/// use turkish_tokenizer::{PostProcessor, TemplateProcessing, TurkishTokenizer};
+///
+/// let template = TemplateProcessing::new("<cls> $A <sep>", "<cls> $A <sep> $B <sep>").unwrap();
+/// let tokenizer = TurkishTokenizer::builder()
+///     .post_processor(PostProcessor::Template(template))
+///     .build()
+///     .unwrap();
+/// let encoding = tokenizer.encode_plus_with("kitap", true);
+/// assert_eq!(encoding.tokens, vec!["<cls>", "kitap", "<sep>"]);
+/// assert_eq!(encoding.special_tokens_mask, vec![1, 0, 1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "TemplateSpec", into = "TemplateSpec")]
+pub struct TemplateProcessing {
+    single: String,
+    pair: String,
+    single_pieces: Vec<TemplatePiece>,
+    pair_pieces: Vec<TemplatePiece>,
+}
+
+/// Serialized form of a [`TemplateProcessing`]
+#[derive(Serialize, Deserialize)]
+struct TemplateSpec {
+    single: String,
+    pair: String,
+}
+
+impl TemplateProcessing {
+    /// Parse the templates for single texts, which must contain `$A` once,
+    /// and for pairs, which must contain `$A` and `$B` once each
+    pub fn new(single: &str, pair: &str) -> Result<Self, TurkishTokenizerError> {
+        let single_pieces = parse_template(single)?;
+        if sequence_count(&single_pieces, true) != 1 || sequence_count(&single_pieces, false) != 0 {
+            return Err(TurkishTokenizerError::InvalidConfig(format!(
+                "Single template '{}' must contain $A once and no $B",
+                single
+            )));
+        }
+        let pair_pieces = parse_template(pair)?;
+        if sequence_count(&pair_pieces, true) != 1 || sequence_count(&pair_pieces, false) != 1 {
+            return Err(TurkishTokenizerError::InvalidConfig(format!(
+                "Pair template '{}' must contain $A and $B once each",
+                pair
+            )));
+        }
+        Ok(TemplateProcessing {
+            single: single.to_string(),
+            pair: pair.to_string(),
+            single_pieces,
+            pair_pieces,
+        })
+    }
+
+    pub fn single(&self) -> &str {
+        &self.single
+    }
+
+    pub fn pair(&self) -> &str {
+        &self.pair
+    }
+
+    /// Special tokens used by either template
+    pub(crate) fn special_tokens(&self) -> impl Iterator<Item = &str> {
+        self.single_pieces.iter().chain(&self.pair_pieces).filter_map(|piece| match piece {
+            TemplatePiece::Special { token, .. } => Some(token.as_str()),
+            TemplatePiece::Sequence { .. } => None,
+        })
+    }
+}
+
+impl TryFrom<TemplateSpec> for TemplateProcessing {
+    type Error = String;
+
+    fn try_from(spec: TemplateSpec) -> Result<Self, Self::Error> {
+        Self::new(&spec.single, &spec.pair).map_err(|e| e.to_string())
+    }
+}
+
+impl From<TemplateProcessing> for TemplateSpec {
+    fn from(template: TemplateProcessing) -> Self {
+        TemplateSpec {
+            single: template.single,
+            pair: template.pair,
+        }
+    }
+}
+
+fn parse_template(template: &str) -> Result<Vec<TemplatePiece>, TurkishTokenizerError> {
+    let mut default_type_id = 0;
+    let mut pieces = Vec::new();
+    for item in template.split_whitespace() {
+        let (name, type_id) = match item.rsplit_once(':').map(|(name, type_id)| (name, type_id.parse::<u32>())) {
+            Some((name, Ok(type_id))) if !name.is_empty() => (name, Some(type_id)),
+            _ => (item, None),
+        };
+        if name == "$B" {
+            default_type_id = 1;
+        }
+        let type_id = type_id.unwrap_or(default_type_id);
+        pieces.push(match name {
+            "$A" => TemplatePiece::Sequence { first: true, type_id },
+            "$B" => TemplatePiece::Sequence { first: false, type_id },
+            _ if name.starts_with('$') => {
+                return Err(TurkishTokenizerError::InvalidConfig(format!("Unknown sequence '{}' in template", name)))
+            }
+            _ => TemplatePiece::Special { token: name.to_string(), type_id },
+        });
+    }
+    Ok(pieces)
+}
+
+fn sequence_count(pieces: &[TemplatePiece], first: bool) -> usize {
+    pieces
+        .iter()
+        .filter(|piece| matches!(piece, TemplatePiece::Sequence { first: f, .. } if *f == first))
+        .count()
+}
+
+impl TurkishTokenizer {
+    /// Number of special tokens the post-processor adds to a single text
+    /// or a pair
+    pub fn num_special_tokens_to_add(&self, pair: bool) -> usize {
+        self.template_pieces(pair)
+            .iter()
+            .filter(|piece| matches!(piece, TemplatePiece::Special { .. }))
+            .count()
+    }
+
+    /// Wrap `first`, and `second` for pairs, in the post-processor's
+    /// special tokens
+    pub(crate) fn post_process(&self, first: &EncodingResult, second: Option<&EncodingResult>) -> EncodingResult {
+        let mut result = EncodingResult::default();
+        for piece in self.template_pieces(second.is_some()).iter() {
+            match *piece {
+                TemplatePiece::Sequence { first: true, type_id } => result.extend(first, type_id),
+                TemplatePiece::Sequence { first: false, type_id } => {
+                    result.extend(second.expect("pair templates are only used for pairs"), type_id)
+                }
+                TemplatePiece::Special { ref token, type_id } => {
+                    let id = self.token_to_id(token).unwrap_or(self.unknown_marker.id);
+                    result.push_special(id, token, type_id);
+                }
+            }
+        }
+        result
+    }
+
+    /// The post-processor's layout for single texts or pairs
+    pub(crate) fn template_pieces(&self, pair: bool) -> Cow<'_, [TemplatePiece]> {
+        match (&self.post_processor, pair) {
+            (PostProcessor::Template(template), false) => Cow::Borrowed(&template.single_pieces),
+            (PostProcessor::Template(template), true) => Cow::Borrowed(&template.pair_pieces),
+            (PostProcessor::BosEos, false) => {
+                let mut pieces = Vec::new();
+                if self.add_bos_token {
+                    pieces.push(self.special_piece(&self.bos_token, 0));
+                }
+                pieces.push(TemplatePiece::Sequence { first: true, type_id: 0 });
+                if self.add_eos_token {
+                    pieces.push(self.special_piece(&self.eos_token, 0));
+                }
+                Cow::Owned(pieces)
+            }
+            (PostProcessor::ClsSep, false) => Cow::Owned(vec![
+                self.special_piece(&self.cls_token, 0),
+                TemplatePiece::Sequence { first: true, type_id: 0 },
+                self.special_piece(&self.sep_token, 0),
+            ]),
+            (PostProcessor::BosEos | PostProcessor::ClsSep, true) => Cow::Owned(vec![
+                self.special_piece(&self.cls_token, 0),
+                TemplatePiece::Sequence { first: true, type_id: 0 },
+                self.special_piece(&self.sep_token, 0),
+                TemplatePiece::Sequence { first: false, type_id: 1 },
+                self.special_piece(&self.sep_token, 1),
+            ]),
+        }
+    }
+
+    fn special_piece(&self, token: &str, type_id: u32) -> TemplatePiece {
+        TemplatePiece::Special {
+            token: token.to_string(),
+            type_id,
+        }
+    }
+}
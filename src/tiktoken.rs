@@ -0,0 +1,151 @@
+//! tiktoken-style ranks file and `CoreBPE`-like interface.
+//!
+//! The ranks file lists every ordinary token as base64-encoded bytes and
+//! its ID, one per line, the format `tiktoken.load_tiktoken_bpe` reads.
+//! Byte fallback tokens are written as their raw byte. Where two tokens
+//! share the same bytes, such as a byte token and the single-character
+//! token for it, only the lower ID is listed. BOS, EOS, padding and the
+//! other special tokens are left out, as tiktoken keeps those apart from
+//! the ranks. The case and unknown markers stay in as ordinary tokens,
+//! since plain encoding emits them.
+//!
+//! tiktoken's byte-pair merging cannot reproduce the morphological
+//! segmentation, so `CoreBpe` encodes with the tokenizer itself.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::{TurkishTokenizer, TurkishTokenizerError};
+
+impl TurkishTokenizer {
+    /// Special tokens kept out of the ranks, with their IDs
+    fn tiktoken_special_tokens(&self) -> HashMap<String, u32> {
+        let mut special_tokens: HashMap<String, u32> = [
+            (&self.bos_token, self.bos_token_id),
+            (&self.eos_token, self.eos_token_id),
+            (&self.pad_token, self.pad_token_id),
+            (&self.sep_token, self.sep_token_id),
+            (&self.cls_token, self.cls_token_id),
+            (&self.mask_token, self.mask_token_id),
+        ]
+        .into_iter()
+        .map(|(token, id)| (token.clone(), id))
+        .collect();
+        special_tokens.extend(
+            self.added_tokens
+                .iter()
+                .filter(|added| added.special)
+                .map(|added| (added.token.token.clone(), added.token.id)),
+        );
+        special_tokens
+    }
+
+    /// Ordinary tokens as `(bytes, ID)`, ordered by ID
+    fn tiktoken_ranks(&self) -> Vec<(Vec<u8>, u32)> {
+        let special_ids: HashSet<u32> = self.tiktoken_special_tokens().into_values().collect();
+        let mut ids: Vec<u32> = self
+            .vocab
+            .iter()
+            .filter(|(token, _)| !(token.starts_with("special_") && self.roots.contains_key(*token)))
+            .map(|(_, &id)| id)
+            .filter(|id| !special_ids.contains(id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut seen = HashSet::new();
+        ids.into_iter()
+            .filter_map(|id| {
+                let bytes = match id.checked_sub(self.byte_offset) {
+                    Some(byte @ 0..=255) => vec![byte as u8],
+                    _ => self.id_to_token(id)?.as_bytes().to_vec(),
+                };
+                (!bytes.is_empty() && seen.insert(bytes.clone())).then_some((bytes, id))
+            })
+            .collect()
+    }
+
+    /// Write the ordinary tokens to `path` as a tiktoken ranks file
+    pub fn export_tiktoken(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let mut contents = String::new();
+        for (bytes, id) in self.tiktoken_ranks() {
+            contents.push_str(&STANDARD.encode(bytes));
+            contents.push(' ');
+            contents.push_str(&id.to_string());
+            contents.push('\n');
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// tiktoken `CoreBPE`-like view of this tokenizer
+    pub fn to_tiktoken(&self) -> CoreBpe {
+        let mut special_tokens: Vec<(String, u32)> = self.tiktoken_special_tokens().into_iter().collect();
+        // Longest first, so a special token never shadows a longer one
+        special_tokens.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        CoreBpe {
+            tokenizer: self.clone(),
+            special_tokens,
+        }
+    }
+}
+
+/// Encoder following tiktoken's `CoreBPE`: ordinary encoding treats special
+/// token strings as plain text, and callers opt in to matching them
+#[derive(Clone)]
+pub struct CoreBpe {
+    tokenizer: TurkishTokenizer,
+    special_tokens: Vec<(String, u32)>,
+}
+
+impl CoreBpe {
+    /// Encode `text`, treating special token strings as plain text
+    pub fn encode_ordinary(&self, text: &str) -> Vec<u32> {
+        self.encode(text, &HashSet::new())
+    }
+
+    /// Encode `text`, turning the special tokens in `allowed_special` into
+    /// their IDs and treating any others as plain text
+    pub fn encode(&self, text: &str, allowed_special: &HashSet<&str>) -> Vec<u32> {
+        let mut ids = Vec::new();
+        let mut plain_start = 0;
+        let mut pos = 0;
+        while pos < text.len() {
+            let rest = &text[pos..];
+            let Some((token, id)) = self.special_tokens.iter().find(|(token, _)| rest.starts_with(token.as_str()))
+            else {
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+                continue;
+            };
+            ids.extend(self.tokenizer.encode(&text[plain_start..pos]));
+            if allowed_special.contains(token.as_str()) {
+                ids.push(*id);
+            } else {
+                // The vocabulary would still match the whole string, so
+                // spell it out a character at a time
+                ids.extend(token.chars().flat_map(|c| self.tokenizer.encode(c.encode_utf8(&mut [0; 4]))));
+            }
+            pos += token.len();
+            plain_start = pos;
+        }
+        ids.extend(self.tokenizer.encode(&text[plain_start..]));
+        ids
+    }
+
+    /// Encode `text`, turning every special token into its ID
+    pub fn encode_with_special_tokens(&self, text: &str) -> Vec<u32> {
+        self.encode(text, &self.special_tokens())
+    }
+
+    pub fn decode(&self, ids: &[u32]) -> String {
+        self.tokenizer.decode(ids)
+    }
+
+    /// Strings recognised by `encode_with_special_tokens`
+    pub fn special_tokens(&self) -> HashSet<&str> {
+        self.special_tokens.iter().map(|(token, _)| token.as_str()).collect()
+    }
+}
@@ -0,0 +1,310 @@
+//! BPE vocabulary training on top of the fixed root and suffix tables.
+//!
+//! Roots and suffixes are matched before BPE at every position, so the BPE
+//! layer only covers what they leave over. Training tokenizes the corpus
+//! with the BPE table emptied, collects the runs of characters that fell
+//! back to bytes, and learns merges over those runs. The new table holds
+//! an alphabet of single characters followed by the merged tokens in the
+//! order they were learned. The alphabet is the corpus's leftover
+//! characters, most frequent first, then the current single-character BPE
+//! tokens, so text outside the corpus keeps its coverage.
+
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::matcher::{PrefixMatcher, VocabMatcher};
+use crate::{TokenType, TurkishTokenizer, TurkishTokenizerError, UnknownStrategy};
+
+/// Pairs seen fewer times than this are not merged
+const MIN_PAIR_COUNT: u64 = 2;
+
+/// A residual run as symbol IDs, with how often it occurs
+struct Word {
+    symbols: Vec<u32>,
+    count: u64,
+}
+
+/// Byte-pair merging over residual runs, with pair counts updated in place
+/// so each merge only revisits the words containing the pair
+struct BpeTrainer {
+    symbols: Vec<String>,
+    symbol_ids: HashMap<String, u32>,
+    words: Vec<Word>,
+    pair_counts: HashMap<(u32, u32), u64>,
+    /// Words that contain, or once contained, each pair
+    pair_words: HashMap<(u32, u32), HashSet<usize>>,
+    /// Pairs by count, most frequent first; entries whose count has since
+    /// changed are skipped when popped
+    queue: BinaryHeap<(u64, Reverse<(u32, u32)>)>,
+}
+
+impl BpeTrainer {
+    fn new(residuals: HashMap<String, u64>) -> Self {
+        let mut runs: Vec<(String, u64)> = residuals.into_iter().collect();
+        runs.sort_unstable();
+        let mut chars: Vec<char> = runs.iter().flat_map(|(run, _)| run.chars()).collect();
+        chars.sort_unstable();
+        chars.dedup();
+
+        let symbols: Vec<String> = chars.iter().map(char::to_string).collect();
+        let symbol_ids: HashMap<String, u32> = symbols.iter().cloned().zip(0..).collect();
+        let words = runs
+            .into_iter()
+            .map(|(run, count)| Word {
+                symbols: run.chars().map(|c| symbol_ids[c.encode_utf8(&mut [0; 4]) as &str]).collect(),
+                count,
+            })
+            .collect();
+        let mut trainer = BpeTrainer {
+            symbols,
+            symbol_ids,
+            words,
+            pair_counts: HashMap::new(),
+            pair_words: HashMap::new(),
+            queue: BinaryHeap::new(),
+        };
+        for index in 0..trainer.words.len() {
+            trainer.add_pairs(index);
+        }
+        let queue = trainer.pair_counts.iter().map(|(&pair, &count)| (count, Reverse(pair))).collect();
+        trainer.queue = queue;
+        trainer
+    }
+
+    /// Characters by how often they occur, most frequent first
+    fn char_counts(&self) -> Vec<(String, u64)> {
+        let mut counts = vec![0; self.symbols.len()];
+        for word in &self.words {
+            for &symbol in &word.symbols {
+                counts[symbol as usize] += word.count;
+            }
+        }
+        let mut chars: Vec<(String, u64)> = self.symbols.iter().cloned().zip(counts).collect();
+        chars.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        chars
+    }
+
+    fn add_pairs(&mut self, index: usize) {
+        let word = &self.words[index];
+        for pair in word.symbols.windows(2) {
+            let pair = (pair[0], pair[1]);
+            *self.pair_counts.entry(pair).or_default() += word.count;
+            self.pair_words.entry(pair).or_default().insert(index);
+        }
+    }
+
+    fn remove_pairs(&mut self, index: usize) {
+        let word = &self.words[index];
+        for pair in word.symbols.windows(2) {
+            if let Some(count) = self.pair_counts.get_mut(&(pair[0], pair[1])) {
+                *count -= word.count;
+            }
+        }
+    }
+
+    /// Merge the most frequent pair everywhere and return the merged token,
+    /// or `None` once no pair is frequent enough
+    fn merge_next(&mut self) -> Option<String> {
+        let pair = loop {
+            let (count, Reverse(pair)) = self.queue.pop()?;
+            if self.pair_counts.get(&pair) != Some(&count) {
+                continue;
+            }
+            if count < MIN_PAIR_COUNT {
+                return None;
+            }
+            break pair;
+        };
+
+        let merged = format!("{}{}", self.symbols[pair.0 as usize], self.symbols[pair.1 as usize]);
+        let merged_id = match self.symbol_ids.get(&merged) {
+            Some(&id) => id,
+            None => {
+                let id = self.symbols.len() as u32;
+                self.symbols.push(merged.clone());
+                self.symbol_ids.insert(merged.clone(), id);
+                id
+            }
+        };
+
+        let mut indices: Vec<usize> = self.pair_words.remove(&pair).unwrap_or_default().into_iter().collect();
+        indices.sort_unstable();
+        let mut changed = HashSet::new();
+        for index in indices {
+            if !self.words[index].symbols.windows(2).any(|window| (window[0], window[1]) == pair) {
+                continue;
+            }
+            self.remove_pairs(index);
+            let old = std::mem::take(&mut self.words[index].symbols);
+            let mut symbols = Vec::with_capacity(old.len());
+            let mut i = 0;
+            while i < old.len() {
+                if i + 1 < old.len() && (old[i], old[i + 1]) == pair {
+                    symbols.push(merged_id);
+                    i += 2;
+                } else {
+                    symbols.push(old[i]);
+                    i += 1;
+                }
+            }
+            changed.extend(old.windows(2).chain(symbols.windows(2)).map(|window| (window[0], window[1])));
+            self.words[index].symbols = symbols;
+            self.add_pairs(index);
+        }
+        self.pair_counts.remove(&pair);
+        changed.remove(&pair);
+        for pair in changed {
+            match self.pair_counts.get(&pair) {
+                Some(&0) => {
+                    self.pair_counts.remove(&pair);
+                }
+                Some(&count) => self.queue.push((count, Reverse(pair))),
+                None => {}
+            }
+        }
+        Some(merged)
+    }
+}
+
+impl TurkishTokenizer {
+    /// Runs of characters in `text` that the BPE table would have to
+    /// cover. Called on a copy without BPE tokens, where those characters
+    /// fall back to bytes. A run starts at a character no root or suffix
+    /// matches and takes in the suffix matches after it, since a BPE token
+    /// starting there may run through them, up to the next root.
+    fn residual_runs(&self, text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut runs = Vec::new();
+        let mut run: Vec<char> = Vec::new();
+        // Word and span of the run's last token
+        let mut last: Option<(Option<usize>, usize, usize)> = None;
+        for token in self.tokenize_iter(text) {
+            let continues = last.is_some_and(|(word_id, _, end)| word_id == token.word_id && end == token.start);
+            match token.token_type {
+                // The byte tokens of one character share its span
+                TokenType::Byte if last.is_some_and(|(_, start, _)| start == token.start) => continue,
+                TokenType::Byte => {
+                    if !continues {
+                        runs.extend((!run.is_empty()).then(|| run.drain(..).collect()));
+                    }
+                    for &c in &chars[token.start..token.end] {
+                        if self.lowercase {
+                            self.push_lower(c, &mut run);
+                        } else {
+                            run.push(c);
+                        }
+                    }
+                }
+                TokenType::Suffix if continues => run.extend(token.token.chars()),
+                _ => {
+                    runs.extend((!run.is_empty()).then(|| run.drain(..).collect()));
+                    last = None;
+                    continue;
+                }
+            }
+            last = Some((token.word_id, token.start, token.end));
+        }
+        runs.extend((!run.is_empty()).then(|| run.into_iter().collect()));
+        runs
+    }
+
+    /// Copy of this tokenizer with a BPE table learned from `corpus`, sized
+    /// so that the BPE IDs end just below `target_vocab_size`. Roots,
+    /// suffixes and settings are kept; byte fallback, user and added tokens
+    /// move to follow the new table. `save_pretrained` writes the table as
+    /// `bpe_tokenler.json`. Training stops early once no pair of tokens
+    /// occurs twice.
+    pub fn train_bpe<'a>(
+        &self,
+        corpus: impl Iterator<Item = &'a str> + Send,
+        target_vocab_size: usize,
+    ) -> Result<TurkishTokenizer, TurkishTokenizerError> {
+        let base_roots = self.base_roots();
+        let first_id = base_roots.values().chain(self.suffixes.values()).max().map_or(0, |&id| id + 1);
+        let budget = match (target_vocab_size as u64).checked_sub(u64::from(first_id)) {
+            Some(budget) if budget > 0 => budget as usize,
+            _ => {
+                return Err(TurkishTokenizerError::InvalidConfig(format!(
+                    "Target vocabulary size {} leaves no room for BPE tokens after the roots and suffixes, which end at ID {}",
+                    target_vocab_size,
+                    first_id - 1
+                )))
+            }
+        };
+
+        let mut base = self.clone();
+        base.bpe_matcher = Arc::new(VocabMatcher::from_table(&HashMap::new()));
+        base.unknown_strategy = UnknownStrategy::ByteFallback;
+        let residuals = corpus
+            .par_bridge()
+            .fold(HashMap::new, |mut counts: HashMap<String, u64>, text| {
+                for run in base.residual_runs(text) {
+                    *counts.entry(run).or_default() += 1;
+                }
+                counts
+            })
+            .reduce(HashMap::new, |mut total, counts| {
+                for (run, count) in counts {
+                    *total.entry(run).or_default() += count;
+                }
+                total
+            });
+
+        let mut trainer = BpeTrainer::new(residuals);
+        let mut current_chars: Vec<(&String, &u32)> =
+            self.bpe_tokens.iter().filter(|(token, _)| token.chars().nth(1).is_none()).collect();
+        current_chars.sort_by_key(|&(_, &id)| id);
+        let alphabet = trainer
+            .char_counts()
+            .into_iter()
+            .map(|(c, _)| c)
+            .chain(current_chars.into_iter().map(|(c, _)| c.clone()));
+
+        // BPE entries must not shadow a root or suffix in the vocabulary
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut table: Vec<String> = Vec::with_capacity(budget);
+        let mut push = |token: String, table: &mut Vec<String>| {
+            if !self.roots.contains_key(&token) && !self.suffixes.contains_key(&token) && seen.insert(token.clone()) {
+                table.push(token);
+            }
+        };
+        for c in alphabet {
+            if table.len() == budget {
+                break;
+            }
+            push(c, &mut table);
+        }
+        while table.len() < budget {
+            let Some(merged) = trainer.merge_next() else {
+                break;
+            };
+            push(merged, &mut table);
+        }
+
+        let mut snapshot = self.snapshot()?;
+        snapshot.tables.bpe_tokens = table.into_iter().zip(first_id..).collect();
+        let byte_offset = first_id + snapshot.tables.bpe_tokens.len() as u32;
+        // Emoji and abbreviation entries that were old BPE tokens are gone
+        // with the table
+        snapshot.emoji_tokens.retain(|&(_, id)| id < first_id || id >= self.byte_offset);
+        snapshot.abbreviations.retain(|&(_, id)| id < first_id || id >= self.byte_offset);
+        let appended = snapshot
+            .user_tokens
+            .iter_mut()
+            .chain(&mut snapshot.added_tokens)
+            .chain(&mut snapshot.emoji_tokens)
+            .chain(&mut snapshot.abbreviations);
+        for (_, id) in appended {
+            if *id >= self.byte_offset {
+                *id = *id - self.byte_offset + byte_offset;
+            }
+        }
+        // Scores of the old BPE and byte tokens no longer apply
+        if let Some(scores) = &mut snapshot.scores {
+            scores.retain(|&(id, _)| id < first_id);
+        }
+        Self::from_snapshot(snapshot)
+    }
+}
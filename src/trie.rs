@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use crate::matcher::PrefixMatcher;
+
+/// Character trie mapping vocabulary entries to IDs, used for longest-prefix
+/// matching without building candidate strings.
+#[derive(Debug, Clone)]
+pub(crate) struct PrefixTrie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    /// Children sorted by character for binary search
+    children: Vec<(char, usize)>,
+    id: Option<u32>,
+}
+
+impl PrefixTrie {
+    pub(crate) fn new() -> Self {
+        PrefixTrie {
+            nodes: vec![TrieNode::default()],
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: &str, id: u32) {
+        let mut node = 0;
+        for c in key.chars() {
+            node = match self.nodes[node].children.binary_search_by_key(&c, |&(ch, _)| ch) {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].children.insert(i, (c, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].id = Some(id);
+    }
+
+    /// Unmark `key` as an entry. Its nodes are kept since other entries may
+    /// share them.
+    pub(crate) fn remove(&mut self, key: &str) {
+        if let Some(node) = self.find(key) {
+            self.nodes[node].id = None;
+        }
+    }
+
+    /// IDs of every entry starting with `prefix`, in no particular order
+    pub(crate) fn ids_with_prefix(&self, prefix: &str) -> Vec<u32> {
+        let mut ids = Vec::new();
+        let mut stack: Vec<usize> = self.find(prefix).into_iter().collect();
+        while let Some(node) = stack.pop() {
+            ids.extend(self.nodes[node].id);
+            stack.extend(self.nodes[node].children.iter().map(|&(_, child)| child));
+        }
+        ids
+    }
+
+    fn find(&self, key: &str) -> Option<usize> {
+        key.chars().try_fold(0, |node, c| self.child(node, c))
+    }
+
+    fn child(&self, node: usize, c: char) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by_key(&c, |&(ch, _)| ch)
+            .ok()
+            .map(|i| children[i].1)
+    }
+}
+
+impl PrefixMatcher for PrefixTrie {
+    fn from_table(table: &HashMap<String, u32>) -> Self {
+        let mut trie = Self::new();
+        for (key, &id) in table {
+            trie.insert(key, id);
+        }
+        trie
+    }
+
+    fn longest_prefix(&self, chars: &[char]) -> Option<(u32, usize)> {
+        let mut node = 0;
+        let mut best = None;
+        for (i, &c) in chars.iter().enumerate() {
+            match self.child(node, c) {
+                Some(next) => node = next,
+                None => break,
+            }
+            if let Some(id) = self.nodes[node].id {
+                best = Some((id, i + 1));
+            }
+        }
+        best
+    }
+
+    fn replace(&mut self, _table: &HashMap<String, u32>, removed: &str, added: &str, id: u32) {
+        self.remove(removed);
+        self.insert(added, id);
+    }
+
+    fn add(&mut self, _table: &HashMap<String, u32>, added: &str, id: u32) {
+        self.insert(added, id);
+    }
+}
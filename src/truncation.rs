@@ -0,0 +1,98 @@
+//! Truncation of single texts and pairs to a maximum length.
+//!
+//! Only the encoded texts lose tokens; the post-processor's special tokens
+//! are counted against `max_length` and always kept. For pairs the
+//! strategy decides which text is cut, so a QA question can stay whole
+//! while its context is shortened.
+
+use crate::{EncodingResult, TruncationStrategy, TurkishTokenizer, TurkishTokenizerError};
+
+impl TruncationStrategy {
+    /// How many tokens of each sequence to keep so they total at most
+    /// `budget`. Fails if the strategy cannot get there: it cuts a missing
+    /// second sequence, or the other sequence alone fills the budget.
+    pub(crate) fn keep(
+        self,
+        first: usize,
+        second: Option<usize>,
+        budget: usize,
+    ) -> Result<(usize, Option<usize>), TurkishTokenizerError> {
+        let Some(second) = second else {
+            return match self {
+                TruncationStrategy::OnlySecond => Err(TurkishTokenizerError::InvalidConfig(
+                    "only_second truncation needs a text pair".to_string(),
+                )),
+                _ => Ok((first.min(budget), None)),
+            };
+        };
+        if first + second <= budget {
+            return Ok((first, Some(second)));
+        }
+        let too_long = |kept: usize| {
+            TurkishTokenizerError::InvalidConfig(format!(
+                "{:?} truncation cannot fit the pair in {} tokens: the other sequence has {}",
+                self, budget, kept
+            ))
+        };
+        match self {
+            // Removing one token at a time from the longer sequence, the
+            // second on ties
+            TruncationStrategy::LongestFirst if first.min(second) <= budget / 2 => Ok(if first <= second {
+                (first, Some(budget - first))
+            } else {
+                (budget - second, Some(second))
+            }),
+            TruncationStrategy::LongestFirst => Ok((budget - budget / 2, Some(budget / 2))),
+            TruncationStrategy::OnlyFirst if second < budget => Ok((budget - second, Some(second))),
+            TruncationStrategy::OnlyFirst => Err(too_long(second)),
+            TruncationStrategy::OnlySecond if first < budget => Ok((first, Some(budget - first))),
+            TruncationStrategy::OnlySecond => Err(too_long(first)),
+        }
+    }
+}
+
+impl TurkishTokenizer {
+    /// `encode_plus_with`, cutting the text so the result has at most
+    /// `max_length` tokens including any special tokens
+    pub fn encode_plus_truncated(
+        &self,
+        text: &str,
+        add_special_tokens: bool,
+        max_length: usize,
+        strategy: TruncationStrategy,
+    ) -> Result<EncodingResult, TurkishTokenizerError> {
+        let special_count = if add_special_tokens { self.num_special_tokens_to_add(false) } else { 0 };
+        let mut encoding = self.encode_plus(text);
+        let (keep, _) = strategy.keep(encoding.input_ids.len(), None, self.truncation_budget(max_length, special_count)?)?;
+        encoding.truncate(keep);
+        Ok(if add_special_tokens { self.post_process(&encoding, None) } else { encoding })
+    }
+
+    /// `encode_pair`, cutting the texts as `strategy` says so the result
+    /// has at most `max_length` tokens including the special tokens
+    pub fn encode_pair_truncated(
+        &self,
+        text_a: &str,
+        text_b: &str,
+        max_length: usize,
+        strategy: TruncationStrategy,
+    ) -> Result<EncodingResult, TurkishTokenizerError> {
+        let budget = self.truncation_budget(max_length, self.num_special_tokens_to_add(true))?;
+        let mut first = self.encode_plus(text_a);
+        let mut second = self.encode_plus(text_b);
+        let (keep_first, keep_second) = strategy.keep(first.input_ids.len(), Some(second.input_ids.len()), budget)?;
+        first.truncate(keep_first);
+        second.truncate(keep_second.unwrap_or(0));
+        Ok(self.post_process(&first, Some(&second)))
+    }
+
+    /// Tokens left for the texts once `special_count` special tokens fit
+    fn truncation_budget(&self, max_length: usize, special_count: usize) -> Result<usize, TurkishTokenizerError> {
+        max_length.checked_sub(special_count).ok_or_else(|| {
+            TurkishTokenizerError::InvalidConfig(format!(
+                "max_length {} is shorter than the {} special tokens",
+                max_length, special_count
+            ))
+        })
+    }
+}
@@ -0,0 +1,287 @@
+//! Token scores for `SegmentationMode::Unigram` and their estimation.
+//!
+//! A score is the log probability of a token under a unigram language
+//! model, and the best segmentation of a word is the one whose scores sum
+//! highest. Scores are estimated from a corpus by expectation
+//! maximization: each pass weighs every segmentation of every word by its
+//! probability under the current scores, then sets each score from the
+//! expected count of its token. The lattice holds every root, suffix and
+//! BPE match, as in `SegmentationMode::BestPath`, but ignores the strict
+//! morphology and morphotactics constraints, which only prune paths.
+
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{cluster_ends, PathStep, TurkishTokenizer, TurkishTokenizerError};
+
+pub const SCORES_FILE: &str = "scores.json";
+
+/// Score of tokens when no scores are set
+pub(crate) const UNSCORED_SCORE: f32 = -20.0;
+/// How far below the lowest score tokens without a score fall
+const UNSCORED_PENALTY: f32 = 10.0;
+/// Extra cost of a character no table matches, over an unscored token
+const UNMATCHED_PENALTY: f32 = 10.0;
+
+/// Log probabilities by token ID
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TokenScores {
+    scores: HashMap<u32, f32>,
+    /// Score of tokens missing from `scores`
+    pub unscored: f32,
+}
+
+impl TokenScores {
+    pub fn new(scores: HashMap<u32, f32>) -> Self {
+        let unscored = scores
+            .values()
+            .copied()
+            .reduce(f32::min)
+            .map_or(UNSCORED_SCORE, |min| min - UNSCORED_PENALTY);
+        TokenScores { scores, unscored }
+    }
+
+    pub fn score(&self, id: u32) -> f32 {
+        self.scores.get(&id).copied().unwrap_or(self.unscored)
+    }
+
+    pub fn entries(&self) -> &HashMap<u32, f32> {
+        &self.scores
+    }
+}
+
+/// Word spans with how often they occur, as lowercased chars
+type Spans = Vec<(Vec<char>, u64)>;
+
+/// An edge of a span's lattice: a token ID, or `None` for an unmatched
+/// grapheme cluster, and its length in chars
+type Edge = (Option<u32>, usize);
+
+fn log_add(a: f64, b: f64) -> f64 {
+    if a == f64::NEG_INFINITY {
+        return b;
+    }
+    let (hi, lo) = if a > b { (a, b) } else { (b, a) };
+    hi + (lo - hi).exp().ln_1p()
+}
+
+impl TurkishTokenizer {
+    /// Per-token log probabilities for `SegmentationMode::Unigram`, if set
+    pub fn scores(&self) -> Option<&HashMap<u32, f32>> {
+        self.scores.as_deref().map(TokenScores::entries)
+    }
+
+    /// Set the log probability of each token ID for
+    /// `SegmentationMode::Unigram`. Tokens without a score rank below
+    /// every scored one.
+    pub fn set_scores(&mut self, scores: HashMap<u32, f32>) {
+        self.scores = Some(Arc::new(TokenScores::new(scores)));
+        self.word_cache.clear();
+    }
+
+    pub fn clear_scores(&mut self) {
+        self.scores = None;
+        self.word_cache.clear();
+    }
+
+    /// Path cost of a token in `SegmentationMode::Unigram`
+    pub(crate) fn unigram_cost(&self, id: u32) -> f64 {
+        -f64::from(self.scores.as_ref().map_or(UNSCORED_SCORE, |scores| scores.score(id)))
+    }
+
+    /// Path cost of an unmatched character in `SegmentationMode::Unigram`
+    pub(crate) fn unigram_unmatched_cost(&self) -> f64 {
+        let unscored = self.scores.as_ref().map_or(UNSCORED_SCORE, |scores| scores.unscored);
+        f64::from(UNMATCHED_PENALTY - unscored)
+    }
+
+    /// Write the scores as `{token: score}`, ordered by ID
+    pub(crate) fn save_scores(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let Some(scores) = self.scores() else {
+            return Ok(());
+        };
+        let mut ids: Vec<u32> = scores.keys().copied().collect();
+        ids.sort_unstable();
+        let entries: serde_json::Map<String, serde_json::Value> = ids
+            .into_iter()
+            .filter_map(|id| Some((self.id_to_token(id)?.to_string(), f64::from(scores[&id]).into())))
+            .collect();
+        fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn load_scores(&mut self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut scores = HashMap::with_capacity(entries.len());
+        for (token, score) in entries {
+            let id = self.token_to_id(&token).ok_or_else(|| {
+                TurkishTokenizerError::InvalidConfig(format!("Scored token {:?} is not in the vocabulary", token))
+            })?;
+            let score: f32 = serde_json::from_value(score)?;
+            scores.insert(id, score);
+        }
+        self.set_scores(scores);
+        Ok(())
+    }
+
+    /// Estimate token scores from `corpus` by `iterations` rounds of
+    /// expectation maximization, starting from uniform scores. Pass the
+    /// result to `set_scores` and segment with `SegmentationMode::Unigram`.
+    /// Tokens that never occur in the corpus get no score.
+    pub fn train_unigram_scores<'a>(
+        &self,
+        corpus: impl Iterator<Item = &'a str> + Send,
+        iterations: usize,
+    ) -> HashMap<u32, f32> {
+        let spans: Spans = corpus
+            .par_bridge()
+            .fold(HashMap::new, |mut counts: HashMap<Vec<char>, u64>, text| {
+                for span in self.word_spans(text) {
+                    *counts.entry(span).or_default() += 1;
+                }
+                counts
+            })
+            .reduce(HashMap::new, |mut total, counts| {
+                for (span, count) in counts {
+                    *total.entry(span).or_default() += count;
+                }
+                total
+            })
+            .into_iter()
+            .collect();
+        let lattices: Vec<(Vec<Vec<Edge>>, u64)> =
+            spans.par_iter().map(|(chars, count)| (self.lattice(chars), *count)).collect();
+
+        let ids = lattices.iter().flat_map(|(lattice, _)| lattice.iter().flatten().filter_map(|&(id, _)| id));
+        let mut scores: HashMap<u32, f64> = ids.map(|id| (id, 0.0)).collect();
+        let uniform = -(scores.len().max(1) as f64).ln();
+        scores.values_mut().for_each(|score| *score = uniform);
+        let mut unscored = uniform - f64::from(UNSCORED_PENALTY);
+
+        for _ in 0..iterations {
+            let expected = lattices
+                .par_iter()
+                .fold(HashMap::new, |mut expected: HashMap<u32, f64>, (lattice, count)| {
+                    expected_counts(lattice, *count as f64, &scores, unscored, &mut expected);
+                    expected
+                })
+                .reduce(HashMap::new, |mut total, expected| {
+                    for (id, count) in expected {
+                        *total.entry(id).or_default() += count;
+                    }
+                    total
+                });
+            let total: f64 = expected.values().sum();
+            if total <= 0.0 {
+                break;
+            }
+            scores = expected
+                .into_iter()
+                .filter(|&(_, count)| count > 0.0)
+                .map(|(id, count)| (id, (count / total).ln()))
+                .collect();
+            unscored = scores.values().copied().fold(0.0, f64::min) - f64::from(UNSCORED_PENALTY);
+        }
+        scores.into_iter().map(|(id, score)| (id, score as f32)).collect()
+    }
+
+    /// Runs of contiguous tokens within the words of `text`, as the
+    /// segmenter sees them. Markers and uppercase letters inside a word
+    /// start a new run, since the segmenter splits there too.
+    fn word_spans(&self, text: &str) -> Vec<Vec<char>> {
+        let chars: Vec<char> = text.chars().collect();
+        let markers = [
+            Some(&self.uppercase_marker),
+            self.all_caps_marker.as_ref(),
+            self.number_marker.as_ref(),
+            Some(&self.unknown_marker),
+        ];
+        let marker_ids: Vec<u32> = markers.into_iter().flatten().chain(self.entity_markers()).map(|marker| marker.id).collect();
+
+        let mut spans = Vec::new();
+        let mut span: Vec<char> = Vec::new();
+        let mut last: Option<(Option<usize>, usize, usize)> = None;
+        for token in self.tokenize_iter(text) {
+            // The byte tokens of one character share its span
+            if last.is_some_and(|(_, start, end)| (start, end) == (token.start, token.end)) {
+                continue;
+            }
+            let continues = last.is_some_and(|(word_id, _, end)| word_id == token.word_id && end == token.start)
+                && !chars[token.start].is_uppercase();
+            if !continues {
+                spans.extend((!span.is_empty()).then(|| std::mem::take(&mut span)));
+            }
+            if token.word_id.is_none() || token.start == token.end || marker_ids.contains(&token.id) {
+                last = None;
+                continue;
+            }
+            for &c in &chars[token.start..token.end] {
+                if self.lowercase {
+                    self.push_lower(c, &mut span);
+                } else {
+                    span.push(c);
+                }
+            }
+            last = Some((token.word_id, token.start, token.end));
+        }
+        spans.extend((!span.is_empty()).then_some(span));
+        spans
+    }
+
+    /// Edges leaving each position of `chars`
+    fn lattice(&self, chars: &[char]) -> Vec<Vec<Edge>> {
+        let cluster_ends = cluster_ends(chars);
+        let mut candidates: Vec<PathStep> = Vec::new();
+        (0..chars.len())
+            .map(|i| {
+                self.path_candidates(chars, i, chars.len(), &mut None, &mut candidates);
+                let mut edges: Vec<Edge> = candidates.iter().map(|step| (Some(step.id), step.len)).collect();
+                edges.push((None, cluster_ends[i] - i));
+                edges
+            })
+            .collect()
+    }
+}
+
+/// Add the expected count of each token over the segmentations of one
+/// span, found by the forward-backward algorithm in log space
+fn expected_counts(
+    lattice: &[Vec<Edge>],
+    count: f64,
+    scores: &HashMap<u32, f64>,
+    unscored: f64,
+    expected: &mut HashMap<u32, f64>,
+) {
+    let score = |id: Option<u32>| match id {
+        Some(id) => scores.get(&id).copied().unwrap_or(unscored),
+        None => unscored - f64::from(UNMATCHED_PENALTY),
+    };
+    let n = lattice.len();
+    let mut forward = vec![f64::NEG_INFINITY; n + 1];
+    forward[0] = 0.0;
+    for i in 0..n {
+        for &(id, len) in &lattice[i] {
+            forward[i + len] = log_add(forward[i + len], forward[i] + score(id));
+        }
+    }
+    let mut backward = vec![f64::NEG_INFINITY; n + 1];
+    backward[n] = 0.0;
+    for i in (0..n).rev() {
+        for &(id, len) in &lattice[i] {
+            backward[i] = log_add(backward[i], score(id) + backward[i + len]);
+        }
+    }
+    let total = forward[n];
+    for i in 0..n {
+        for &(id, len) in &lattice[i] {
+            let Some(id) = id else {
+                continue;
+            };
+            let posterior = (forward[i] + score(Some(id)) + backward[i + len] - total).exp();
+            *expected.entry(id).or_default() += posterior * count;
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Runtime user dictionary: extra root entries for domain terms that would
+//! otherwise be split into BPE pieces.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::matcher::PrefixMatcher;
+use crate::{TurkishTokenizer, TurkishTokenizerError};
+
+/// File holding the user dictionary inside a tokenizer directory
+pub const USER_TOKENS_FILE: &str = "user_tokens.json";
+
+impl TurkishTokenizer {
+    /// Register extra root entries such as product names, medical terms or
+    /// slang so they are matched whole. An entry without an ID gets the
+    /// next ID after the whole vocabulary, byte tokens included; an entry
+    /// with the ID of an existing token becomes an extra surface form of
+    /// it. Entries are matched against lowercased text when `lowercase` is
+    /// on. Returns the ID of every entry.
+    pub fn add_user_tokens(&mut self, tokens: &[(&str, Option<u32>)]) -> Result<Vec<u32>, TurkishTokenizerError> {
+        let mut ids = Vec::with_capacity(tokens.len());
+        for &(token, id) in tokens {
+            if token.is_empty() {
+                return Err(TurkishTokenizerError::InvalidConfig("User tokens must not be empty".to_string()));
+            }
+            if let Some(&existing) = self.vocab.get(token) {
+                if let Some(id) = id.filter(|&id| id != existing) {
+                    return Err(TurkishTokenizerError::ConflictingId {
+                        token: token.to_string(),
+                        id,
+                        existing,
+                    });
+                }
+                ids.push(existing);
+                continue;
+            }
+
+            let id = match id {
+                Some(id) if (self.byte_offset..=self.byte_offset + u32::from(u8::MAX)).contains(&id) => {
+                    return Err(TurkishTokenizerError::IdOutOfRange(id));
+                }
+                Some(id) => id,
+                None => self.vocab.values().max().map_or(0, |&max_id| max_id + 1),
+            };
+            Arc::make_mut(&mut self.roots).insert(token.to_string(), id);
+            Arc::make_mut(&mut self.vocab).insert(token.to_string(), id);
+            Arc::make_mut(&mut self.root_matcher).add(&self.roots, token, id);
+            let mut forms = self.decoder.forms(id).unwrap_or_default().to_vec();
+            forms.push(token.to_string());
+            Arc::make_mut(&mut self.decoder).set_forms(id, forms);
+            self.user_tokens.push((token.to_string(), id));
+            ids.push(id);
+        }
+        self.word_cache.clear();
+        Ok(ids)
+    }
+
+    /// Entries added with `add_user_tokens`, in the order they were added
+    pub fn user_tokens(&self) -> &[(String, u32)] {
+        &self.user_tokens
+    }
+
+    pub(crate) fn save_user_tokens(&self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: serde_json::Map<String, serde_json::Value> = self
+            .user_tokens
+            .iter()
+            .map(|(token, id)| (token.clone(), (*id).into()))
+            .collect();
+        fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn load_user_tokens(&mut self, path: impl AsRef<Path>) -> Result<(), TurkishTokenizerError> {
+        let entries: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut tokens = Vec::with_capacity(entries.len());
+        for (token, id) in &entries {
+            tokens.push((token.as_str(), Some(serde_json::from_value(id.clone())?)));
+        }
+        self.add_user_tokens(&tokens)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+//! HTML rendering of a tokenization, for reports and teaching material.
+//!
+//! Each token becomes a `<span>` with a `tt-root`, `tt-suffix`, `tt-bpe`,
+//! `tt-byte` or `tt-special` class. Its background color is set inline, so
+//! the snippet can be pasted anywhere without a stylesheet. Hovering a token
+//! shows its ID and type. Case markers, BOS/EOS and the other special tokens
+//! count as special, whatever table they come from.
+
+use crate::{TokenType, TurkishTokenizer};
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl TurkishTokenizer {
+    /// HTML snippet showing how `text` is tokenized, one colored span per
+    /// token with its ID and type in the tooltip
+    pub fn visualize(&self, text: &str) -> String {
+        let tokens = self.tokenize_text(text);
+        let ids: Vec<u32> = tokens.iter().map(|token| token.id).collect();
+        let markers = [
+            Some(&self.uppercase_marker),
+            self.all_caps_marker.as_ref(),
+            self.number_marker.as_ref(),
+            Some(&self.unknown_marker),
+        ];
+        let marker_ids: Vec<u32> = markers.into_iter().flatten().chain(self.entity_markers()).map(|marker| marker.id).collect();
+        let special_mask = self.get_special_tokens_mask(&ids);
+
+        let mut html = String::from(
+            "<div class=\"tt-tokens\" style=\"font-family:monospace;white-space:pre-wrap;line-height:2\">",
+        );
+        for (token, special) in tokens.iter().zip(special_mask) {
+            let (class, color) = if special == 1 || marker_ids.contains(&token.id) {
+                ("special", "#e1bee7")
+            } else {
+                match token.token_type {
+                    TokenType::Root => ("root", "#c8e6c9"),
+                    TokenType::Suffix => ("suffix", "#b3e5fc"),
+                    TokenType::Bpe => ("bpe", "#fff9c4"),
+                    TokenType::Byte => ("byte", "#ffcdd2"),
+                }
+            };
+            html.push_str(&format!(
+                "<span class=\"tt-token tt-{class}\" style=\"background:{color};border-radius:3px;margin:0 1px;padding:1px 2px\" title=\"ID {id}, {class}\">{token}</span>",
+                class = class,
+                color = color,
+                id = token.id,
+                token = escape_html(&token.token),
+            ));
+        }
+        html.push_str("</div>");
+        html
+    }
+}
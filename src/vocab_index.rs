@@ -0,0 +1,81 @@
+//! Prefix queries over the vocabulary for constrained decoding, which
+//! needs the tokens that fit the text a generation must continue with.
+//!
+//! Entries are the vocabulary strings as matched, lowercase, with case
+//! carried by the `<uppercase>` and `<all_caps>` markers. Byte tokens are
+//! indexed by their byte instead of their `<0xNN>` string, since they
+//! spell any text one UTF-8 byte at a time.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::matcher::PrefixMatcher;
+use crate::trie::PrefixTrie;
+use crate::TurkishTokenizer;
+
+/// Vocabulary trie answering which token IDs start with or continue a
+/// string. Created by [`TurkishTokenizer::vocab_index`]; it is a snapshot
+/// and does not see tokens added afterwards.
+///
+/// ```
+/// use turkish_tokenizer::TurkishTokenizer;
+///
+/// let tokenizer = TurkishTokenizer::new().unwrap();
+/// let index = tokenizer.vocab_index();
+/// let kitap = tokenizer.get_vocab()["kitap"];
+/// assert!(index.ids_with_prefix("kit").contains(&kitap));
+/// assert!(index.ids_continuing("kitaplar").contains(&kitap));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python", pyclass(module = "turkish_tokenizer_rs", frozen))]
+pub struct VocabIndex {
+    trie: PrefixTrie,
+    /// ID of byte 0, unless byte fallback is disabled
+    byte_offset: Option<u32>,
+}
+
+impl VocabIndex {
+    /// Sorted IDs of the entries starting with `prefix`; all text entries
+    /// for an empty prefix
+    pub fn ids_with_prefix(&self, prefix: &str) -> Vec<u32> {
+        let mut ids = self.trie.ids_with_prefix(prefix);
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Sorted IDs of the tokens that can come next when the output must
+    /// continue with `text`: entries that are a prefix of it, entries
+    /// starting with it, and the byte token of its first byte
+    pub fn ids_continuing(&self, text: &str) -> Vec<u32> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut ids = self.trie.ids_with_prefix(text);
+        ids.extend(self.trie.prefixes(&chars).map(|(id, _)| id));
+        if let Some(offset) = self.byte_offset {
+            match text.as_bytes().first() {
+                Some(&byte) => ids.push(offset + u32::from(byte)),
+                None => ids.extend(offset..offset + 256),
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+impl TurkishTokenizer {
+    /// Index the current vocabulary for prefix queries
+    pub fn vocab_index(&self) -> VocabIndex {
+        let bytes = self.byte_offset..self.byte_offset + 256;
+        let mut trie = PrefixTrie::new();
+        for (token, &id) in self.vocab.iter() {
+            if !bytes.contains(&id) {
+                trie.insert(token, id);
+            }
+        }
+        VocabIndex {
+            trie,
+            byte_offset: self.byte_fallback().then_some(self.byte_offset),
+        }
+    }
+}
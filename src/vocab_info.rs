@@ -0,0 +1,111 @@
+//! Token-to-ID snapshots for checking that a tokenizer update keeps the
+//! IDs a trained model's embeddings were learned for.
+//!
+//! A new vocabulary is compatible with an old one when every old token
+//! keeps its ID. Adding tokens is fine; removing a token or moving it to
+//! another ID silently changes what the model sees.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::TurkishTokenizer;
+
+/// Every token of a vocabulary with its ID, including byte, user, added
+/// and special tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VocabInfo {
+    tokens: HashMap<String, u32>,
+}
+
+/// A token whose ID differs between two vocabularies
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedId {
+    pub token: String,
+    pub old_id: u32,
+    pub new_id: u32,
+}
+
+/// Differences from an old vocabulary to a new one, each list ordered by ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VocabDiff {
+    /// Tokens only in the new vocabulary
+    pub added: Vec<(String, u32)>,
+    /// Tokens only in the old vocabulary
+    pub removed: Vec<(String, u32)>,
+    /// Tokens in both under different IDs
+    pub changed: Vec<ChangedId>,
+}
+
+impl VocabDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Whether IDs produced with the old vocabulary mean the same in the
+    /// new one, i.e. nothing was removed or moved
+    pub fn is_compatible(&self) -> bool {
+        self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl VocabInfo {
+    pub fn new(tokens: HashMap<String, u32>) -> Self {
+        VocabInfo { tokens }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn get(&self, token: &str) -> Option<u32> {
+        self.tokens.get(token).copied()
+    }
+
+    /// What changed going from this vocabulary to `other`
+    pub fn diff(&self, other: &VocabInfo) -> VocabDiff {
+        let mut diff = VocabDiff::default();
+        for (token, &old_id) in &self.tokens {
+            match other.get(token) {
+                None => diff.removed.push((token.clone(), old_id)),
+                Some(new_id) if new_id != old_id => diff.changed.push(ChangedId {
+                    token: token.clone(),
+                    old_id,
+                    new_id,
+                }),
+                Some(_) => {}
+            }
+        }
+        for (token, &new_id) in &other.tokens {
+            if !self.tokens.contains_key(token) {
+                diff.added.push((token.clone(), new_id));
+            }
+        }
+        diff.added.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+        diff.removed.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+        diff.changed.sort_by(|a, b| (a.old_id, &a.token).cmp(&(b.old_id, &b.token)));
+        diff
+    }
+
+    /// Whether a model trained on this vocabulary can use `other`: every
+    /// token here keeps its ID there. `other` may add tokens.
+    pub fn is_compatible_with(&self, other: &VocabInfo) -> bool {
+        self.tokens.iter().all(|(token, &id)| other.get(token) == Some(id))
+    }
+}
+
+impl TurkishTokenizer {
+    /// Snapshot of the current token IDs, for `VocabInfo::diff`
+    pub fn vocab_info(&self) -> VocabInfo {
+        VocabInfo::new(HashMap::clone(&self.vocab))
+    }
+
+    /// Whether IDs from this tokenizer mean the same under `other`; see
+    /// `VocabInfo::is_compatible_with`
+    pub fn is_compatible_with(&self, other: &TurkishTokenizer) -> bool {
+        self.vocab.iter().all(|(token, id)| other.vocab.get(token) == Some(id))
+    }
+}
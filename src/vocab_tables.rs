@@ -0,0 +1,35 @@
+//! Vocabulary tables in file order, shared with the build script that
+//! precompiles the embedded vocabulary to bincode.
+
+use serde::{Deserialize, Serialize};
+
+/// `(surface form, ID)` entries of the root, suffix and BPE tables.
+/// Order matters: forms sharing an ID are chosen by position when decoding.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct VocabTables {
+    pub roots: Vec<(String, u32)>,
+    pub suffixes: Vec<(String, u32)>,
+    pub bpe_tokens: Vec<(String, u32)>,
+}
+
+impl VocabTables {
+    pub fn from_json(roots_json: &str, suffixes_json: &str, bpe_tokens_json: &str) -> Result<Self, serde_json::Error> {
+        Ok(VocabTables {
+            roots: table_entries(roots_json)?,
+            suffixes: table_entries(suffixes_json)?,
+            bpe_tokens: table_entries(bpe_tokens_json)?,
+        })
+    }
+
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+}
+
+fn table_entries(json: &str) -> Result<Vec<(String, u32)>, serde_json::Error> {
+    let entries: serde_json::Map<String, serde_json::Value> = serde_json::from_str(json)?;
+    entries
+        .into_iter()
+        .map(|(token, id)| Ok((token, serde_json::from_value(id)?)))
+        .collect()
+}
@@ -0,0 +1,53 @@
+//! JavaScript bindings for wasm32 targets, built with the `wasm` feature.
+//!
+//! `new TurkishTokenizer()` uses the vocabulary compiled into the wasm
+//! binary. `fromJson` and `fromBytes` build a tokenizer from vocabulary
+//! fetched at runtime instead.
+
+use wasm_bindgen::prelude::*;
+
+use crate::TurkishTokenizer;
+
+#[wasm_bindgen(js_name = TurkishTokenizer)]
+pub struct WasmTokenizer {
+    inner: TurkishTokenizer,
+}
+
+#[wasm_bindgen(js_class = TurkishTokenizer)]
+impl WasmTokenizer {
+    /// Tokenizer with the embedded vocabulary
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<WasmTokenizer, JsError> {
+        Ok(Self { inner: TurkishTokenizer::new()? })
+    }
+
+    /// Tokenizer built from the contents of `kokler.json`, `ekler.json` and
+    /// `bpe_tokenler.json`
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(roots_json: &str, suffixes_json: &str, bpe_tokens_json: &str) -> Result<WasmTokenizer, JsError> {
+        Ok(Self { inner: TurkishTokenizer::from_json(roots_json, suffixes_json, bpe_tokens_json)? })
+    }
+
+    /// Tokenizer restored from a `to_bytes` snapshot
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmTokenizer, JsError> {
+        Ok(Self { inner: TurkishTokenizer::from_bytes(bytes)? })
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        self.inner.encode(text)
+    }
+
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.inner.tokenize(text)
+    }
+
+    pub fn decode(&self, ids: &[u32]) -> String {
+        self.inner.decode(ids)
+    }
+
+    #[wasm_bindgen(js_name = countTokens)]
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.inner.count_tokens(text)
+    }
+}
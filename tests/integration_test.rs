@@ -1,8 +1,8 @@
-use turkish_tokenizer::{TurkishTokenizer, TokenType};
+use turkish_tokenizer::{PostProcessor, TemplateProcessing, TurkishTokenizer, TokenType, WhitespaceMode};
 
 #[test]
 fn test_comprehensive_tokenization() {
-    let tokenizer = TurkishTokenizer::new_rust().unwrap();
+    let tokenizer = TurkishTokenizer::new().unwrap();
     
     // Test various Turkish linguistic features
     let test_cases = vec![
@@ -36,7 +36,7 @@ fn test_comprehensive_tokenization() {
 
 #[test]
 fn test_special_tokens() {
-    let tokenizer = TurkishTokenizer::new_rust().unwrap();
+    let tokenizer = TurkishTokenizer::new().unwrap();
     
     // Test that special tokens are properly handled
     assert!(tokenizer.contains_token("<pad>"));
@@ -52,7 +52,7 @@ fn test_special_tokens() {
 
 #[test]
 fn test_token_type_distribution() {
-    let tokenizer = TurkishTokenizer::new_rust().unwrap();
+    let tokenizer = TurkishTokenizer::new().unwrap();
     
     // Test a word that should have different token types
     let tokens = tokenizer.tokenize_text("kitaplarımı");
@@ -64,7 +64,7 @@ fn test_token_type_distribution() {
         match token.token_type {
             TokenType::Root => has_root = true,
             TokenType::Suffix => has_suffix = true,
-            TokenType::Bpe => {},
+            TokenType::Bpe | TokenType::Byte => {},
         }
     }
     
@@ -74,7 +74,7 @@ fn test_token_type_distribution() {
 
 #[test]
 fn test_encoding_consistency() {
-    let tokenizer = TurkishTokenizer::new_rust().unwrap();
+    let tokenizer = TurkishTokenizer::new().unwrap();
     
     let text = "Türkçe çok güzel";
     
@@ -95,7 +95,7 @@ fn test_encoding_consistency() {
 
 #[test]
 fn test_empty_and_edge_cases() {
-    let tokenizer = TurkishTokenizer::new_rust().unwrap();
+    let tokenizer = TurkishTokenizer::new().unwrap();
     
     // Empty string
     let tokens = tokenizer.tokenize("");
@@ -116,3 +116,688 @@ fn test_empty_and_edge_cases() {
     let tokens = tokenizer.tokenize("a");
     assert!(!tokens.is_empty());
 }
+
+#[test]
+fn test_load_from_files() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/turkish_tokenizer");
+    let from_dir = TurkishTokenizer::from_dir(dir).unwrap();
+    let embedded = TurkishTokenizer::new().unwrap();
+
+    let text = "Kitaplarımızdan geliyorum";
+    assert_eq!(from_dir.encode(text), embedded.encode(text));
+    assert_eq!(from_dir.vocab_size(), embedded.vocab_size());
+    // The precompiled vocabulary keeps the order of forms sharing an ID
+    let ids = embedded.encode("evlerde kitaplarda gördüklerimiz");
+    assert_eq!(from_dir.decode(&ids), embedded.decode(&ids));
+
+    assert!(TurkishTokenizer::from_dir("/nonexistent/vocab/dir").is_err());
+}
+
+#[test]
+fn test_save_and_load_pretrained() {
+    let dir = std::env::temp_dir().join(format!("turkish-tokenizer-pretrained-{}", std::process::id()));
+    let mut tokenizer = TurkishTokenizer::builder()
+        .bos_token("<s>")
+        .emit_case_markers(false)
+        .post_processor(PostProcessor::Template(
+            TemplateProcessing::new("<s> $A </s>", "<s> $A </s> $B </s>").unwrap(),
+        ))
+        .build()
+        .unwrap();
+    tokenizer.add_user_tokens(&[("kardiyomiyopati", None)]).unwrap();
+    tokenizer.add_tokens(&["covid-19"]);
+    tokenizer.add_special_tokens(&["<|im_start|>"]).unwrap();
+    tokenizer.add_emoji_tokens(&["☕", "🥳"]);
+    tokenizer.add_abbreviations(&["T.C.", "vb."]);
+    let scores = tokenizer.train_unigram_scores(["kitaplarımızdan geliyorum"].into_iter(), 3);
+    tokenizer.set_scores(scores);
+    tokenizer.save_pretrained(&dir).unwrap();
+
+    assert!(dir.join(turkish_tokenizer::TOKENIZER_CONFIG_FILE).exists());
+    assert!(dir.join(turkish_tokenizer::SPECIAL_TOKENS_MAP_FILE).exists());
+    assert!(dir.join(turkish_tokenizer::SCORES_FILE).exists());
+    assert!(dir.join(turkish_tokenizer::EMOJI_TOKENS_FILE).exists());
+    assert!(dir.join(turkish_tokenizer::ABBREVIATIONS_FILE).exists());
+
+    let loaded = TurkishTokenizer::from_pretrained(&dir).unwrap();
+    let text = "<|im_start|>Kitaplarımızdan geliyorum ☕🥳 kardiyomiyopati covid-19 T.C. vb.";
+    assert_eq!(loaded.encode(text), tokenizer.encode(text));
+    assert_eq!(loaded.user_tokens(), tokenizer.user_tokens());
+    assert_eq!(loaded.get_added_vocab(), tokenizer.get_added_vocab());
+    assert_eq!(loaded.encode_plus_with(text, true).input_ids, tokenizer.encode_plus_with(text, true).input_ids);
+    assert_eq!(loaded.decode(&loaded.encode(text)), tokenizer.decode(&tokenizer.encode(text)));
+    assert_eq!(loaded.tokenizer_config(), tokenizer.tokenizer_config());
+    assert_eq!(loaded.bos_token_id, tokenizer.bos_token_id);
+    assert_eq!(loaded.get_vocab(), tokenizer.get_vocab());
+    assert_eq!(loaded.scores(), tokenizer.scores());
+    assert_eq!(loaded.emoji_tokens(), tokenizer.emoji_tokens());
+    assert_eq!(loaded.abbreviations(), tokenizer.abbreviations());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_tokenizer_config() {
+    let dir = std::env::temp_dir().join(format!("turkish-tokenizer-config-{}", std::process::id()));
+    let tokenizer = TurkishTokenizer::builder().model_max_length(512).build().unwrap();
+    tokenizer.write_tokenizer_config(&dir).unwrap();
+
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join(turkish_tokenizer::TOKENIZER_CONFIG_FILE)).unwrap())
+            .unwrap();
+    assert_eq!(config["tokenizer_class"], turkish_tokenizer::TOKENIZER_CLASS);
+    assert_eq!(config["model_max_length"], 512);
+    assert_eq!(config["eos_token"], tokenizer.eos_token.as_str());
+    assert_eq!(config["unk_token"], "<unknown>");
+
+    // Configs written before these keys existed still load
+    let mut legacy = config.clone();
+    legacy.as_object_mut().unwrap().retain(|key, _| key != "tokenizer_class" && key != "model_max_length");
+    let legacy: turkish_tokenizer::TokenizerConfig = serde_json::from_value(legacy).unwrap();
+    assert_eq!(legacy.tokenizer_class, turkish_tokenizer::TOKENIZER_CLASS);
+    assert_eq!(legacy.model_max_length, None);
+
+    tokenizer.save_pretrained(&dir).unwrap();
+    let loaded = TurkishTokenizer::from_pretrained(&dir).unwrap();
+    assert_eq!(loaded.model_max_length, Some(512));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    let mut tokenizer = TurkishTokenizer::builder()
+        .whitespace_mode(WhitespaceMode::Collapse)
+        .word_cache_capacity(16)
+        .build()
+        .unwrap();
+    tokenizer.add_user_tokens(&[("kardiyomiyopati", None)]).unwrap();
+    tokenizer.add_tokens(&["covid-19"]);
+    tokenizer.add_special_tokens(&["<|im_end|>"]).unwrap();
+
+    let restored = TurkishTokenizer::from_bytes(&tokenizer.to_bytes().unwrap()).unwrap();
+    let text = "Kitaplarımızdan geliyorum ☕ kardiyomiyopati covid-19<|im_end|>";
+    assert_eq!(restored.encode(text), tokenizer.encode(text));
+    assert_eq!(restored.tokenizer_config(), tokenizer.tokenizer_config());
+    assert_eq!(restored.get_vocab(), tokenizer.get_vocab());
+    assert_eq!(restored.get_added_vocab(), tokenizer.get_added_vocab());
+    assert_eq!(restored.word_cache_capacity(), 16);
+    assert!(TurkishTokenizer::from_bytes(b"not a tokenizer").is_err());
+}
+
+/// Reader returning at most `chunk` bytes per call
+struct SmallReads<'a> {
+    data: &'a [u8],
+    chunk: usize,
+}
+
+impl std::io::Read for SmallReads<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.chunk.min(buf.len()).min(self.data.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn test_tokenize_stream() {
+    let mut tokenizer = TurkishTokenizer::new().unwrap();
+    let text = "Kitaplarımızdan  geliyorum.\nBugün çok\t\tgüzel bir gün ☕ \n";
+    for mode in [WhitespaceMode::Space, WhitespaceMode::Preserve, WhitespaceMode::Collapse] {
+        tokenizer.set_whitespace_mode(mode);
+        let expected: Vec<_> = tokenizer
+            .tokenize_text(text)
+            .into_iter()
+            .map(|t| (t.token, t.id, t.start, t.end, t.word_id))
+            .collect();
+
+        for chunk in [1, 3, 7, 1024] {
+            let reader = SmallReads { data: text.as_bytes(), chunk };
+            let streamed: Vec<_> = tokenizer
+                .tokenize_stream(reader)
+                .map(|t| t.map(|t| (t.token, t.id, t.start, t.end, t.word_id)))
+                .collect::<std::io::Result<_>>()
+                .unwrap();
+            assert_eq!(streamed, expected, "{:?}, chunk size {}", mode, chunk);
+        }
+    }
+
+    let invalid = SmallReads { data: b"merhaba \xff\xfe", chunk: 4 };
+    assert!(tokenizer.tokenize_stream(invalid).any(|t| t.is_err()));
+}
+
+#[cfg(feature = "tokenizers")]
+#[test]
+fn test_hf_pipeline_matches_encode() {
+    for mode in [WhitespaceMode::Space, WhitespaceMode::Preserve, WhitespaceMode::Collapse] {
+        let mut tokenizer = TurkishTokenizer::new().unwrap();
+        tokenizer.set_whitespace_mode(mode);
+        let hf_tokenizer = tokenizer.to_hf_tokenizer();
+
+        let text = "Kitaplarımızdan  birini\tİstanbul'a\ngötürdüm 🙂";
+        let encoding = hf_tokenizer.encode(text, false).unwrap();
+        assert_eq!(encoding.get_ids(), tokenizer.encode(text), "{:?}", mode);
+
+        // Offsets come back as byte ranges into the input
+        let tokens = tokenizer.tokenize_text(text);
+        let chars: Vec<char> = text.chars().collect();
+        for (token, &(start, end)) in tokens.iter().zip(encoding.get_offsets()) {
+            assert_eq!(text[start..end], chars[token.start..token.end].iter().collect::<String>());
+        }
+
+        assert_eq!(hf_tokenizer.decode(encoding.get_ids(), false).unwrap(), tokenizer.decode(encoding.get_ids()));
+    }
+}
+
+#[test]
+fn test_export_gguf_vocab() {
+    #[derive(Debug, PartialEq)]
+    enum Value {
+        Int(i64),
+        Str(String),
+        Other,
+    }
+
+    struct Cursor<'a>(&'a [u8]);
+
+    impl Cursor<'_> {
+        fn take(&mut self, len: usize) -> &[u8] {
+            let (head, tail) = self.0.split_at(len);
+            self.0 = tail;
+            head
+        }
+        fn u32(&mut self) -> u32 {
+            u32::from_le_bytes(self.take(4).try_into().unwrap())
+        }
+        fn u64(&mut self) -> u64 {
+            u64::from_le_bytes(self.take(8).try_into().unwrap())
+        }
+        fn string(&mut self) -> String {
+            let len = self.u64() as usize;
+            String::from_utf8(self.take(len).to_vec()).unwrap()
+        }
+        fn value(&mut self, value_type: u32) -> Value {
+            match value_type {
+                4 => Value::Int(i64::from(self.u32())),
+                5 => Value::Int(i64::from(self.u32() as i32)),
+                6 | 7 => {
+                    self.take(if value_type == 6 { 4 } else { 1 });
+                    Value::Other
+                }
+                8 => Value::Str(self.string()),
+                other => panic!("unexpected GGUF type {}", other),
+            }
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!("turkish-tokenizer-{}.gguf", std::process::id()));
+    let mut tokenizer = TurkishTokenizer::new().unwrap();
+    tokenizer.add_special_tokens(&["<|im_start|>"]).unwrap();
+    tokenizer.export_gguf_vocab(&path).unwrap();
+    let data = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(data.len() % 32, 0);
+
+    let mut cursor = Cursor(&data);
+    assert_eq!(cursor.take(4), b"GGUF");
+    assert_eq!(cursor.u32(), 3);
+    assert_eq!(cursor.u64(), 0);
+    let mut metadata = std::collections::HashMap::new();
+    for _ in 0..cursor.u64() {
+        let key = cursor.string();
+        let values = match cursor.u32() {
+            9 => {
+                let element_type = cursor.u32();
+                (0..cursor.u64()).map(|_| cursor.value(element_type)).collect()
+            }
+            value_type => vec![cursor.value(value_type)],
+        };
+        metadata.insert(key, values);
+    }
+    assert!(cursor.0.iter().all(|&b| b == 0));
+
+    let tokens = &metadata["tokenizer.ggml.tokens"];
+    let types = &metadata["tokenizer.ggml.token_type"];
+    assert_eq!(metadata["tokenizer.ggml.model"], [Value::Str("llama".to_string())]);
+    assert_eq!(tokens.len(), *tokenizer.get_vocab().values().max().unwrap() as usize + 1);
+    assert_eq!(types.len(), tokens.len());
+    assert_eq!(metadata["tokenizer.ggml.scores"].len(), tokens.len());
+
+    let id = |token: &str| tokenizer.token_to_id(token).unwrap() as usize;
+    assert_eq!(tokens[id("kitap")], Value::Str("kitap".to_string()));
+    assert_eq!(tokens[id(" ")], Value::Str("▁".to_string()));
+    let type_of = |token: &str| &types[id(token)];
+    assert_eq!(type_of("kitap"), &Value::Int(1));
+    assert_eq!(type_of("<unknown>"), &Value::Int(2));
+    assert_eq!(type_of("<eos>"), &Value::Int(3));
+    assert_eq!(type_of("<|im_start|>"), &Value::Int(3));
+    assert_eq!(type_of("<0xE2>"), &Value::Int(6));
+    assert_eq!(metadata["tokenizer.ggml.eos_token_id"], [Value::Int(i64::from(tokenizer.eos_token_id))]);
+}
+
+#[test]
+fn test_tiktoken_export_and_encoding() {
+    use std::collections::HashSet;
+
+    let mut tokenizer = TurkishTokenizer::new().unwrap();
+    tokenizer.add_special_tokens(&["<|im_start|>"]).unwrap();
+
+    let path = std::env::temp_dir().join(format!("turkish-tokenizer-{}.tiktoken", std::process::id()));
+    tokenizer.export_tiktoken(&path).unwrap();
+    let ranks = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut seen = HashSet::new();
+    let mut ids = Vec::new();
+    for line in ranks.lines() {
+        let (token, id) = line.split_once(' ').unwrap();
+        assert!(seen.insert(token.to_string()), "duplicate rank for {}", token);
+        ids.push(id.parse::<u32>().unwrap());
+    }
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    // "kitap" in base64
+    assert!(ranks.contains(&format!("a2l0YXA= {}\n", tokenizer.token_to_id("kitap").unwrap())));
+    assert!(!ids.contains(&tokenizer.eos_token_id));
+    assert!(!ids.contains(&tokenizer.token_to_id("<|im_start|>").unwrap()));
+
+    let bpe = tokenizer.to_tiktoken();
+    let text = "<|im_start|>Merhaba<eos>";
+    let im_start = tokenizer.token_to_id("<|im_start|>").unwrap();
+    let with_special = bpe.encode_with_special_tokens(text);
+    assert_eq!(with_special.first(), Some(&im_start));
+    assert_eq!(with_special.last(), Some(&tokenizer.eos_token_id));
+
+    let ordinary = bpe.encode_ordinary(text);
+    assert!(!ordinary.contains(&im_start) && !ordinary.contains(&tokenizer.eos_token_id));
+    assert_eq!(bpe.decode(&ordinary), text);
+
+    let only_eos = bpe.encode(text, &HashSet::from(["<eos>"]));
+    assert!(!only_eos.contains(&im_start));
+    assert_eq!(only_eos.last(), Some(&tokenizer.eos_token_id));
+}
+
+#[cfg(feature = "serve")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_server() {
+    use std::io::{Read, Write};
+    use std::sync::Arc;
+
+    let tokenizer = Arc::new(TurkishTokenizer::new().unwrap());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(turkish_tokenizer::server::serve(listener, tokenizer.clone()));
+
+    let request = |method: &str, path: &str, body: &str| {
+        let raw = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        );
+        let response = tokio::task::spawn_blocking(move || {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            stream.write_all(raw.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        async move {
+            let response = response.await.unwrap();
+            let (head, body) = response.split_once("\r\n\r\n").unwrap();
+            (head.split(' ').nth(1).unwrap().to_string(), body.to_string())
+        }
+    };
+
+    assert_eq!(request("GET", "/healthz", "").await, ("200".to_string(), "ok".to_string()));
+
+    let (status, body) = request("POST", "/encode", r#"{"text": "kitaplar"}"#).await;
+    assert_eq!(status, "200");
+    let ids: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(ids["ids"], serde_json::json!(tokenizer.encode("kitaplar")));
+
+    let (_, body) = request("POST", "/encode", r#"{"text": ["ev", "Merhaba dünya"]}"#).await;
+    let batch: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(batch["ids"], serde_json::json!([tokenizer.encode("ev"), tokenizer.encode("Merhaba dünya")]));
+
+    let decode_body = serde_json::json!({"ids": [tokenizer.encode("Merhaba dünya")]}).to_string();
+    let (_, body) = request("POST", "/decode", &decode_body).await;
+    assert_eq!(body, r#"{"text":["Merhaba dünya"]}"#);
+
+    let (_, body) = request("POST", "/tokenize", r#"{"text": "evler"}"#).await;
+    let tokens: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(tokens["tokens"][1]["token"], "ler");
+
+    let (status, _) = request("POST", "/encode", r#"{"ids": [1]}"#).await;
+    assert_eq!(status, "422");
+
+    let (status, metrics) = request("GET", "/metrics", "").await;
+    assert_eq!(status, "200");
+    let encoded = ["kitaplar", "ev", "Merhaba dünya"].map(|text| tokenizer.encode(text).len()).iter().sum::<usize>();
+    assert!(metrics.contains("turkish_tokenizer_requests_total{endpoint=\"/encode\"} 2\n"));
+    assert!(metrics.contains(&format!("turkish_tokenizer_tokens_total{{endpoint=\"/encode\"}} {}\n", encoded)));
+    assert!(metrics.contains("turkish_tokenizer_batch_size_bucket{endpoint=\"/encode\",le=\"1\"} 1\n"));
+    assert!(metrics.contains("turkish_tokenizer_request_duration_seconds_count{endpoint=\"/decode\"} 1\n"));
+
+    // Characters outside the vocabulary come out as byte tokens
+    let unknown_count = |metrics: &str| -> u64 {
+        let line = "turkish_tokenizer_unknown_tokens_total{endpoint=\"/encode\"} ";
+        metrics.lines().find_map(|l| l.strip_prefix(line)).unwrap().parse().unwrap()
+    };
+    let before = unknown_count(&metrics);
+    request("POST", "/encode", r#"{"text": "kitap 𓀀"}"#).await;
+    let (_, metrics) = request("GET", "/metrics", "").await;
+    assert!(unknown_count(&metrics) > before);
+}
+
+#[cfg(feature = "grpc")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_grpc_server() {
+    use std::sync::Arc;
+    use turkish_tokenizer::grpc::proto::{
+        self, tokenizer_client::TokenizerClient, DecodeBatchRequest, DecodeRequest, TokenizeBatchRequest,
+        TokenizeRequest,
+    };
+
+    let tokenizer = Arc::new(TurkishTokenizer::new().unwrap());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(turkish_tokenizer::grpc::serve(listener, tokenizer.clone()));
+    let mut client = TokenizerClient::connect(format!("http://{}", addr)).await.unwrap();
+
+    let response = client
+        .tokenize(TokenizeRequest {
+            text: "Kitaplarımızdan".to_string(),
+            add_special_tokens: false,
+            return_tokens: true,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.ids, tokenizer.encode("Kitaplarımızdan"));
+    assert_eq!(response.tokens[1].token, "kitap");
+    assert_eq!(response.tokens[1].token_type, proto::TokenType::Root as i32);
+    assert_eq!(response.tokens[1].word_id, Some(0));
+
+    let batch = client
+        .tokenize_batch(TokenizeBatchRequest {
+            texts: vec!["ev".to_string(), "merhaba dünya".to_string()],
+            add_special_tokens: true,
+            return_tokens: false,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(batch.results[1].ids, tokenizer.encode_with("merhaba dünya", true));
+    assert!(batch.results[1].tokens.is_empty());
+
+    let ids = tokenizer.encode("Merhaba dünya");
+    let decoded = client
+        .decode(DecodeRequest {
+            ids: ids.clone(),
+            skip_special_tokens: false,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(decoded.text, "Merhaba dünya");
+
+    let requests = ["ev", "kitap"].map(|text| TokenizeBatchRequest {
+        texts: vec![text.to_string()],
+        add_special_tokens: false,
+        return_tokens: false,
+    });
+    let mut stream = client
+        .tokenize_batch_stream(tokio_stream::iter(requests))
+        .await
+        .unwrap()
+        .into_inner();
+    for text in ["ev", "kitap"] {
+        let response = stream.message().await.unwrap().unwrap();
+        assert_eq!(response.results[0].ids, tokenizer.encode(text));
+    }
+    assert!(stream.message().await.unwrap().is_none());
+
+    let requests = [DecodeBatchRequest {
+        sequences: vec![proto::Ids { ids }],
+        skip_special_tokens: false,
+    }];
+    let mut stream = client
+        .decode_batch_stream(tokio_stream::iter(requests))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(stream.message().await.unwrap().unwrap().texts, ["Merhaba dünya"]);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_tokenize_corpus() {
+    use std::process::Command;
+
+    let dir = std::env::temp_dir().join(format!("turkish-tokenizer-corpus-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("a.txt"), "Merhaba dünya\n\nkitaplarımı okudum\n").unwrap();
+    std::fs::write(dir.join("nested/b.txt"), "geliyorum").unwrap();
+    let output = dir.join("out.jsonl");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_turkish-tokenizer"))
+        .arg("tokenize-corpus")
+        .arg("--input")
+        .arg(&dir)
+        .arg("--output")
+        .arg(&output)
+        .args(["--threads", "2"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let jsonl = std::fs::read_to_string(&output).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let tokenizer = TurkishTokenizer::new().unwrap();
+    let records: Vec<serde_json::Value> = jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    let texts: Vec<&str> = records.iter().map(|record| record["text"].as_str().unwrap()).collect();
+    assert_eq!(texts, ["Merhaba dünya", "kitaplarımı okudum", "geliyorum"]);
+    for (record, text) in records.iter().zip(texts) {
+        assert_eq!(record["input_ids"], serde_json::json!(tokenizer.encode(text)));
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_encode_pipe() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_turkish-tokenizer"))
+        .args(["encode", "--format", "ids"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all("Merhaba dünya\n\nkitaplarımı\n".as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let tokenizer = TurkishTokenizer::new().unwrap();
+    let expected: Vec<String> = ["Merhaba dünya", "", "kitaplarımı"]
+        .iter()
+        .map(|text| tokenizer.encode(text).iter().map(u32::to_string).collect::<Vec<_>>().join(" "))
+        .collect();
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().collect::<Vec<_>>(), expected);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_repl_table() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_turkish-tokenizer"))
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"kitaplar\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let table = String::from_utf8(output.stdout).unwrap();
+    // Not a terminal, so no color codes
+    assert!(!table.contains('\x1b'));
+    let rows: Vec<Vec<&str>> = table.lines().map(|line| line.split_whitespace().collect()).collect();
+    assert_eq!(rows[0], ["TOKEN", "ID", "TYPE", "SPAN", "WORD"]);
+    let tokenizer = TurkishTokenizer::new().unwrap();
+    let kitap = tokenizer.token_to_id("kitap").unwrap().to_string();
+    assert_eq!(rows[1], ["\"kitap\"", kitap.as_str(), "root", "0..5", "0"]);
+    assert_eq!(rows[2][2], "suffix");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_coverage() {
+    use std::process::Command;
+
+    let path = std::env::temp_dir().join(format!("turkish-tokenizer-coverage-{}.txt", std::process::id()));
+    std::fs::write(&path, "Kitaplar 😀\n\nev\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_turkish-tokenizer"))
+        .args(["coverage", "--json", "--input"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let expected = TurkishTokenizer::new().unwrap().coverage(["Kitaplar 😀", "ev"]);
+    assert_eq!(report["texts"], 2);
+    assert_eq!(report["tokens"], expected.tokens);
+    assert_eq!(report["oov_char_classes"]["emoji"], 1);
+    assert_eq!(report["unknown_rate"], expected.unknown_rate());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_stats() {
+    use std::process::Command;
+
+    let path = std::env::temp_dir().join(format!("turkish-tokenizer-stats-{}.txt", std::process::id()));
+    std::fs::write(&path, "kitap kitaplar\nev\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_turkish-tokenizer"))
+        .args(["stats", "--input"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+
+    let tokenizer = TurkishTokenizer::new().unwrap();
+    let histogram: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tokens = histogram["tokens"].as_array().unwrap();
+    assert_eq!(histogram["unique_tokens"], tokens.len());
+    assert_eq!(histogram["total_tokens"], tokenizer.encode("kitap kitaplar").len() + tokenizer.encode("ev").len());
+    assert!(tokens.windows(2).all(|pair| pair[0]["count"].as_u64() >= pair[1]["count"].as_u64()));
+    let kitap = tokens.iter().find(|token| token["token"] == "kitap").unwrap();
+    assert_eq!(kitap["count"], 2);
+    assert_eq!(kitap["id"], tokenizer.token_to_id("kitap").unwrap());
+}
+
+#[test]
+fn test_prune_vocab() {
+    let mut tokenizer = TurkishTokenizer::new().unwrap();
+    tokenizer.add_user_tokens(&[("kovid", None)]).unwrap();
+    tokenizer.add_tokens(&["<kod>"]);
+    tokenizer.add_special_tokens(&["<|im_start|>"]).unwrap();
+
+    let corpus = ["Merhaba dünya, kitaplarımı okudum.", "kovid <kod> xyzq 😀", "<|im_start|>ev"];
+    let counts = tokenizer.count_token_frequencies(corpus.iter().copied());
+    let (pruned, remapping) = tokenizer.prune_vocab(&counts, 1).unwrap();
+
+    let removed: Vec<u32> = remapping.removed().collect();
+    assert!(!removed.is_empty());
+    assert_eq!(tokenizer.vocab_size() - pruned.vocab_size(), removed.len());
+    assert_eq!(remapping.new_to_old().len(), remapping.old_to_new().len() - removed.len());
+    assert!(remapping.new_to_old().windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Every text of the corpus encodes to the same tokens under new IDs
+    for text in corpus {
+        let ids = tokenizer.encode(text);
+        assert_eq!(remapping.remap(&ids), Some(pruned.encode(text)));
+        assert_eq!(pruned.decode(&pruned.encode(text)), tokenizer.decode(&ids));
+    }
+    for token in ["kitap", "<0xF0>", "kovid", "<kod>", "<|im_start|>", "<eos>", "."] {
+        let old = tokenizer.token_to_id(token).unwrap();
+        assert_eq!(remapping.get(old), pruned.token_to_id(token), "{}", token);
+        assert_eq!(remapping.new_to_old()[pruned.token_to_id(token).unwrap() as usize], old);
+    }
+    for &id in &removed {
+        let token = tokenizer.id_to_token(id).unwrap();
+        assert_eq!(counts.get(&id), None);
+        assert_eq!(pruned.token_to_id(token), None, "{}", token);
+    }
+
+    // Nothing below a count of zero
+    let (unchanged, remapping) = tokenizer.prune_vocab(&counts, 0).unwrap();
+    assert_eq!(unchanged.vocab_size(), tokenizer.vocab_size());
+    assert_eq!(remapping.removed().count(), 0);
+}
+
+#[test]
+fn test_vocab_diff() {
+    let tokenizer = TurkishTokenizer::new().unwrap();
+    let mut extended = tokenizer.clone();
+    extended.add_tokens(&["<kod>"]);
+    let diff = tokenizer.vocab_info().diff(&extended.vocab_info());
+    assert_eq!(diff.added, vec![("<kod>".to_string(), extended.token_to_id("<kod>").unwrap())]);
+    assert!(diff.removed.is_empty() && diff.changed.is_empty());
+    assert!(diff.is_compatible());
+    assert!(tokenizer.is_compatible_with(&extended));
+    assert!(!extended.is_compatible_with(&tokenizer));
+    assert!(tokenizer.vocab_info().diff(&tokenizer.vocab_info()).is_empty());
+
+    // Pruning drops BPE tokens and moves the byte tokens down
+    let counts = tokenizer.count_token_frequencies(["kitaplarımı okudum"].into_iter());
+    let (pruned, remapping) = tokenizer.prune_vocab(&counts, 1).unwrap();
+    let diff = tokenizer.vocab_info().diff(&pruned.vocab_info());
+    assert!(diff.added.is_empty());
+    assert_eq!(diff.removed.len(), remapping.removed().count());
+    let byte = tokenizer.token_to_id("<0xF0>").unwrap();
+    assert!(diff.changed.iter().any(|changed| changed.token == "<0xF0>" && changed.old_id == byte));
+    assert!(!diff.is_compatible());
+    assert!(!tokenizer.vocab_info().is_compatible_with(&pruned.vocab_info()));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_vocab_diff() {
+    use std::process::Command;
+
+    let dir = std::env::temp_dir().join(format!("turkish-tokenizer-vocab-diff-{}", std::process::id()));
+    let tokenizer = TurkishTokenizer::new().unwrap();
+    let mut extended = tokenizer.clone();
+    extended.add_tokens(&["<kod>"]);
+    tokenizer.save_pretrained(dir.join("old")).unwrap();
+    extended.save_pretrained(dir.join("new")).unwrap();
+
+    let diff = |old: &str, new: &str, flag: &str| {
+        Command::new(env!("CARGO_BIN_EXE_turkish-tokenizer"))
+            .args(["vocab", "diff", flag])
+            .args([dir.join(old), dir.join(new)])
+            .output()
+            .unwrap()
+    };
+    let output = diff("old", "new", "--json");
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["added"], serde_json::json!([["<kod>", extended.token_to_id("<kod>").unwrap()]]));
+    assert_eq!(report["removed"], serde_json::json!([]));
+
+    assert!(diff("old", "new", "--check").status.success());
+    let output = diff("new", "old", "--check");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("0 added, 1 removed, 0 re-IDed; NOT compatible"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
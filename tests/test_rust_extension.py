@@ -0,0 +1,102 @@
+#!/usr/bin/env python3
+"""
+Tests for the Rust extension module, built with `maturin develop`.
+Skipped when the extension (or numpy, for the tensor tests) is not installed.
+"""
+
+import pickle
+import unittest
+
+try:
+    from turkish_tokenizer_rs import TurkishTokenizer
+except ImportError:
+    TurkishTokenizer = None
+
+try:
+    import numpy
+except ImportError:
+    numpy = None
+
+
+@unittest.skipIf(TurkishTokenizer is None, "turkish_tokenizer_rs is not installed")
+class TestPickle(unittest.TestCase):
+    """Pickling goes through to_bytes and from_bytes."""
+
+    def test_round_trip_keeps_added_tokens(self):
+        tokenizer = TurkishTokenizer()
+        tokenizer.add_tokens(["kardiyomiyopati"])
+        tokenizer.add_user_tokens([("yapayzeka", None)])
+        text = "kardiyomiyopati ve yapayzeka üzerine konuştuk."
+        added = [tokenizer.get_vocab()[token] for token in ("kardiyomiyopati", "yapayzeka")]
+
+        loaded = pickle.loads(pickle.dumps(tokenizer))
+        self.assertEqual(loaded.encode(text), tokenizer.encode(text))
+        self.assertTrue(all(id in loaded.encode(text) for id in added))
+        self.assertEqual(loaded.decode(loaded.encode(text)), tokenizer.decode(tokenizer.encode(text)))
+        self.assertEqual(loaded.get_vocab(), tokenizer.get_vocab())
+
+    def test_bytes_round_trip(self):
+        tokenizer = TurkishTokenizer()
+        loaded = TurkishTokenizer.from_bytes(tokenizer.to_bytes())
+        self.assertEqual(loaded.encode("Kitaplarımızdan geliyorum"), tokenizer.encode("Kitaplarımızdan geliyorum"))
+
+
+@unittest.skipIf(TurkishTokenizer is None, "turkish_tokenizer_rs is not installed")
+@unittest.skipIf(numpy is None, "numpy is not installed")
+class TestNumpyTensors(unittest.TestCase):
+    """`return_tensors="np"` returns int64 arrays shaped like the lists."""
+
+    def setUp(self):
+        self.tokenizer = TurkishTokenizer()
+
+    def assert_int64(self, array, shape):
+        self.assertIsInstance(array, numpy.ndarray)
+        self.assertEqual(array.dtype, numpy.int64)
+        self.assertEqual(array.shape, shape)
+
+    def test_pair(self):
+        lists = self.tokenizer("Kim geldi?", "Ali dün geldi.", return_offsets_mapping=True, return_length=True)
+        arrays = self.tokenizer(
+            "Kim geldi?", "Ali dün geldi.", return_offsets_mapping=True, return_length=True, return_tensors="np"
+        )
+        width = len(lists["input_ids"])
+
+        for name in ("input_ids", "attention_mask", "token_type_ids"):
+            self.assert_int64(arrays[name], (1, width))
+            self.assertEqual(arrays[name][0].tolist(), lists[name])
+        self.assertIn(1, arrays["token_type_ids"][0].tolist())
+        self.assert_int64(arrays["offset_mapping"], (1, width, 2))
+        self.assertEqual([tuple(pair) for pair in arrays["offset_mapping"][0].tolist()], lists["offset_mapping"])
+        self.assert_int64(arrays["length"], (1,))
+        self.assertEqual(arrays["length"].tolist(), [width])
+
+    def test_padded_batch(self):
+        texts = ["ev", "Merhaba dünya, bugün hava çok güzel"]
+        lists = self.tokenizer(texts, padding=True, return_special_tokens_mask=True)
+        arrays = self.tokenizer(texts, padding=True, return_special_tokens_mask=True, return_tensors="np")
+        width = len(lists["input_ids"][1])
+
+        for name in ("input_ids", "attention_mask", "special_tokens_mask"):
+            self.assert_int64(arrays[name], (2, width))
+            self.assertEqual(arrays[name].tolist(), lists[name])
+        self.assertLess(arrays["attention_mask"][0].sum(), width)
+        self.assertEqual(arrays["attention_mask"][1].sum(), width)
+
+    def test_padded_pair_batch(self):
+        arrays = self.tokenizer(
+            ["Kim?", "Ne zaman geldi?"],
+            ["Ali.", "Dün akşam geldi."],
+            padding="max_length",
+            max_length=24,
+            return_tensors="np",
+        )
+        for name in ("input_ids", "attention_mask", "token_type_ids"):
+            self.assert_int64(arrays[name], (2, 24))
+
+    def test_unpadded_batch_of_different_lengths_fails(self):
+        with self.assertRaises(ValueError):
+            self.tokenizer(["ev", "Merhaba dünya"], return_tensors="np")
+
+
+if __name__ == "__main__":
+    unittest.main()